@@ -0,0 +1,98 @@
+//! Process table refresh benchmarks
+//!
+//! Compares a fresh `System::new_all()` per call (the old behaviour) with the
+//! long-lived, incremental `ProcessSampler`, and the owner-only passes the
+//! port scan and the TUI use on top of it.
+//!
+//! Run with: `cargo bench --bench refresh`
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use portr::{port, ProcessSampler};
+use std::hint::black_box;
+use sysinfo::{Pid, System};
+
+fn bench_refresh(c: &mut Criterion) {
+    let mut group = c.benchmark_group("refresh");
+
+    group.bench_function("system_new_all", |b| {
+        b.iter(|| {
+            let mut sys = System::new_all();
+            sys.refresh_all();
+            black_box(sys.processes().len())
+        })
+    });
+
+    let mut sampler = ProcessSampler::new();
+    sampler.refresh();
+    group.bench_function("sampler_incremental", |b| {
+        b.iter(|| {
+            sampler.refresh();
+            black_box(sampler.system().processes().len())
+        })
+    });
+
+    let owners = [Pid::from_u32(std::process::id())];
+    group.bench_function("sampler_owners", |b| {
+        b.iter(|| {
+            sampler.refresh_owners(&owners);
+            black_box(sampler.system().processes().len())
+        })
+    });
+
+    let mut narrow = ProcessSampler::new();
+    group.bench_function("sampler_pids", |b| {
+        b.iter(|| {
+            narrow.refresh_pids(&owners);
+            black_box(narrow.system().processes().len())
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_listening_ports(c: &mut Criterion) {
+    let mut group = c.benchmark_group("listening_ports");
+    group.sample_size(20);
+
+    group.bench_function("fresh_sampler", |b| {
+        b.iter(|| black_box(port::get_listening_ports().map(|p| p.len())))
+    });
+
+    let mut sampler = ProcessSampler::new();
+    group.bench_function("reused_sampler", |b| {
+        b.iter(|| black_box(port::get_listening_ports_with(&mut sampler).map(|p| p.len())))
+    });
+
+    group.finish();
+}
+
+fn bench_process_tree(c: &mut Criterion) {
+    let pid = std::process::id();
+    let mut group = c.benchmark_group("process_tree");
+
+    group.bench_function("separate_scans", |b| {
+        b.iter(|| {
+            black_box(port::get_process_tree(pid));
+            black_box(port::get_child_processes(pid));
+        })
+    });
+
+    let mut sampler = ProcessSampler::new();
+    sampler.refresh();
+    group.bench_function("shared_snapshot", |b| {
+        b.iter(|| {
+            black_box(port::get_process_tree_with(&sampler, pid));
+            black_box(port::get_child_processes_with(&sampler, pid));
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_refresh,
+    bench_listening_ports,
+    bench_process_tree
+);
+criterion_main!(benches);
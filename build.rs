@@ -2,7 +2,7 @@
 //!
 //! Run `cargo build` and find man pages in `target/man/`
 
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::{Args, CommandFactory, Parser, Subcommand};
 use clap_mangen::Man;
 use std::env;
 use std::fs;
@@ -24,8 +24,18 @@ use std::path::PathBuf;
   portr 3000 --dry-run   Show what would be killed
   portr 3000-3010        Scan port range
   portr --tcp            Show only TCP ports
+  portr --user me        Show only your own ports
+  portr --where 'port:3000-3999 mem>200'
+                         Filter with a query
+  portr --tunnels        Show only SSH tunnels
+  portr --sample 500ms   Sample CPU usage over 500ms
+  portr --columns port,proc,mem,cmd --sort mem:desc
+                         Pick columns and sort order
   portr --csv            Export as CSV
   portr --md             Export as Markdown
+  portr docker ls        List container port mappings
+  portr 8080 -k --docker-action pause
+                         Pause the container on 8080 instead of stopping it
   portr completions bash Generate shell completions
 
 🐸 LazyFrog | kindware.dev")]
@@ -62,24 +72,70 @@ struct Cli {
     #[arg(long)]
     udp: bool,
 
+    /// Show only SSH tunnels (ssh -L/-R/-D listeners)
+    #[arg(long)]
+    tunnels: bool,
+
+    /// Show only ports owned by this user ("me" for the current user)
+    #[arg(long, value_name = "USER")]
+    user: Option<String>,
+
+    /// Show only ports matching a query (e.g. "port:3000-3999 proc:node !docker")
+    #[arg(long = "where", value_name = "QUERY")]
+    filter: Option<String>,
+
+    /// Verbose output with extra details
+    #[arg(short, long)]
+    verbose: bool,
+
+    #[command(flatten)]
+    format: FormatArgs,
+
+    #[command(flatten)]
+    table: TableArgs,
+
+    /// Sample CPU usage over this interval before reporting (e.g., 200ms, 1s)
+    #[arg(long, value_name = "DURATION")]
+    sample: Option<String>,
+
+    #[command(flatten)]
+    docker: DockerArgs,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Args)]
+struct FormatArgs {
     /// Output as JSON
     #[arg(long)]
     json: bool,
-
     /// Output as CSV
     #[arg(long)]
     csv: bool,
-
     /// Output as Markdown
     #[arg(long)]
     md: bool,
+}
 
-    /// Verbose output with extra details
-    #[arg(short, long)]
-    verbose: bool,
+#[derive(Args)]
+struct TableArgs {
+    /// Columns to show and export, comma separated (e.g., port,proc,mem,cmd)
+    #[arg(long, value_name = "COLUMNS")]
+    columns: Option<String>,
+    /// Sort by a column, optionally with :asc or :desc (e.g., mem:desc)
+    #[arg(long, value_name = "COLUMN")]
+    sort: Option<String>,
+}
 
-    #[command(subcommand)]
-    command: Option<Commands>,
+#[derive(Args)]
+struct DockerArgs {
+    /// What to do with a Docker container when killing its port (stop, restart, pause, rm)
+    #[arg(long, value_name = "ACTION", default_value = "stop")]
+    docker_action: String,
+    /// Seconds to wait for a container to stop gracefully [default: from config, 10]
+    #[arg(long, value_name = "SECS")]
+    stop_timeout: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -92,9 +148,34 @@ enum Commands {
         /// Show only UDP connections
         #[arg(long)]
         udp: bool,
+        /// Show only SSH tunnels (ssh -L/-R/-D listeners)
+        #[arg(long)]
+        tunnels: bool,
+        /// Show only ports owned by this user ("me" for the current user)
+        #[arg(long, value_name = "USER")]
+        user: Option<String>,
+        /// Show only ports matching a query (e.g. "port:3000-3999 proc:node !docker")
+        #[arg(long = "where", value_name = "QUERY")]
+        filter: Option<String>,
+        /// Sample CPU usage over this interval before reporting (e.g., 200ms, 1s)
+        #[arg(long, value_name = "DURATION")]
+        sample: Option<String>,
+        #[command(flatten)]
+        format: FormatArgs,
+        #[command(flatten)]
+        table: TableArgs,
     },
     /// Interactive TUI mode with keyboard navigation
-    Interactive,
+    Interactive {
+        #[command(flatten)]
+        table: TableArgs,
+    },
+    /// Full-screen TUI dashboard (beautiful real-time view)
+    #[command(alias = "tui")]
+    Dashboard {
+        #[command(flatten)]
+        table: TableArgs,
+    },
     /// Watch ports in real-time
     Watch {
         /// Port to watch (optional, watches all if not specified)
@@ -103,17 +184,48 @@ enum Commands {
         /// Refresh interval in seconds
         #[arg(short, long, default_value = "2")]
         interval: u64,
+        /// Show only ports owned by this user ("me" for the current user)
+        #[arg(long, value_name = "USER")]
+        user: Option<String>,
+        /// Print one JSON line per change instead of redrawing a table
+        #[arg(long)]
+        events: bool,
+        #[command(flatten)]
+        table: TableArgs,
+    },
+    /// Show recorded port history (enable with [history] in the config)
+    History {
+        /// Only show events for this port
+        #[arg(value_name = "PORT")]
+        port: Option<u16>,
+        /// Only show events newer than this (e.g., 30m, 2h, 7d)
+        #[arg(long, value_name = "DURATION")]
+        since: Option<String>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
     /// Find which process is using a port
     Find {
         /// Port number to find
         #[arg(value_name = "PORT")]
         port: u16,
+        /// Verbose output with extra details
+        #[arg(short, long)]
+        verbose: bool,
+        /// Sample CPU usage over this interval before reporting (e.g., 200ms, 1s)
+        #[arg(long, value_name = "DURATION")]
+        sample: Option<String>,
+        #[command(flatten)]
+        format: FormatArgs,
     },
     /// Kill process on a specific port
     Kill {
         /// Port numbers to kill
-        #[arg(value_name = "PORTS", required = true)]
+        #[arg(
+            value_name = "PORTS",
+            required_unless_present_any = ["compose_project", "compose_service"]
+        )]
         ports: Vec<u16>,
         /// Force kill without confirmation
         #[arg(short, long)]
@@ -124,6 +236,14 @@ enum Commands {
         /// Use SIGKILL instead of SIGTERM (Unix only)
         #[arg(long)]
         sigkill: bool,
+        /// Act on every published container of this docker compose project
+        #[arg(long, value_name = "PROJECT")]
+        compose_project: Option<String>,
+        /// Act on the containers of this docker compose service
+        #[arg(long, value_name = "SERVICE")]
+        compose_service: Option<String>,
+        #[command(flatten)]
+        docker: DockerArgs,
     },
     /// Generate shell completions
     Completions {
@@ -135,6 +255,100 @@ enum Commands {
         #[command(subcommand)]
         action: ConfigAction,
     },
+    /// Docker containers and their published ports (requires --features docker)
+    Docker {
+        /// Show which Docker endpoint portr talks to
+        #[arg(short, long, global = true)]
+        verbose: bool,
+        #[command(subcommand)]
+        action: DockerCommand,
+    },
+    /// Background daemon that records port events and serves snapshots
+    Daemon {
+        #[command(subcommand)]
+        action: Option<DaemonAction>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DaemonAction {
+    /// Run the daemon in the foreground (the default)
+    Run {
+        /// Scan interval in seconds
+        #[arg(short, long, default_value = "2")]
+        interval: String,
+    },
+    /// Show whether a daemon is running
+    Status {
+        /// Output the snapshot as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Stream port events from the daemon as JSON lines
+    Events,
+}
+
+#[derive(Subcommand)]
+enum DockerCommand {
+    /// List running containers with their port mappings
+    Ls {
+        #[command(flatten)]
+        format: FormatArgs,
+    },
+    /// Stop a container by published port or name
+    Stop {
+        /// Host port the container publishes, or its name/ID
+        #[arg(value_name = "PORT|NAME")]
+        target: String,
+        /// Skip confirmation
+        #[arg(short, long)]
+        force: bool,
+        /// Dry run - show what would be stopped
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+        /// Seconds to wait for a graceful stop [default: from config, 10]
+        #[arg(long, value_name = "SECS")]
+        stop_timeout: Option<String>,
+    },
+    /// Restart a container by published port or name
+    Restart {
+        /// Host port the container publishes, or its name/ID
+        #[arg(value_name = "PORT|NAME")]
+        target: String,
+        /// Skip confirmation
+        #[arg(short, long)]
+        force: bool,
+        /// Dry run - show what would be restarted
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+        /// Seconds to wait for a graceful stop [default: from config, 10]
+        #[arg(long, value_name = "SECS")]
+        stop_timeout: Option<String>,
+    },
+    /// Pause a container by published port or name
+    Pause {
+        /// Host port the container publishes, or its name/ID
+        #[arg(value_name = "PORT|NAME")]
+        target: String,
+        /// Skip confirmation
+        #[arg(short, long)]
+        force: bool,
+        /// Dry run - show what would be paused
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+    /// Force-remove a container by published port or name
+    Rm {
+        /// Host port the container publishes, or its name/ID
+        #[arg(value_name = "PORT|NAME")]
+        target: String,
+        /// Skip confirmation
+        #[arg(short, long)]
+        force: bool,
+        /// Dry run - show what would be removed
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -0,0 +1,464 @@
+//! Selectable columns and sort keys for port tables and exports
+//!
+//! `--columns` and `--sort` (or `[table]` in the config) pick which
+//! [`Column`]s the table, the exporters and the dashboard show, and the
+//! [`SortKey`] they are ordered by. Without a choice every output keeps its
+//! own default layout.
+
+use crate::error::PortrError;
+use crate::port::PortInfo;
+use crate::services;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A column of port output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    Port,
+    Proto,
+    Pid,
+    Proc,
+    User,
+    Addr,
+    State,
+    Mem,
+    Cpu,
+    Uptime,
+    Service,
+    Container,
+    /// Command line; only shown or exported when asked for, since
+    /// arguments can carry secrets
+    Cmd,
+}
+
+/// Columns of `print_port_table` when none are chosen
+pub const TABLE_COLUMNS: &[Column] = &[
+    Column::Port,
+    Column::Proto,
+    Column::Pid,
+    Column::Proc,
+    Column::User,
+    Column::Mem,
+    Column::Uptime,
+];
+
+impl Column {
+    /// All columns, in the order `--help` lists them
+    pub const ALL: [Column; 13] = [
+        Column::Port,
+        Column::Proto,
+        Column::Pid,
+        Column::Proc,
+        Column::User,
+        Column::Addr,
+        Column::State,
+        Column::Mem,
+        Column::Cpu,
+        Column::Uptime,
+        Column::Service,
+        Column::Container,
+        Column::Cmd,
+    ];
+
+    /// Name used by `--columns`, `--sort` and the config
+    pub fn name(&self) -> &'static str {
+        match self {
+            Column::Port => "port",
+            Column::Proto => "proto",
+            Column::Pid => "pid",
+            Column::Proc => "proc",
+            Column::User => "user",
+            Column::Addr => "addr",
+            Column::State => "state",
+            Column::Mem => "mem",
+            Column::Cpu => "cpu",
+            Column::Uptime => "uptime",
+            Column::Service => "service",
+            Column::Container => "container",
+            Column::Cmd => "cmd",
+        }
+    }
+
+    /// Parse a column name or one of its long forms ("process", "memory", ...)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        let alias = match name.as_str() {
+            "protocol" => "proto",
+            "process" => "proc",
+            "address" => "addr",
+            "memory" => "mem",
+            "svc" => "service",
+            "command" => "cmd",
+            other => other,
+        };
+        Column::ALL.iter().copied().find(|c| c.name() == alias)
+    }
+
+    /// Table header
+    pub fn header(&self) -> &'static str {
+        match self {
+            Column::Port => "PORT",
+            Column::Proto => "PROTO",
+            Column::Pid => "PID",
+            Column::Proc => "PROCESS",
+            Column::User => "USER",
+            Column::Addr => "ADDRESS",
+            Column::State => "STATE",
+            Column::Mem => "MEMORY",
+            Column::Cpu => "CPU",
+            Column::Uptime => "UPTIME",
+            Column::Service => "SERVICE",
+            Column::Container => "CONTAINER",
+            Column::Cmd => "COMMAND",
+        }
+    }
+
+    /// Markdown header
+    pub fn title(&self) -> &'static str {
+        match self {
+            Column::Port => "Port",
+            Column::Proto => "Protocol",
+            Column::Pid => "PID",
+            Column::Proc => "Process",
+            Column::User => "User",
+            Column::Addr => "Address",
+            Column::State => "State",
+            Column::Mem => "Memory",
+            Column::Cpu => "CPU",
+            Column::Uptime => "Uptime",
+            Column::Service => "Service",
+            Column::Container => "Container",
+            Column::Cmd => "Command",
+        }
+    }
+
+    /// CSV header and JSON key, matching the `PortInfo` field names
+    pub fn field(&self) -> &'static str {
+        match self {
+            Column::Port => "port",
+            Column::Proto => "protocol",
+            Column::Pid => "pid",
+            Column::Proc => "process",
+            Column::User => "user",
+            Column::Addr => "local_address",
+            Column::State => "state",
+            Column::Mem => "memory_mb",
+            Column::Cpu => "cpu_percent",
+            Column::Uptime => "uptime_secs",
+            Column::Service => "service",
+            Column::Container => "container",
+            Column::Cmd => "command",
+        }
+    }
+
+    /// Text shown in tables and Markdown
+    pub fn display(&self, p: &PortInfo) -> String {
+        match self {
+            Column::Proc => p.display_name().into_owned(),
+            Column::User => p.user.clone().unwrap_or_else(|| "-".to_string()),
+            Column::Mem => format!("{:.1} MB", p.memory_mb),
+            Column::Cpu => format!("{:.1}%", p.cpu_percent),
+            Column::Uptime => p.uptime_display(),
+            _ => match self.raw(p) {
+                raw if raw.is_empty() => "-".to_string(),
+                raw => raw,
+            },
+        }
+    }
+
+    /// Unformatted value for CSV; empty when unknown
+    pub fn raw(&self, p: &PortInfo) -> String {
+        match self {
+            Column::Port => p.port.to_string(),
+            Column::Proto => p.protocol.clone(),
+            Column::Pid => p.pid.to_string(),
+            Column::Proc => p.process_name.clone(),
+            Column::User => p.user.clone().unwrap_or_default(),
+            Column::Addr => p.local_address.clone(),
+            Column::State => p.state.clone(),
+            Column::Mem => format!("{:.1}", p.memory_mb),
+            Column::Cpu => format!("{:.1}", p.cpu_percent),
+            Column::Uptime => p.uptime_secs.to_string(),
+            Column::Service => services::short_name(p.port).unwrap_or_default().to_string(),
+            Column::Container => p
+                .container
+                .as_ref()
+                .map(|c| c.name.clone())
+                .unwrap_or_default(),
+            Column::Cmd => p.command.join(" "),
+        }
+    }
+
+    /// Value for JSON: numbers stay numbers, unknown values are null
+    pub fn json(&self, p: &PortInfo) -> Value {
+        match self {
+            Column::Port => p.port.into(),
+            Column::Pid => p.pid.into(),
+            Column::Mem => ((p.memory_mb * 10.0).round() / 10.0).into(),
+            Column::Cpu => ((f64::from(p.cpu_percent) * 10.0).round() / 10.0).into(),
+            Column::Uptime => p.uptime_secs.into(),
+            _ => match self.raw(p) {
+                raw if raw.is_empty() => Value::Null,
+                raw => raw.into(),
+            },
+        }
+    }
+
+    /// Compare two ports by this column, ascending; numbers compare as
+    /// numbers and text case-insensitively
+    pub fn compare(&self, a: &PortInfo, b: &PortInfo) -> Ordering {
+        match self {
+            Column::Port => a.port.cmp(&b.port),
+            Column::Pid => a.pid.cmp(&b.pid),
+            Column::Mem => a.memory_mb.total_cmp(&b.memory_mb),
+            Column::Cpu => a.cpu_percent.total_cmp(&b.cpu_percent),
+            Column::Uptime => a.uptime_secs.cmp(&b.uptime_secs),
+            _ => self
+                .display(a)
+                .to_lowercase()
+                .cmp(&self.display(b).to_lowercase()),
+        }
+    }
+
+    /// Whether sorting by this column starts with the largest value
+    pub fn descending_by_default(&self) -> bool {
+        matches!(self, Column::Mem | Column::Cpu)
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parse a comma-separated column list such as "port,proc,mem"
+pub fn parse_columns(list: &str) -> Result<Vec<Column>, PortrError> {
+    let mut columns = Vec::new();
+    for name in list.split(',') {
+        let column = Column::from_name(name).ok_or_else(|| {
+            let names: Vec<&str> = Column::ALL.iter().map(Column::name).collect();
+            PortrError::InvalidColumn(format!(
+                "'{}' (expected one of: {})",
+                name.trim(),
+                names.join(", ")
+            ))
+        })?;
+        if columns.contains(&column) {
+            return Err(PortrError::InvalidColumn(format!(
+                "'{}' is listed twice",
+                column
+            )));
+        }
+        columns.push(column);
+    }
+    Ok(columns)
+}
+
+/// Column and direction to sort ports by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    pub column: Column,
+    pub descending: bool,
+}
+
+impl Default for SortKey {
+    fn default() -> Self {
+        Self::new(Column::Port)
+    }
+}
+
+impl SortKey {
+    /// Sort by `column` in its default direction
+    pub fn new(column: Column) -> Self {
+        Self {
+            column,
+            descending: column.descending_by_default(),
+        }
+    }
+
+    /// Parse "column", "column:asc" or "column:desc"
+    pub fn parse(spec: &str) -> Result<Self, PortrError> {
+        let (name, direction) = match spec.split_once(':') {
+            Some((name, direction)) => (name, Some(direction.trim().to_lowercase())),
+            None => (spec, None),
+        };
+        let column = Column::from_name(name)
+            .ok_or_else(|| PortrError::InvalidSort(format!("unknown column '{}'", name.trim())))?;
+        let descending = match direction.as_deref() {
+            None => column.descending_by_default(),
+            Some("asc") => false,
+            Some("desc") => true,
+            Some(other) => {
+                return Err(PortrError::InvalidSort(format!(
+                    "unknown direction '{}' (use asc or desc)",
+                    other
+                )))
+            }
+        };
+        Ok(Self { column, descending })
+    }
+
+    /// The same column, the other way round
+    pub fn reversed(&self) -> Self {
+        Self {
+            descending: !self.descending,
+            ..*self
+        }
+    }
+
+    /// Sort ports in place; ties keep their order
+    pub fn sort(&self, ports: &mut [PortInfo]) {
+        ports.sort_by(|a, b| {
+            let order = self.column.compare(a, b);
+            if self.descending {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+    }
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = if self.descending { "desc" } else { "asc" };
+        write!(f, "{}:{}", self.column, direction)
+    }
+}
+
+/// Columns and sort order chosen on the command line or in the config
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableOptions {
+    /// Chosen columns; `None` keeps each output's default layout
+    pub columns: Option<Vec<Column>>,
+    /// Chosen order; `None` keeps port order
+    pub sort: Option<SortKey>,
+}
+
+impl TableOptions {
+    /// Combine command-line choices with the `[table]` config section,
+    /// the command line winning
+    ///
+    /// A bad config entry falls back to the default and is returned as an
+    /// error to warn about, so a typo in the config can't stop the commands
+    /// that show and fix it.
+    pub fn resolve(
+        columns: Option<Vec<Column>>,
+        sort: Option<SortKey>,
+        config: &crate::config::TableSettings,
+    ) -> (Self, Vec<PortrError>) {
+        let mut errors = Vec::new();
+        let columns = columns.or_else(|| from_config(&config.columns, parse_columns, &mut errors));
+        let sort = sort.or_else(|| from_config(&config.sort, SortKey::parse, &mut errors));
+        (Self { columns, sort }, errors)
+    }
+
+    /// Sort ports by the chosen key, if any
+    pub fn sort(&self, ports: &mut [PortInfo]) {
+        if let Some(sort) = self.sort {
+            sort.sort(ports);
+        }
+    }
+}
+
+/// Parse a `[table]` value, collecting the error instead of failing
+fn from_config<T>(
+    value: &Option<String>,
+    parse: fn(&str) -> Result<T, PortrError>,
+    errors: &mut Vec<PortrError>,
+) -> Option<T> {
+    let value = value.as_deref()?;
+    parse(value)
+        .map_err(|e| errors.push(PortrError::ConfigError(format!("[table] {}", e))))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TableSettings;
+
+    fn port(port: u16, process: &str, memory_mb: f64) -> PortInfo {
+        PortInfo {
+            process_name: process.to_string(),
+            local_address: "0.0.0.0".to_string(),
+            memory_mb,
+            uptime_secs: 90,
+            command: vec!["node".to_string(), "server.js".to_string()],
+            ..PortInfo::test_port(port, 100 + u32::from(port % 100))
+        }
+    }
+
+    #[test]
+    fn test_parse_columns() {
+        assert_eq!(
+            parse_columns("port, Process,memory,cmd").unwrap(),
+            vec![Column::Port, Column::Proc, Column::Mem, Column::Cmd]
+        );
+        let error = parse_columns("port,colour").unwrap_err().to_string();
+        assert!(error.contains("'colour' (expected one of: port, proto"));
+        assert!(parse_columns("port,port")
+            .unwrap_err()
+            .to_string()
+            .contains("listed twice"));
+        assert!(parse_columns("").is_err());
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let mut ports = vec![
+            port(8080, "nginx", 20.0),
+            port(3000, "Node", 300.0),
+            port(5432, "postgres", 80.0),
+        ];
+
+        // Memory starts with the largest, text sorts case-insensitively
+        SortKey::parse("mem").unwrap().sort(&mut ports);
+        let order: Vec<u16> = ports.iter().map(|p| p.port).collect();
+        assert_eq!(order, vec![3000, 5432, 8080]);
+
+        SortKey::parse("proc:desc").unwrap().sort(&mut ports);
+        let order: Vec<u16> = ports.iter().map(|p| p.port).collect();
+        assert_eq!(order, vec![5432, 3000, 8080]);
+
+        assert_eq!(SortKey::parse("port:asc").unwrap(), SortKey::default());
+        assert_eq!(SortKey::parse("mem").unwrap().to_string(), "mem:desc");
+        assert!(SortKey::parse("mem:up")
+            .unwrap_err()
+            .to_string()
+            .contains("unknown direction 'up'"));
+    }
+
+    #[test]
+    fn test_table_options_prefer_command_line() {
+        let config = TableSettings {
+            columns: Some("port,cmd".to_string()),
+            sort: Some("pid:desc".to_string()),
+        };
+        let (options, errors) =
+            TableOptions::resolve(None, Some(SortKey::new(Column::Mem)), &config);
+        assert!(errors.is_empty());
+        assert_eq!(options.columns, Some(vec![Column::Port, Column::Cmd]));
+        assert_eq!(options.sort, Some(SortKey::new(Column::Mem)));
+
+        // A bad entry falls back to the default; the good one still applies
+        let broken = TableSettings {
+            columns: Some("port,nope".to_string()),
+            sort: Some("mem".to_string()),
+        };
+        let (options, errors) = TableOptions::resolve(None, None, &broken);
+        assert_eq!(options.columns, None);
+        assert_eq!(options.sort, Some(SortKey::new(Column::Mem)));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0]
+            .to_string()
+            .starts_with("config error: [table] invalid column: 'nope'"));
+
+        let p = port(45678, "node", 1.0);
+        assert_eq!(Column::Cmd.display(&p), "node server.js");
+        assert_eq!(Column::Service.display(&p), "-");
+        assert_eq!(Column::Container.json(&p), Value::Null);
+    }
+}
@@ -15,6 +15,14 @@ pub struct Config {
     pub aliases: HashMap<String, u16>,
     /// Theme customization
     pub theme: Theme,
+    /// Docker container handling
+    pub docker: DockerSettings,
+    /// Port history recording
+    pub history: HistorySettings,
+    /// Dashboard keybindings as (action, keys), in file order
+    pub keys: Vec<(String, String)>,
+    /// Port table columns and sort order
+    pub table: TableSettings,
 }
 
 /// Default behavior settings
@@ -41,6 +49,54 @@ impl Default for Defaults {
     }
 }
 
+/// Seconds Docker waits after SIGTERM before killing a stopping container
+pub const DEFAULT_STOP_TIMEOUT: u32 = 10;
+
+/// Docker container handling settings
+#[derive(Debug, Clone)]
+pub struct DockerSettings {
+    /// Seconds to wait for a graceful stop before Docker kills the container
+    pub stop_timeout: u32,
+}
+
+impl Default for DockerSettings {
+    fn default() -> Self {
+        Self {
+            stop_timeout: DEFAULT_STOP_TIMEOUT,
+        }
+    }
+}
+
+/// Port history settings
+#[derive(Debug, Clone)]
+pub struct HistorySettings {
+    /// Record bind/unbind events from `portr watch` and the dashboard
+    pub enabled: bool,
+    /// Drop events older than this many days
+    pub retention_days: u32,
+    /// Keep at most this many events
+    pub max_events: usize,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_days: 30,
+            max_events: 100_000,
+        }
+    }
+}
+
+/// Port table settings, as written in the config; checked when used
+#[derive(Debug, Clone, Default)]
+pub struct TableSettings {
+    /// Comma-separated columns for tables, exports and the dashboard
+    pub columns: Option<String>,
+    /// Sort key such as "mem:desc"
+    pub sort: Option<String>,
+}
+
 /// Theme customization
 #[derive(Debug, Clone)]
 pub struct Theme {
@@ -81,6 +137,44 @@ pub fn config_path() -> Option<PathBuf> {
     }
 }
 
+/// Get the directory portr keeps its data (history) in
+///
+/// `PORTR_DATA_DIR` overrides the platform default.
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("PORTR_DATA_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+
+    #[cfg(windows)]
+    {
+        std::env::var("LOCALAPPDATA")
+            .ok()
+            .map(|p| PathBuf::from(p).join("portr"))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        std::env::var("HOME").ok().map(|p| {
+            PathBuf::from(p)
+                .join("Library")
+                .join("Application Support")
+                .join("portr")
+        })
+    }
+
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var("HOME")
+                    .ok()
+                    .map(|p| PathBuf::from(p).join(".local").join("share"))
+            })
+            .map(|p| p.join("portr"))
+    }
+}
+
 /// Load configuration from the config file
 pub fn load_config() -> Config {
     let path = match config_path() {
@@ -137,6 +231,31 @@ fn parse_config(content: &str) -> Config {
                         config.aliases.insert(key.to_string(), port);
                     }
                 }
+                "docker" => {
+                    if let ("stop_timeout", Ok(secs)) = (key, value.parse()) {
+                        config.docker.stop_timeout = secs;
+                    }
+                }
+                "history" => match key {
+                    "enabled" => config.history.enabled = value == "true",
+                    "retention_days" => {
+                        if let Ok(days) = value.parse() {
+                            config.history.retention_days = days;
+                        }
+                    }
+                    "max_events" => {
+                        if let Ok(max) = value.parse() {
+                            config.history.max_events = max;
+                        }
+                    }
+                    _ => {}
+                },
+                "keys" => config.keys.push((key.to_string(), value.to_string())),
+                "table" => match key {
+                    "columns" => config.table.columns = Some(value.to_string()),
+                    "sort" => config.table.sort = Some(value.to_string()),
+                    _ => {}
+                },
                 "theme" => match key {
                     "banner_color" => config.theme.banner_color = value.to_string(),
                     "success_color" => config.theme.success_color = value.to_string(),
@@ -203,6 +322,32 @@ mongo = 27017
 ollama = 11434
 docker = 2375
 
+[docker]
+# Seconds to wait for a graceful stop before the container is killed
+stop_timeout = 10
+
+[history]
+# Record port bind/unbind events from `portr watch` and the dashboard
+# (view them with `portr history`)
+enabled = false
+retention_days = 30
+max_events = 100000
+
+[keys]
+# Dashboard keybindings: action = "keys" (space separated, e.g. "r F5",
+# "ctrl+k"). A listed action loses its default keys; a key bound to two
+# actions is an error. Press ? in the dashboard to see the active keys.
+# kill = "K"
+# toggle_mark = "space"
+# refresh = "r F5"
+
+[table]
+# Columns for port tables, exports and the dashboard, from: port, proto,
+# pid, proc, user, addr, state, mem, cpu, uptime, service, container, cmd
+# columns = "port,proto,pid,proc,user,mem,uptime"
+# Sort key: a column, optionally with :asc or :desc
+# sort = "mem:desc"
+
 [theme]
 # Color customization
 banner_color = "cyan"
@@ -274,6 +419,36 @@ color = "never"
         assert_eq!(config.defaults.color, "never");
     }
 
+    #[test]
+    fn test_parse_config_keys() {
+        let content = r#"
+[keys]
+kill = "x"
+refresh = "r F5"
+"#;
+        let config = parse_config(content);
+        assert_eq!(
+            config.keys,
+            vec![
+                ("kill".to_string(), "x".to_string()),
+                ("refresh".to_string(), "r F5".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_config_table() {
+        let content = r#"
+[table]
+columns = "port,proc,mem,cmd"
+sort = "mem:desc"
+"#;
+        let config = parse_config(content);
+        assert_eq!(config.table.columns.as_deref(), Some("port,proc,mem,cmd"));
+        assert_eq!(config.table.sort.as_deref(), Some("mem:desc"));
+        assert!(parse_config("").table.columns.is_none());
+    }
+
     #[test]
     fn test_resolve_alias() {
         let mut config = Config::default();
@@ -304,4 +479,20 @@ color = "never"
             assert!(p.to_string_lossy().contains("config.toml"));
         }
     }
+
+    #[test]
+    fn test_parse_config_docker_stop_timeout() {
+        let config = parse_config("[docker]\nstop_timeout = 30\n");
+        assert_eq!(config.docker.stop_timeout, 30);
+        assert_eq!(Config::default().docker.stop_timeout, 10);
+    }
+
+    #[test]
+    fn test_parse_config_history() {
+        let config = parse_config("[history]\nenabled = true\nretention_days = 7\n");
+        assert!(config.history.enabled);
+        assert_eq!(config.history.retention_days, 7);
+        assert_eq!(config.history.max_events, 100_000);
+        assert!(!Config::default().history.enabled);
+    }
 }
@@ -0,0 +1,444 @@
+//! Background daemon
+//!
+//! `portr daemon` polls listening ports, appends bind/unbind/owner-change
+//! events to the history log and serves the latest snapshot on a Unix
+//! socket in the data dir. The protocol is one request line per connection:
+//!
+//! - `snapshot` — the daemon answers with one [`DaemonSnapshot`] JSON line
+//! - `events` — the connection stays open and receives one
+//!   [`HistoryEvent`](crate::history::HistoryEvent) JSON line per change
+
+use crate::config;
+use crate::error::PortrError;
+use crate::history::HistoryEvent;
+use crate::port::PortInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Socket file name inside the data dir
+pub const SOCKET_FILE: &str = "portr.sock";
+
+/// State served by the daemon
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DaemonSnapshot {
+    /// PID of the daemon process
+    pub pid: u32,
+    /// Unix time the daemon started
+    pub started: i64,
+    /// Unix time of the last scan
+    pub updated: i64,
+    /// Seconds between scans
+    pub interval_secs: u64,
+    pub ports: Vec<PortInfo>,
+    /// Command line per owner PID; `PortInfo` keeps them out of its own
+    /// JSON so exports never carry them
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub commands: BTreeMap<u32, Vec<String>>,
+}
+
+impl DaemonSnapshot {
+    /// Snapshot of a scan finished at `updated`
+    pub fn new(
+        pid: u32,
+        started: i64,
+        updated: i64,
+        interval_secs: u64,
+        ports: Vec<PortInfo>,
+    ) -> Self {
+        let commands = ports
+            .iter()
+            .filter(|p| !p.command.is_empty())
+            .map(|p| (p.pid, p.command.clone()))
+            .collect();
+        Self {
+            pid,
+            started,
+            updated,
+            interval_secs,
+            ports,
+            commands,
+        }
+    }
+
+    /// Whether the last scan is recent enough to stand in for a new one
+    ///
+    /// A daemon that is stuck or suspended keeps answering with its old
+    /// scan; one interval plus a second of slack is allowed.
+    pub fn is_fresh(&self, now: i64) -> bool {
+        let max_age = i64::try_from(self.interval_secs)
+            .unwrap_or(i64::MAX)
+            .saturating_add(1);
+        now.saturating_sub(self.updated) <= max_age
+    }
+
+    /// The ports with their command lines put back
+    pub fn into_ports(self) -> Vec<PortInfo> {
+        let commands = self.commands;
+        self.ports
+            .into_iter()
+            .map(|mut port| {
+                if let Some(command) = commands.get(&port.pid) {
+                    port.command = command.clone();
+                }
+                port
+            })
+            .collect()
+    }
+}
+
+/// Path of the daemon's socket
+pub fn socket_path() -> Option<PathBuf> {
+    config::data_dir().map(|dir| dir.join(SOCKET_FILE))
+}
+
+/// Fetch the current snapshot from a running daemon
+///
+/// `None` when no daemon answers, so callers can fall back to scanning.
+pub fn query_daemon() -> Option<DaemonSnapshot> {
+    #[cfg(unix)]
+    {
+        unix::request_snapshot(&socket_path()?).ok()
+    }
+
+    #[cfg(not(unix))]
+    {
+        None
+    }
+}
+
+/// Ports from a running daemon, if its last scan is fresh
+///
+/// `None` when no daemon answers or its snapshot is stale, so callers scan
+/// themselves.
+pub fn fresh_ports() -> Option<Vec<PortInfo>> {
+    query_daemon()
+        .filter(|snapshot| snapshot.is_fresh(crate::history::unix_now()))
+        .map(DaemonSnapshot::into_ports)
+}
+
+/// Whether a daemon is answering on the socket
+pub fn is_daemon_running() -> bool {
+    query_daemon().is_some()
+}
+
+#[cfg(unix)]
+pub use unix::{request_snapshot, run_daemon, subscribe_events, DaemonServer};
+
+#[cfg(not(unix))]
+/// Run the daemon (Unix only)
+pub fn run_daemon(_interval: std::time::Duration) -> Result<(), PortrError> {
+    Err(PortrError::SystemError(
+        "daemon mode needs Unix domain sockets".to_string(),
+    ))
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::*;
+    use crate::history::{self, HistoryRecorder};
+    use crate::port;
+    use crate::sampler::ProcessSampler;
+    use colored::Colorize;
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// How long clients wait for the daemon to answer
+    const CLIENT_TIMEOUT: Duration = Duration::from_secs(2);
+
+    #[derive(Default)]
+    struct Shared {
+        /// `None` until the first scan is published
+        snapshot: Option<DaemonSnapshot>,
+        subscribers: Vec<UnixStream>,
+    }
+
+    /// Socket server holding the latest snapshot and event subscribers
+    pub struct DaemonServer {
+        path: PathBuf,
+        shared: Arc<Mutex<Shared>>,
+    }
+
+    impl DaemonServer {
+        /// Bind the socket and start accepting clients on a background thread
+        ///
+        /// A socket left behind by a daemon that died is replaced; a live
+        /// one is an error.
+        pub fn bind(path: &Path) -> Result<Self, PortrError> {
+            if path.exists() {
+                if UnixStream::connect(path).is_ok() {
+                    return Err(PortrError::SystemError(format!(
+                        "a daemon is already running on {}",
+                        path.display()
+                    )));
+                }
+                std::fs::remove_file(path)?;
+            }
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+
+            let listener = UnixListener::bind(path)?;
+            let shared = Arc::new(Mutex::new(Shared::default()));
+
+            let accept_shared = Arc::clone(&shared);
+            std::thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let shared = Arc::clone(&accept_shared);
+                    // A misbehaving client only loses its own connection
+                    std::thread::spawn(move || handle_client(stream, &shared));
+                }
+            });
+
+            Ok(Self {
+                path: path.to_path_buf(),
+                shared,
+            })
+        }
+
+        /// Replace the snapshot and push `events` to subscribers
+        pub fn publish(&self, snapshot: DaemonSnapshot, events: &[HistoryEvent]) {
+            let mut shared = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+            shared.snapshot = Some(snapshot);
+
+            if events.is_empty() {
+                return;
+            }
+            let mut lines = String::new();
+            for event in events {
+                if let Ok(line) = serde_json::to_string(event) {
+                    lines.push_str(&line);
+                    lines.push('\n');
+                }
+            }
+            // Drop subscribers that went away
+            shared
+                .subscribers
+                .retain_mut(|s| s.write_all(lines.as_bytes()).is_ok());
+        }
+    }
+
+    impl Drop for DaemonServer {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    fn handle_client(stream: UnixStream, shared: &Mutex<Shared>) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+        let mut request = String::new();
+        BufReader::new(&stream).read_line(&mut request)?;
+
+        let mut stream = stream;
+        match request.trim() {
+            "snapshot" => {
+                let snapshot = {
+                    let shared = shared.lock().unwrap_or_else(|e| e.into_inner());
+                    shared.snapshot.as_ref().map(serde_json::to_string)
+                };
+                // Closing without an answer makes clients scan themselves
+                match snapshot {
+                    Some(json) => writeln!(stream, "{}", json?),
+                    None => Ok(()),
+                }
+            }
+            "events" => {
+                // A stalled subscriber must not hold up the polling loop
+                stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+                shared
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .subscribers
+                    .push(stream);
+                Ok(())
+            }
+            other => writeln!(
+                stream,
+                "{}",
+                serde_json::json!({ "error": format!("unknown request '{}'", other) })
+            ),
+        }
+    }
+
+    /// Ask the daemon at `path` for its snapshot
+    pub fn request_snapshot(path: &Path) -> Result<DaemonSnapshot, PortrError> {
+        let mut stream = UnixStream::connect(path)?;
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+        stream.write_all(b"snapshot\n")?;
+
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line)?;
+        Ok(serde_json::from_str(&line)?)
+    }
+
+    /// Subscribe to the daemon at `path`, calling `on_event` per change
+    ///
+    /// Returns when the daemon closes the connection or `on_event` returns
+    /// false.
+    pub fn subscribe_events(
+        path: &Path,
+        mut on_event: impl FnMut(HistoryEvent) -> bool,
+    ) -> Result<(), PortrError> {
+        let mut stream = UnixStream::connect(path)?;
+        stream.write_all(b"events\n")?;
+
+        for line in BufReader::new(stream).lines() {
+            if let Ok(event) = serde_json::from_str(&line?) {
+                if !on_event(event) {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the daemon in the foreground until killed
+    pub fn run_daemon(interval: Duration) -> Result<(), PortrError> {
+        let path = socket_path()
+            .ok_or_else(|| PortrError::IoError("could not determine data directory".into()))?;
+        let history_path = history::history_path()
+            .ok_or_else(|| PortrError::IoError("could not determine data directory".into()))?;
+
+        let server = DaemonServer::bind(&path)?;
+        let mut recorder = HistoryRecorder::open(history_path, &config::load_config().history)?;
+        let mut sampler = ProcessSampler::new();
+        let started = history::unix_now();
+
+        // A failed scan or history write skips one round instead of stopping
+        // the daemon; each distinct error is reported once
+        let mut last_error = None;
+        let mut warn = |what: &str, e: PortrError| {
+            let message = format!("{}: {}", what, e);
+            if last_error.as_ref() != Some(&message) {
+                eprintln!("{} {}", "warning:".yellow().bold(), message);
+                last_error = Some(message);
+            }
+        };
+
+        loop {
+            let ports = match port::get_listening_ports_with(&mut sampler) {
+                Ok(ports) => ports,
+                Err(e) => {
+                    warn("port scan failed", e);
+                    std::thread::sleep(interval);
+                    continue;
+                }
+            };
+            let events = recorder.record(&ports).unwrap_or_else(|e| {
+                warn("history not recorded", e);
+                Vec::new()
+            });
+            server.publish(
+                DaemonSnapshot::new(
+                    std::process::id(),
+                    started,
+                    history::unix_now(),
+                    interval.as_secs(),
+                    ports,
+                ),
+                &events,
+            );
+            std::thread::sleep(interval);
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::history::HistoryKind;
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stale_snapshots_are_not_used() {
+        let snapshot = DaemonSnapshot::new(42, 0, 100, 2, Vec::new());
+        assert!(snapshot.is_fresh(100));
+        assert!(snapshot.is_fresh(103));
+        assert!(!snapshot.is_fresh(104));
+    }
+
+    #[test]
+    fn test_snapshot_and_event_subscription() {
+        let dir = std::env::temp_dir().join(format!("portr-daemon-{}", std::process::id()));
+        let path = dir.join(SOCKET_FILE);
+        let server = DaemonServer::bind(&path).unwrap();
+
+        // A second daemon on the same socket is refused
+        assert!(DaemonServer::bind(&path).is_err());
+
+        server.publish(
+            DaemonSnapshot {
+                pid: 42,
+                interval_secs: 2,
+                ..Default::default()
+            },
+            &[],
+        );
+        let snapshot = request_snapshot(&path).unwrap();
+        assert_eq!(snapshot.pid, 42);
+        assert!(snapshot.ports.is_empty());
+
+        // Command lines survive the socket even though PortInfo skips them
+        let port = PortInfo {
+            command: vec!["node".to_string(), "server.js".to_string()],
+            ..PortInfo::test_port(3000, 7)
+        };
+        server.publish(DaemonSnapshot::new(42, 0, 10, 2, vec![port]), &[]);
+        let ports = request_snapshot(&path).unwrap().into_ports();
+        assert_eq!(ports[0].command, vec!["node", "server.js"]);
+
+        let (tx, rx) = mpsc::channel();
+        let sub_path = path.clone();
+        std::thread::spawn(move || {
+            subscribe_events(&sub_path, |event| tx.send(event).is_ok()).unwrap();
+        });
+
+        let event = HistoryEvent {
+            timestamp: 1,
+            event: HistoryKind::Bind,
+            port: 8080,
+            protocol: "TCP".to_string(),
+            pid: 7,
+            process: "node".to_string(),
+            local_address: "127.0.0.1:8080".to_string(),
+            command: String::new(),
+            user: None,
+            state: "LISTEN".to_string(),
+            previous_pid: None,
+            previous_process: None,
+            previous_state: None,
+        };
+        // Keep publishing until the subscriber has registered
+        let received = (0..50).find_map(|_| {
+            server.publish(DaemonSnapshot::default(), std::slice::from_ref(&event));
+            rx.recv_timeout(Duration::from_millis(100)).ok()
+        });
+        assert_eq!(received, Some(event));
+
+        drop(server);
+        assert!(!path.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_unknown_request_gets_valid_json() {
+        let dir = std::env::temp_dir().join(format!("portr-daemon-bad-{}", std::process::id()));
+        let path = dir.join(SOCKET_FILE);
+        let _server = DaemonServer::bind(&path).unwrap();
+
+        let mut stream = UnixStream::connect(&path).unwrap();
+        stream.write_all(b"say \"hi\" \\o\n").unwrap();
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line).unwrap();
+
+        let reply: serde_json::Value = serde_json::from_str(&line).expect("valid JSON");
+        assert_eq!(reply["error"], "unknown request 'say \"hi\" \\o'");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,9 +1,12 @@
 //! Display formatting and output
 
+use crate::columns::{Column, TABLE_COLUMNS};
 use crate::error::PortrError;
+use crate::history::{HistoryEvent, HistoryKind};
 use crate::port::PortInfo;
 use colored::Colorize;
-use tabled::{settings::Style, Table, Tabled};
+use std::collections::BTreeMap;
+use tabled::{builder::Builder, settings::Style, Table, Tabled};
 
 /// Print the portr ASCII banner
 pub fn print_banner() {
@@ -26,43 +29,40 @@ pub fn print_banner() {
     println!("{}", banner.cyan());
 }
 
-/// Table row for port display
-#[derive(Tabled)]
-struct PortRow {
-    #[tabled(rename = "PORT")]
-    port: String,
-    #[tabled(rename = "PROTO")]
-    protocol: String,
-    #[tabled(rename = "PID")]
-    pid: String,
-    #[tabled(rename = "PROCESS")]
-    process: String,
-    #[tabled(rename = "MEMORY")]
-    memory: String,
-    #[tabled(rename = "UPTIME")]
-    uptime: String,
+/// Widest a cell of `column` gets in the port table
+fn max_width(column: Column) -> usize {
+    match column {
+        Column::Proc | Column::Container => 25,
+        Column::User => 12,
+        Column::Cmd => 60,
+        _ => usize::MAX,
+    }
 }
 
 /// Print a table of ports
 pub fn print_port_table(ports: &[PortInfo]) {
+    print_port_table_with(ports, None);
+}
+
+/// Print a table of ports with the given columns, or the default set
+pub fn print_port_table_with(ports: &[PortInfo], columns: Option<&[Column]>) {
     if ports.is_empty() {
         println!("{}", "No listening ports found.".dimmed());
         return;
     }
 
-    let rows: Vec<PortRow> = ports
-        .iter()
-        .map(|p| PortRow {
-            port: p.port.to_string(),
-            protocol: p.protocol.clone(),
-            pid: p.pid.to_string(),
-            process: truncate(&p.process_name, 25),
-            memory: format!("{:.1} MB", p.memory_mb),
-            uptime: p.uptime_display(),
-        })
-        .collect();
+    let columns = columns.unwrap_or(TABLE_COLUMNS);
+    let mut builder = Builder::default();
+    builder.push_record(columns.iter().map(Column::header));
+    for p in ports {
+        builder.push_record(
+            columns
+                .iter()
+                .map(|c| truncate(&c.display(p), max_width(*c))),
+        );
+    }
 
-    let table = Table::new(rows).with(Style::rounded()).to_string();
+    let table = builder.build().with(Style::rounded()).to_string();
 
     println!("{}", table);
     println!(
@@ -71,6 +71,9 @@ pub fn print_port_table(ports: &[PortInfo]) {
         ports.len().to_string().yellow()
     );
 
+    print_compose_groups(ports);
+    print_tunnels(ports);
+
     // Hint for discoverability
     println!(
         "\n{} {} {} {} {} {} {}",
@@ -84,6 +87,70 @@ pub fn print_port_table(ports: &[PortInfo]) {
     );
 }
 
+/// Print ports published by docker compose, grouped by project and service
+fn print_compose_groups(ports: &[PortInfo]) {
+    let groups = compose_groups(ports);
+    if groups.is_empty() {
+        return;
+    }
+
+    println!("\n{} Compose projects", "🐳".blue().bold());
+    for (project, services) in groups {
+        println!("  {}", project.cyan().bold());
+        for (service, service_ports) in services {
+            let list: Vec<String> = service_ports.iter().map(u16::to_string).collect();
+            println!("    {:<20} {}", service, list.join(", ").yellow());
+        }
+    }
+}
+
+/// Print SSH tunnels as their own category below the table
+fn print_tunnels(ports: &[PortInfo]) {
+    let tunnels: Vec<_> = ports
+        .iter()
+        .filter_map(|p| p.tunnel.as_ref().map(|t| (p.port, t)))
+        .collect();
+    if tunnels.is_empty() {
+        return;
+    }
+
+    println!("\n{} SSH tunnels", "⇄".magenta().bold());
+    for (port, tunnel) in tunnels {
+        println!(
+            "  {:<7} {} {}",
+            port.to_string().yellow(),
+            tunnel.kind.flag().magenta(),
+            tunnel
+        );
+    }
+}
+
+/// Ports of compose containers as project → service → ports
+fn compose_groups(ports: &[PortInfo]) -> BTreeMap<&str, BTreeMap<&str, Vec<u16>>> {
+    let mut groups: BTreeMap<&str, BTreeMap<&str, Vec<u16>>> = BTreeMap::new();
+
+    for p in ports {
+        if let Some(compose) = p.compose() {
+            let service_ports = groups
+                .entry(compose.project.as_str())
+                .or_default()
+                .entry(compose.service.as_str())
+                .or_default();
+            if !service_ports.contains(&p.port) {
+                service_ports.push(p.port);
+            }
+        }
+    }
+
+    for services in groups.values_mut() {
+        for service_ports in services.values_mut() {
+            service_ports.sort_unstable();
+        }
+    }
+
+    groups
+}
+
 /// Print detailed information about a single port
 pub fn print_port_details(info: &PortInfo, verbose: bool) {
     let box_width = 60;
@@ -102,6 +169,29 @@ pub fn print_port_details(info: &PortInfo, verbose: bool) {
     print_detail_line("PID", &info.pid.to_string(), "yellow");
     print_detail_line("Process", &info.process_name, "green");
 
+    if let Some(ref container) = info.container {
+        print_detail_line("Container", &container.name, "cyan");
+        if let Some(ref compose) = container.compose {
+            print_detail_line("Compose", &compose.to_string(), "cyan");
+        }
+        print_detail_line("Image", &container.image, "dimmed");
+    }
+
+    if let Some(ref forward) = info.kube_forward {
+        print_detail_line("Forward", &forward.to_string(), "cyan");
+        if let Some(ref context) = forward.context {
+            print_detail_line("Context", context, "dimmed");
+        }
+    }
+
+    if let Some(ref tunnel) = info.tunnel {
+        print_detail_line(
+            "Tunnel",
+            &format!("ssh {} {}", tunnel.kind.flag(), tunnel),
+            "magenta",
+        );
+    }
+
     // Show parent process if available
     if let (Some(ppid), Some(ref pname)) = (info.parent_pid, &info.parent_name) {
         print_detail_line("Parent", &format!("{} (PID {})", pname, ppid), "dimmed");
@@ -128,6 +218,22 @@ pub fn print_port_details(info: &PortInfo, verbose: bool) {
         if let Some(ref user) = info.user {
             print_detail_line("User", user, "dimmed");
         }
+        if let Some(ref euser) = info.effective_user {
+            if info.user.as_ref() != Some(euser) {
+                print_detail_line("Eff. User", euser, "dimmed");
+            }
+        }
+        if let Some(ref group) = info.group {
+            print_detail_line("Group", group, "dimmed");
+        }
+        if let Some(ref egroup) = info.effective_group {
+            if info.group.as_ref() != Some(egroup) {
+                print_detail_line("Eff. Group", egroup, "dimmed");
+            }
+        }
+        if let Some(ref container) = info.container {
+            print_detail_line("Cont. ID", &container.id, "dimmed");
+        }
     }
 
     // Bottom border
@@ -161,6 +267,154 @@ fn print_detail_line(label: &str, value: &str, color: &str) {
     println!("{}  {:>10}: {}", "│".cyan(), label.dimmed(), colored_value);
 }
 
+/// Table row for Docker container port mappings
+#[cfg(feature = "docker")]
+#[derive(Tabled)]
+struct ContainerRow {
+    #[tabled(rename = "SERVICE")]
+    service: String,
+    #[tabled(rename = "CONTAINER")]
+    container: String,
+    #[tabled(rename = "IMAGE")]
+    image: String,
+    #[tabled(rename = "HOST")]
+    host: String,
+    #[tabled(rename = "CONTAINER PORT")]
+    target: String,
+    #[tabled(rename = "STATUS")]
+    status: String,
+    #[tabled(rename = "CRITICAL")]
+    critical: String,
+}
+
+/// Print a table of container port mappings
+#[cfg(feature = "docker")]
+pub fn print_container_table(rows: &[crate::docker::ContainerPortRow]) {
+    if rows.is_empty() {
+        println!("{}", "No running containers found.".dimmed());
+        return;
+    }
+
+    let table_rows: Vec<ContainerRow> = rows
+        .iter()
+        .map(|r| ContainerRow {
+            service: match (r.compose_project.as_deref(), r.compose_service.as_deref()) {
+                (Some(project), Some(service)) => truncate(&format!("{}/{}", project, service), 25),
+                _ => "-".to_string(),
+            },
+            container: truncate(&r.container, 25),
+            image: truncate(&r.image, 25),
+            host: match (r.host_ip.as_deref(), r.host_port) {
+                (Some(ip), Some(port)) => format!("{}:{}", ip, port),
+                (None, Some(port)) => port.to_string(),
+                _ => "-".to_string(),
+            },
+            target: match (r.container_port, r.protocol.as_deref()) {
+                (Some(port), Some(proto)) => format!("{}/{}", port, proto),
+                _ => "-".to_string(),
+            },
+            status: truncate(&r.status, 20),
+            critical: if r.critical { "⚠" } else { "" }.to_string(),
+        })
+        .collect();
+
+    println!("{}", Table::new(table_rows).with(Style::rounded()));
+
+    let mut containers: Vec<&str> = rows.iter().map(|r| r.id.as_str()).collect();
+    containers.dedup();
+    println!(
+        "\n{} {} container(s) running",
+        "🐳".blue().bold(),
+        containers.len().to_string().yellow()
+    );
+}
+
+/// Table row for port history
+#[derive(Tabled)]
+struct HistoryRow {
+    #[tabled(rename = "TIME")]
+    time: String,
+    #[tabled(rename = "EVENT")]
+    event: String,
+    #[tabled(rename = "PORT")]
+    port: String,
+    #[tabled(rename = "PID")]
+    pid: String,
+    #[tabled(rename = "PROCESS")]
+    process: String,
+    #[tabled(rename = "COMMAND")]
+    command: String,
+}
+
+/// Print recorded bind/unbind events, oldest first
+pub fn print_history(events: &[HistoryEvent]) {
+    if events.is_empty() {
+        println!("{}", "No history events match.".dimmed());
+        return;
+    }
+
+    let rows: Vec<HistoryRow> = events
+        .iter()
+        .map(|e| HistoryRow {
+            time: e.time_display(),
+            event: match e.event {
+                HistoryKind::Bind => "▲ bind".to_string(),
+                HistoryKind::Unbind => "▼ unbind".to_string(),
+                HistoryKind::OwnerChange => "⇄ new owner".to_string(),
+                HistoryKind::StateChange => match e.previous_state {
+                    Some(ref prev) => format!("~ {} → {}", prev, e.state),
+                    None => "~ state".to_string(),
+                },
+            },
+            port: format!("{}/{}", e.port, e.protocol),
+            pid: match e.previous_pid {
+                Some(prev) => format!("{} → {}", prev, e.pid),
+                None => e.pid.to_string(),
+            },
+            process: truncate(&e.process, 25),
+            command: truncate(
+                if e.command.is_empty() {
+                    "-"
+                } else {
+                    &e.command
+                },
+                40,
+            ),
+        })
+        .collect();
+
+    println!("{}", Table::new(rows).with(Style::rounded()));
+    println!(
+        "\n{} {} event(s)",
+        "●".blue().bold(),
+        events.len().to_string().yellow()
+    );
+
+    // Ports that were taken more than once
+    let flapping: Vec<(u16, usize)> = bind_counts(events)
+        .into_iter()
+        .filter(|(_, binds)| *binds > 1)
+        .collect();
+    if !flapping.is_empty() {
+        println!("\n{} Rebound ports", "⟳".yellow().bold());
+        for (port, binds) in flapping {
+            println!("  {:<7} bound {} times", port.to_string().yellow(), binds);
+        }
+    }
+}
+
+/// Number of times each port was bound (including owner changes)
+fn bind_counts(events: &[HistoryEvent]) -> BTreeMap<u16, usize> {
+    let mut counts = BTreeMap::new();
+    for e in events
+        .iter()
+        .filter(|e| matches!(e.event, HistoryKind::Bind | HistoryKind::OwnerChange))
+    {
+        *counts.entry(e.port).or_insert(0) += 1;
+    }
+    counts
+}
+
 /// Print JSON output
 pub fn print_json<T: serde::Serialize>(data: &T) -> Result<(), PortrError> {
     let json = serde_json::to_string_pretty(data)?;
@@ -168,10 +422,11 @@ pub fn print_json<T: serde::Serialize>(data: &T) -> Result<(), PortrError> {
     Ok(())
 }
 
-/// Truncate a string to a maximum length
+/// Truncate a string to a maximum length (in characters)
 fn truncate(s: &str, max: usize) -> String {
-    if s.len() > max {
-        format!("{}...", &s[..max - 3])
+    if s.chars().count() > max {
+        let head: String = s.chars().take(max - 3).collect();
+        format!("{}...", head)
     } else {
         s.to_string()
     }
@@ -231,6 +486,39 @@ mod tests {
         assert_eq!(port_status_icon("ESTABLISHED"), "◉");
     }
 
+    #[test]
+    fn test_compose_groups() {
+        use crate::port::{ComposeService, ContainerRef};
+
+        let port = |port: u16, service: Option<&str>| PortInfo {
+            process_name: "docker-proxy".to_string(),
+            local_address: format!("0.0.0.0:{}", port),
+            memory_mb: 1.0,
+            uptime_secs: 1,
+            container: service.map(|s| ContainerRef {
+                id: "abc".to_string(),
+                name: format!("shop-{}-1", s),
+                image: "img".to_string(),
+                compose: Some(ComposeService {
+                    project: "shop".to_string(),
+                    service: s.to_string(),
+                }),
+            }),
+            ..PortInfo::test_port(port, 100)
+        };
+
+        let ports = vec![
+            port(8081, Some("api")),
+            port(5432, Some("db")),
+            port(8080, Some("api")),
+            port(3000, None),
+        ];
+        let groups = compose_groups(&ports);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups["shop"]["api"], vec![8080, 8081]);
+        assert_eq!(groups["shop"]["db"], vec![5432]);
+    }
+
     #[test]
     fn test_port_status_icon_unknown() {
         assert_eq!(port_status_icon("UNKNOWN"), "○");
@@ -7,7 +7,13 @@
 //! Containers are identified by name + image, not ID, to handle
 //! container ID changes during restarts/recreations.
 
+use crate::config::DEFAULT_STOP_TIMEOUT;
 use crate::error::PortrError;
+use crate::port::{self, ComposeService, ContainerRef, PortInfo};
+use crate::services::RiskLevel;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::OnceLock;
 
 /// Information about a Docker container using a port
 #[derive(Debug, Clone)]
@@ -22,6 +28,8 @@ pub struct ContainerInfo {
     pub status: String,
     /// All exposed ports
     pub ports: Vec<PortMapping>,
+    /// Compose project and service, from the container's compose labels
+    pub compose: Option<ComposeService>,
 }
 
 impl ContainerInfo {
@@ -36,34 +44,575 @@ impl ContainerInfo {
         self.name == other.name && self.image == other.image
     }
 
+    /// Short reference stored on [`PortInfo`] for ports this container publishes
+    pub fn container_ref(&self) -> ContainerRef {
+        ContainerRef {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            image: self.image.clone(),
+            compose: self.compose.clone(),
+        }
+    }
+
+    /// "project/service" for compose containers, else the container name
+    pub fn display_name(&self) -> String {
+        match self.compose {
+            Some(ref compose) => compose.to_string(),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Whether this container belongs to the given compose project and/or service
+    ///
+    /// `None` matches anything; a container outside compose never matches.
+    pub fn in_compose(&self, project: Option<&str>, service: Option<&str>) -> bool {
+        self.compose.as_ref().is_some_and(|c| {
+            project.is_none_or(|p| c.project == p) && service.is_none_or(|s| c.service == s)
+        })
+    }
+
+    /// Check whether this container publishes `port` on the host
+    pub fn publishes(&self, port: u16, protocol: &str) -> bool {
+        self.ports
+            .iter()
+            .any(|p| p.host_port == Some(port) && p.protocol.eq_ignore_ascii_case(protocol))
+    }
+
+    /// Check whether this container publishes `port` over any protocol
+    pub fn publishes_any(&self, port: u16) -> bool {
+        self.ports.iter().any(|p| p.host_port == Some(port))
+    }
+
+    /// Widest exposure across all published ports
+    pub fn exposure(&self) -> Exposure {
+        self.ports
+            .iter()
+            .map(PortMapping::exposure)
+            .max()
+            .unwrap_or(Exposure::Unpublished)
+    }
+
     /// Check if container is bound to localhost only (lower risk)
     pub fn is_localhost_only(&self) -> bool {
-        // If all host ports are bound to 127.0.0.1, it's localhost only
-        // Note: bollard doesn't provide bind IP directly in port summary,
-        // so we assume non-localhost by default for safety
-        false
+        self.exposure() == Exposure::Localhost
+    }
+
+    /// Published ports in `docker ps` style (e.g. "127.0.0.1:5432->5432/tcp")
+    pub fn published_ports(&self) -> Vec<String> {
+        let mut ports: Vec<String> = self
+            .ports
+            .iter()
+            .filter(|p| p.host_port.is_some())
+            .map(PortMapping::to_string)
+            .collect();
+        ports.dedup();
+        ports
     }
 }
 
 /// Port mapping for a container
 #[derive(Debug, Clone)]
 pub struct PortMapping {
+    /// Host address the port is bound to ("0.0.0.0" / "::" for all interfaces)
+    pub host_ip: Option<String>,
     pub host_port: Option<u16>,
     pub container_port: u16,
     pub protocol: String,
 }
 
+impl PortMapping {
+    /// How widely this mapping is reachable
+    ///
+    /// A published port without a known bind address is treated as exposed
+    /// on all interfaces, which is Docker's default.
+    pub fn exposure(&self) -> Exposure {
+        if self.host_port.is_none() {
+            return Exposure::Unpublished;
+        }
+
+        match self.host_ip.as_deref() {
+            None | Some("") | Some("0.0.0.0") | Some("::") => Exposure::AllInterfaces,
+            Some(ip) => match ip.parse::<std::net::IpAddr>() {
+                Ok(addr) if addr.is_loopback() => Exposure::Localhost,
+                _ => Exposure::Interface,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for PortMapping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.host_ip.as_deref(), self.host_port) {
+            (Some(ip), Some(hp)) if ip.contains(':') => {
+                write!(
+                    f,
+                    "[{}]:{}->{}/{}",
+                    ip, hp, self.container_port, self.protocol
+                )
+            }
+            (Some(ip), Some(hp)) => {
+                write!(
+                    f,
+                    "{}:{}->{}/{}",
+                    ip, hp, self.container_port, self.protocol
+                )
+            }
+            (None, Some(hp)) => write!(f, "{}->{}/{}", hp, self.container_port, self.protocol),
+            (_, None) => write!(f, "{}/{}", self.container_port, self.protocol),
+        }
+    }
+}
+
+/// How widely a container's published ports are reachable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Exposure {
+    /// No ports published on the host
+    Unpublished,
+    /// Bound to loopback only (127.0.0.1 / ::1)
+    Localhost,
+    /// Bound to a specific non-loopback address
+    Interface,
+    /// Bound to every interface (0.0.0.0 / ::)
+    AllInterfaces,
+}
+
+impl Exposure {
+    /// Get exposure label
+    pub fn label(&self) -> &'static str {
+        match self {
+            Exposure::Unpublished => "not published",
+            Exposure::Localhost => "localhost only",
+            Exposure::Interface => "specific interface",
+            Exposure::AllInterfaces => "all interfaces",
+        }
+    }
+}
+
+/// Action to perform on a container
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerAction {
+    /// Stop the container (`docker stop`)
+    Stop,
+    /// Restart the container (`docker restart`)
+    Restart,
+    /// Freeze the container's processes (`docker pause`)
+    Pause,
+    /// Force-remove the container (`docker rm -f`)
+    Remove,
+}
+
+impl ContainerAction {
+    /// All actions, in menu order
+    pub const ALL: [ContainerAction; 4] = [
+        ContainerAction::Stop,
+        ContainerAction::Restart,
+        ContainerAction::Pause,
+        ContainerAction::Remove,
+    ];
+
+    /// Imperative verb ("stop")
+    pub fn verb(&self) -> &'static str {
+        match self {
+            ContainerAction::Stop => "stop",
+            ContainerAction::Restart => "restart",
+            ContainerAction::Pause => "pause",
+            ContainerAction::Remove => "remove",
+        }
+    }
+
+    /// Past tense for status messages ("Stopped")
+    pub fn past_tense(&self) -> &'static str {
+        match self {
+            ContainerAction::Stop => "Stopped",
+            ContainerAction::Restart => "Restarted",
+            ContainerAction::Pause => "Paused",
+            ContainerAction::Remove => "Removed",
+        }
+    }
+
+    /// Equivalent docker CLI command, for hints
+    pub fn docker_command(&self) -> &'static str {
+        match self {
+            ContainerAction::Stop => "docker stop",
+            ContainerAction::Restart => "docker restart",
+            ContainerAction::Pause => "docker pause",
+            ContainerAction::Remove => "docker rm -f",
+        }
+    }
+
+    /// What can go wrong when applied to a critical (stateful) container
+    pub fn critical_warning(&self) -> &'static str {
+        match self {
+            ContainerAction::Stop => "Stopping may cause DATA LOSS",
+            ContainerAction::Restart => "Clients will be disconnected during the restart",
+            ContainerAction::Pause => "Clients will hang until the container is unpaused",
+            ContainerAction::Remove => "Removing deletes all data not stored in a volume",
+        }
+    }
+}
+
+/// One published port of a container, flattened for listings and exports
+#[derive(Debug, Clone, Serialize)]
+pub struct ContainerPortRow {
+    pub container: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compose_project: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compose_service: Option<String>,
+    pub id: String,
+    pub image: String,
+    pub status: String,
+    pub host_ip: Option<String>,
+    pub host_port: Option<u16>,
+    pub container_port: Option<u16>,
+    pub protocol: Option<String>,
+    pub critical: bool,
+}
+
+/// Flatten containers into one row per port mapping
+///
+/// Containers without any port mappings still get a single row. Compose
+/// containers come first, grouped by project and service.
+pub fn container_port_rows(containers: &[ContainerInfo]) -> Vec<ContainerPortRow> {
+    let mut rows = Vec::new();
+
+    let mut containers: Vec<&ContainerInfo> = containers.iter().collect();
+    containers
+        .sort_by(|a, b| (a.compose.is_none(), &a.compose).cmp(&(b.compose.is_none(), &b.compose)));
+
+    for c in containers {
+        let critical = is_critical_container(c);
+        let row = |m: Option<&PortMapping>| ContainerPortRow {
+            container: c.name.clone(),
+            compose_project: c.compose.as_ref().map(|s| s.project.clone()),
+            compose_service: c.compose.as_ref().map(|s| s.service.clone()),
+            id: c.id.clone(),
+            image: c.image.clone(),
+            status: c.status.clone(),
+            host_ip: m.and_then(|m| m.host_ip.clone()),
+            host_port: m.and_then(|m| m.host_port),
+            container_port: m.map(|m| m.container_port),
+            protocol: m.map(|m| m.protocol.clone()),
+            critical,
+        };
+
+        if c.ports.is_empty() {
+            rows.push(row(None));
+        } else {
+            rows.extend(c.ports.iter().map(|m| row(Some(m))));
+        }
+    }
+
+    rows
+}
+
+/// Where a Docker endpoint was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EndpointSource {
+    /// The `DOCKER_HOST` environment variable
+    DockerHost,
+    /// A docker CLI context (`DOCKER_CONTEXT` or `currentContext`)
+    Context(String),
+    /// The system socket (`/var/run/docker.sock` or the Windows named pipe)
+    Default,
+    /// Rootless Docker (`$XDG_RUNTIME_DIR/docker.sock`)
+    Rootless,
+    /// Docker Desktop's per-user socket
+    DockerDesktop,
+    /// Colima's VM socket
+    Colima,
+    /// Podman's Docker-compatible API socket
+    Podman,
+}
+
+impl EndpointSource {
+    /// Human-readable origin for verbose output
+    pub fn label(&self) -> String {
+        match self {
+            EndpointSource::DockerHost => "DOCKER_HOST".to_string(),
+            EndpointSource::Context(name) => format!("context '{}'", name),
+            EndpointSource::Default => "default socket".to_string(),
+            EndpointSource::Rootless => "rootless Docker".to_string(),
+            EndpointSource::DockerDesktop => "Docker Desktop".to_string(),
+            EndpointSource::Colima => "Colima".to_string(),
+            EndpointSource::Podman => "Podman".to_string(),
+        }
+    }
+}
+
+/// A Docker Engine API endpoint, e.g. `unix:///var/run/docker.sock`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DockerEndpoint {
+    /// Endpoint URL as Docker writes it (`unix://`, `npipe://`, `tcp://`)
+    pub host: String,
+    /// Where the endpoint came from
+    pub source: EndpointSource,
+}
+
+impl DockerEndpoint {
+    fn new(host: impl Into<String>, source: EndpointSource) -> Self {
+        Self {
+            host: host.into(),
+            source,
+        }
+    }
+
+    /// Local socket or named pipe path, if this is not a network endpoint
+    pub fn socket_path(&self) -> Option<PathBuf> {
+        if let Some(path) = self.host.strip_prefix("unix://") {
+            return Some(PathBuf::from(path));
+        }
+        self.host
+            .strip_prefix("npipe://")
+            .map(|pipe| PathBuf::from(pipe.replace('/', "\\")))
+    }
+
+    /// Whether the endpoint can be tried: local sockets must exist,
+    /// network endpoints are assumed reachable until a request fails
+    pub fn is_reachable(&self) -> bool {
+        match self.socket_path() {
+            Some(path) => path.exists(),
+            None => true,
+        }
+    }
+
+    /// Open an API client for this endpoint
+    #[cfg(feature = "docker")]
+    fn connect(&self) -> Result<bollard::Docker, PortrError> {
+        use bollard::{Docker, API_DEFAULT_VERSION};
+
+        const TIMEOUT_SECS: u64 = 120;
+
+        let scheme = self.host.split("://").next().unwrap_or("");
+        let result = match scheme {
+            "unix" | "npipe" => {
+                Docker::connect_with_socket(&self.host, TIMEOUT_SECS, API_DEFAULT_VERSION)
+            }
+            "tcp" | "http" => {
+                Docker::connect_with_http(&self.host, TIMEOUT_SECS, API_DEFAULT_VERSION)
+            }
+            _ => {
+                return Err(PortrError::DockerError(format!(
+                "Unsupported Docker endpoint {} ({}); use a unix://, npipe:// or tcp:// endpoint",
+                self.host,
+                self.source.label()
+            )))
+            }
+        };
+
+        result.map_err(|e| PortrError::DockerError(format!("{}: {}", self.host, e)))
+    }
+}
+
+impl std::fmt::Display for DockerEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.host, self.source.label())
+    }
+}
+
+/// Environment consulted when looking for the Docker endpoint
+///
+/// Mirrors the docker CLI: `DOCKER_HOST` wins, then the selected context,
+/// then the well-known sockets of Docker, rootless Docker, Docker Desktop,
+/// Colima and Podman.
+#[derive(Debug, Clone, Default)]
+struct EndpointSearch {
+    docker_host: Option<String>,
+    docker_context: Option<String>,
+    /// Docker CLI config directory (`$DOCKER_CONFIG` or `~/.docker`)
+    config_dir: Option<PathBuf>,
+    home: Option<PathBuf>,
+    runtime_dir: Option<PathBuf>,
+    /// Filesystem root for system sockets (overridden in tests)
+    root: PathBuf,
+}
+
+impl EndpointSearch {
+    fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+
+        let home = var("HOME")
+            .or_else(|| var("USERPROFILE"))
+            .map(PathBuf::from);
+        let config_dir = var("DOCKER_CONFIG")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|h| h.join(".docker")));
+
+        #[cfg(unix)]
+        let runtime_dir = var("XDG_RUNTIME_DIR").map(PathBuf::from).or_else(|| {
+            Some(PathBuf::from(format!(
+                "/run/user/{}",
+                nix::unistd::getuid().as_raw()
+            )))
+        });
+        #[cfg(not(unix))]
+        let runtime_dir = None;
+
+        Self {
+            docker_host: var("DOCKER_HOST"),
+            docker_context: var("DOCKER_CONTEXT"),
+            config_dir,
+            home,
+            runtime_dir,
+            root: PathBuf::from("/"),
+        }
+    }
+
+    /// Pick the endpoint to use
+    ///
+    /// An explicit `DOCKER_HOST` or context is returned even if its socket
+    /// is missing, so errors point at what the user configured; otherwise
+    /// the first well-known socket that exists wins.
+    fn resolve(&self) -> Option<DockerEndpoint> {
+        if let Some(ref host) = self.docker_host {
+            return Some(DockerEndpoint::new(
+                host.clone(),
+                EndpointSource::DockerHost,
+            ));
+        }
+
+        if let Some(endpoint) = self.context_endpoint() {
+            return Some(endpoint);
+        }
+
+        self.candidates().into_iter().find(|e| e.is_reachable())
+    }
+
+    /// Endpoint of the selected docker context, unless it is "default"
+    fn context_endpoint(&self) -> Option<DockerEndpoint> {
+        let config_dir = self.config_dir.as_ref()?;
+        let name = match self.docker_context {
+            Some(ref name) => name.clone(),
+            None => {
+                let config = std::fs::read_to_string(config_dir.join("config.json")).ok()?;
+                let config: serde_json::Value = serde_json::from_str(&config).ok()?;
+                config.get("currentContext")?.as_str()?.to_string()
+            }
+        };
+        if name.is_empty() || name == "default" {
+            return None;
+        }
+
+        // Context directories are named by a hash of the context name, so
+        // scan them and match on the Name field instead
+        let meta_dir = config_dir.join("contexts").join("meta");
+        std::fs::read_dir(meta_dir)
+            .ok()?
+            .flatten()
+            .filter_map(|entry| std::fs::read_to_string(entry.path().join("meta.json")).ok())
+            .filter_map(|meta| serde_json::from_str::<serde_json::Value>(&meta).ok())
+            .find(|meta| meta.get("Name").and_then(|n| n.as_str()) == Some(name.as_str()))
+            .and_then(|meta| {
+                meta.pointer("/Endpoints/docker/Host")?
+                    .as_str()
+                    .map(str::to_string)
+            })
+            .map(|host| DockerEndpoint::new(host, EndpointSource::Context(name)))
+    }
+
+    /// Well-known sockets, most common first
+    fn candidates(&self) -> Vec<DockerEndpoint> {
+        let mut candidates = Vec::new();
+
+        #[cfg(windows)]
+        candidates.push(DockerEndpoint::new(
+            "npipe:////./pipe/docker_engine",
+            EndpointSource::Default,
+        ));
+
+        let mut push = |path: PathBuf, source: EndpointSource| {
+            candidates.push(DockerEndpoint::new(
+                format!("unix://{}", path.display()),
+                source,
+            ));
+        };
+
+        if cfg!(unix) {
+            push(
+                self.root.join("var/run/docker.sock"),
+                EndpointSource::Default,
+            );
+        }
+        if let Some(ref runtime) = self.runtime_dir {
+            push(runtime.join("docker.sock"), EndpointSource::Rootless);
+        }
+        if let Some(ref home) = self.home {
+            push(
+                home.join(".docker/run/docker.sock"),
+                EndpointSource::DockerDesktop,
+            );
+            push(
+                home.join(".docker/desktop/docker.sock"),
+                EndpointSource::DockerDesktop,
+            );
+            push(
+                home.join(".colima/default/docker.sock"),
+                EndpointSource::Colima,
+            );
+            push(home.join(".colima/docker.sock"), EndpointSource::Colima);
+        }
+        if let Some(ref runtime) = self.runtime_dir {
+            push(runtime.join("podman/podman.sock"), EndpointSource::Podman);
+        }
+        if cfg!(unix) {
+            push(
+                self.root.join("run/podman/podman.sock"),
+                EndpointSource::Podman,
+            );
+        }
+
+        candidates
+    }
+}
+
+/// The Docker endpoint portr talks to, resolved once per process
+pub fn endpoint() -> Option<&'static DockerEndpoint> {
+    static ENDPOINT: OnceLock<Option<DockerEndpoint>> = OnceLock::new();
+    ENDPOINT
+        .get_or_init(|| EndpointSearch::from_env().resolve())
+        .as_ref()
+}
+
 /// Check if Docker is available on the system
 pub fn is_docker_available() -> bool {
-    #[cfg(windows)]
-    {
-        // Check if Docker named pipe exists
-        std::path::Path::new(r"\\.\pipe\docker_engine").exists()
+    endpoint().is_some_and(DockerEndpoint::is_reachable)
+}
+
+/// Explain why Docker can't be reached
+pub fn unavailable_reason() -> String {
+    match endpoint() {
+        Some(endpoint) => format!("Docker is not running (nothing at {})", endpoint),
+        None => "Docker is not running (no socket found via DOCKER_HOST, docker context, \
+             or the Docker, rootless, Desktop, Colima and Podman defaults)"
+            .to_string(),
     }
+}
 
-    #[cfg(unix)]
-    {
-        std::path::Path::new("/var/run/docker.sock").exists()
+/// Connect to the resolved endpoint
+#[cfg(feature = "docker")]
+fn connect() -> Result<bollard::Docker, PortrError> {
+    match endpoint() {
+        Some(endpoint) => endpoint.connect(),
+        None => Err(PortrError::DockerError(unavailable_reason())),
+    }
+}
+
+/// Print the Docker endpoint in use (verbose output)
+pub fn print_endpoint() {
+    use colored::Colorize;
+
+    match endpoint() {
+        Some(endpoint) => println!(
+            "  {} Docker endpoint: {} {}",
+            "🐳".blue().bold(),
+            endpoint.host.cyan(),
+            format!("({})", endpoint.source.label()).dimmed()
+        ),
+        None => println!(
+            "  {} Docker endpoint: {}",
+            "🐳".blue().bold(),
+            "none found".dimmed()
+        ),
     }
 }
 
@@ -84,6 +633,103 @@ pub fn get_container_for_port(port: u16) -> Option<ContainerInfo> {
     .unwrap_or_default()
 }
 
+/// Find the container that publishes a host port
+pub fn container_for_port<'a>(
+    containers: &'a [ContainerInfo],
+    port: u16,
+    protocol: &str,
+) -> Option<&'a ContainerInfo> {
+    containers.iter().find(|c| c.publishes(port, protocol))
+}
+
+/// Fill in [`PortInfo::container`] for ports that containers publish
+///
+/// Docker publishes container ports through `docker-proxy` (or the Desktop
+/// backend), so the process table alone never names the container. This
+/// matches those listeners against the published ports of running containers.
+/// With the userland proxy off (`"userland-proxy": false`), iptables forwards
+/// published ports and nothing listens on the host; those get an entry of
+/// their own.
+pub fn attach_containers(ports: &mut Vec<PortInfo>) {
+    let containers = match get_all_containers() {
+        Ok(c) if !c.is_empty() => c,
+        _ => return,
+    };
+    merge_containers(ports, &containers);
+}
+
+/// Attach `containers` to forwarder listeners and add their unlistened ports
+///
+/// Added entries have PID 0 and `dockerd` as the process, since no host
+/// process owns the socket; kills go to the container like any other
+/// container port.
+fn merge_containers(ports: &mut Vec<PortInfo>, containers: &[ContainerInfo]) {
+    for info in ports
+        .iter_mut()
+        .filter(|p| port::is_docker_process(&p.process_name))
+    {
+        info.container = container_for_port(containers, info.port, &info.protocol)
+            .map(ContainerInfo::container_ref);
+    }
+
+    for container in containers {
+        for mapping in &container.ports {
+            let Some(host_port) = mapping.host_port else {
+                continue;
+            };
+            let protocol = mapping.protocol.to_uppercase();
+            // Docker lists IPv4 and IPv6 bindings of one port separately
+            if ports
+                .iter()
+                .any(|p| p.port == host_port && p.protocol == protocol)
+            {
+                continue;
+            }
+            let host_ip = mapping.host_ip.as_deref().unwrap_or("0.0.0.0");
+            ports.push(PortInfo {
+                port: host_port,
+                state: if protocol == "TCP" { "LISTEN" } else { "*" }.to_string(),
+                protocol,
+                pid: 0,
+                process_name: "dockerd".to_string(),
+                process_path: None,
+                local_address: format!("{}:{}", host_ip, host_port),
+                remote_address: None,
+                user: None,
+                effective_user: None,
+                group: None,
+                effective_group: None,
+                memory_mb: 0.0,
+                cpu_percent: 0.0,
+                uptime_secs: 0,
+                parent_pid: None,
+                parent_name: None,
+                container: Some(container.container_ref()),
+                kube_forward: None,
+                tunnel: None,
+                command: Vec::new(),
+            });
+        }
+    }
+    ports.sort_by_key(|p| p.port);
+}
+
+/// Running compose containers that publish ports, filtered by project and/or service
+pub fn compose_containers(
+    containers: &[ContainerInfo],
+    project: Option<&str>,
+    service: Option<&str>,
+) -> Vec<ContainerInfo> {
+    let mut matched: Vec<ContainerInfo> = containers
+        .iter()
+        .filter(|c| c.in_compose(project, service))
+        .filter(|c| c.ports.iter().any(|p| p.host_port.is_some()))
+        .cloned()
+        .collect();
+    matched.sort_by(|a, b| a.compose.cmp(&b.compose).then_with(|| a.name.cmp(&b.name)));
+    matched
+}
+
 /// Get all running containers with their port mappings
 pub fn get_all_containers() -> Result<Vec<ContainerInfo>, PortrError> {
     if !is_docker_available() {
@@ -99,131 +745,99 @@ pub fn get_all_containers() -> Result<Vec<ContainerInfo>, PortrError> {
 
 /// Stop a container by ID
 pub fn stop_container(container_id: &str) -> Result<(), PortrError> {
-    if !is_docker_available() {
-        return Err(PortrError::DockerError("Docker not available".to_string()));
-    }
-
-    tokio::runtime::Builder::new_current_thread()
-        .enable_all()
-        .build()
-        .map_err(|e| PortrError::DockerError(e.to_string()))?
-        .block_on(stop_container_async(container_id))
+    apply_container_action(container_id, ContainerAction::Stop, DEFAULT_STOP_TIMEOUT)
 }
 
 /// Stop a container by name (more stable than ID which can change)
 pub fn stop_container_by_name(container_name: &str) -> Result<(), PortrError> {
+    // Docker API accepts container name as well as ID
+    apply_container_action(container_name, ContainerAction::Stop, DEFAULT_STOP_TIMEOUT)
+}
+
+/// Find a running container by name or ID prefix
+pub fn find_container(target: &str) -> Result<Option<ContainerInfo>, PortrError> {
+    let target = target.trim_start_matches('/');
+    let containers = get_all_containers()?;
+
+    Ok(containers
+        .into_iter()
+        .find(|c| c.name == target || (target.len() >= 3 && c.id.starts_with(target))))
+}
+
+/// Apply an action to a container by name (or ID)
+///
+/// `stop_timeout` is how long Docker waits for a graceful shutdown before
+/// killing the container (stop and restart only).
+pub fn apply_container_action(
+    container: &str,
+    action: ContainerAction,
+    stop_timeout: u32,
+) -> Result<(), PortrError> {
     if !is_docker_available() {
-        return Err(PortrError::DockerError("Docker not available".to_string()));
+        return Err(PortrError::DockerError(unavailable_reason()));
     }
 
-    // Docker API accepts container name as well as ID
-    tokio::runtime::Builder::new_current_thread()
+    let runtime = tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
-        .map_err(|e| PortrError::DockerError(e.to_string()))?
-        .block_on(stop_container_async(container_name))
+        .map_err(|e| PortrError::DockerError(e.to_string()))?;
+
+    match action {
+        ContainerAction::Stop => runtime.block_on(stop_container_async(container, stop_timeout)),
+        ContainerAction::Restart => {
+            runtime.block_on(restart_container_async(container, stop_timeout))
+        }
+        ContainerAction::Pause => runtime.block_on(pause_container_async(container)),
+        ContainerAction::Remove => runtime.block_on(remove_container_async(container)),
+    }
+}
+
+/// Restart a container by name or ID
+pub fn restart_container(container: &str) -> Result<(), PortrError> {
+    apply_container_action(container, ContainerAction::Restart, DEFAULT_STOP_TIMEOUT)
+}
+
+/// Score the risk of stopping a container
+///
+/// Critical images (databases, brokers) start high; exposure decides the
+/// rest, since a container reachable from other machines likely has other
+/// clients depending on it.
+pub fn container_risk(container: &ContainerInfo) -> RiskLevel {
+    let exposure = container.exposure();
+
+    if is_critical_container(container) {
+        return if exposure >= Exposure::Interface {
+            RiskLevel::Critical
+        } else {
+            RiskLevel::High
+        };
+    }
+
+    match exposure {
+        Exposure::AllInterfaces => RiskLevel::High,
+        Exposure::Interface => RiskLevel::Medium,
+        Exposure::Localhost | Exposure::Unpublished => RiskLevel::Low,
+    }
 }
 
 /// Check if a container is running a critical service that requires confirmation
 pub fn is_critical_container(container: &ContainerInfo) -> bool {
-    let critical_images = [
-        "postgres",
-        "mysql",
-        "mariadb",
-        "mongo",
-        "redis",
-        "elasticsearch",
-        "rabbitmq",
-        "kafka",
-        "zookeeper",
-        "consul",
-        "vault",
-        "etcd",
-        "minio",
-    ];
-
-    let image_lower = container.image.to_lowercase();
-    critical_images.iter().any(|&c| image_lower.contains(c))
+    crate::services::is_critical_image(&container.image)
 }
 
 // Async implementations using bollard
 #[cfg(feature = "docker")]
 async fn get_container_for_port_async(port: u16) -> Option<ContainerInfo> {
-    use bollard::container::ListContainersOptions;
-    use bollard::Docker;
-    use std::collections::HashMap;
-
-    let docker = Docker::connect_with_local_defaults().ok()?;
-
-    let options = ListContainersOptions::<String> {
-        all: false, // Only running containers
-        filters: HashMap::new(),
-        ..Default::default()
-    };
-
-    let containers = docker.list_containers(Some(options)).await.ok()?;
-
-    for container in containers {
-        if let Some(ports) = &container.ports {
-            for port_binding in ports {
-                if let Some(public_port) = port_binding.public_port {
-                    if public_port == port {
-                        let name = container
-                            .names
-                            .as_ref()
-                            .and_then(|n| n.first())
-                            .map(|n| n.trim_start_matches('/').to_string())
-                            .unwrap_or_else(|| "unknown".to_string());
-
-                        let port_mappings: Vec<PortMapping> = ports
-                            .iter()
-                            .map(|p| PortMapping {
-                                host_port: p.public_port,
-                                container_port: p.private_port,
-                                protocol: p
-                                    .typ
-                                    .map(|t| format!("{:?}", t).to_lowercase())
-                                    .unwrap_or_else(|| "tcp".to_string()),
-                            })
-                            .collect();
-
-                        let id_str = container.id.clone().unwrap_or_default();
-                        let short_id = if id_str.len() >= 12 {
-                            id_str[..12].to_string()
-                        } else {
-                            id_str
-                        };
-
-                        return Some(ContainerInfo {
-                            id: short_id,
-                            name,
-                            image: container
-                                .image
-                                .clone()
-                                .unwrap_or_else(|| "unknown".to_string()),
-                            status: container
-                                .status
-                                .clone()
-                                .unwrap_or_else(|| "unknown".to_string()),
-                            ports: port_mappings,
-                        });
-                    }
-                }
-            }
-        }
-    }
-
-    None
+    let containers = get_all_containers_async().await.ok()?;
+    containers.into_iter().find(|c| c.publishes_any(port))
 }
 
 #[cfg(feature = "docker")]
 async fn get_all_containers_async() -> Result<Vec<ContainerInfo>, PortrError> {
     use bollard::container::ListContainersOptions;
-    use bollard::Docker;
     use std::collections::HashMap;
 
-    let docker = Docker::connect_with_local_defaults()
-        .map_err(|e| PortrError::DockerError(e.to_string()))?;
+    let docker = connect()?;
 
     let options = ListContainersOptions::<String> {
         all: false,
@@ -238,67 +852,125 @@ async fn get_all_containers_async() -> Result<Vec<ContainerInfo>, PortrError> {
 
     let mut result = Vec::new();
 
-    for container in containers {
-        let name = container
-            .names
-            .as_ref()
-            .and_then(|n| n.first())
-            .map(|n| n.trim_start_matches('/').to_string())
-            .unwrap_or_else(|| "unknown".to_string());
-
-        let port_mappings: Vec<PortMapping> = container
+    for summary in containers {
+        let mut container = container_from_summary(&summary);
+
+        // Some engines leave the bind IP out of the list response; the
+        // container's HostConfig always has the full port bindings.
+        let missing_ip = container
             .ports
-            .as_ref()
-            .map(|ports| {
-                ports
-                    .iter()
-                    .map(|p| PortMapping {
-                        host_port: p.public_port,
-                        container_port: p.private_port,
-                        protocol: p
-                            .typ
-                            .map(|t| format!("{:?}", t).to_lowercase())
-                            .unwrap_or_else(|| "tcp".to_string()),
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        result.push(ContainerInfo {
-            id: container
-                .id
-                .clone()
-                .unwrap_or_default()
-                .chars()
-                .take(12)
-                .collect(),
-            name,
-            image: container
-                .image
-                .clone()
-                .unwrap_or_else(|| "unknown".to_string()),
-            status: container
-                .status
-                .clone()
-                .unwrap_or_else(|| "unknown".to_string()),
-            ports: port_mappings,
-        });
+            .iter()
+            .any(|p| p.host_port.is_some() && p.host_ip.is_none());
+        if missing_ip {
+            if let Ok(details) = docker.inspect_container(&container.id, None).await {
+                if let Some(bindings) = details.host_config.and_then(|h| h.port_bindings) {
+                    fill_bind_ips(&mut container.ports, &bindings);
+                }
+            }
+        }
+
+        result.push(container);
     }
 
     Ok(result)
 }
 
+/// Convert a container summary from the list endpoint
+#[cfg(feature = "docker")]
+fn container_from_summary(container: &bollard::models::ContainerSummary) -> ContainerInfo {
+    let name = container
+        .names
+        .as_ref()
+        .and_then(|n| n.first())
+        .map(|n| n.trim_start_matches('/').to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let ports = container
+        .ports
+        .as_ref()
+        .map(|ports| {
+            ports
+                .iter()
+                .map(|p| PortMapping {
+                    host_ip: p.ip.clone().filter(|ip| !ip.is_empty()),
+                    host_port: p.public_port,
+                    container_port: p.private_port,
+                    protocol: p
+                        .typ
+                        .map(|t| format!("{:?}", t).to_lowercase())
+                        .unwrap_or_else(|| "tcp".to_string()),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    ContainerInfo {
+        id: container
+            .id
+            .clone()
+            .unwrap_or_default()
+            .chars()
+            .take(12)
+            .collect(),
+        name,
+        image: container
+            .image
+            .clone()
+            .unwrap_or_else(|| "unknown".to_string()),
+        status: container
+            .status
+            .clone()
+            .unwrap_or_else(|| "unknown".to_string()),
+        ports,
+        compose: container.labels.as_ref().and_then(compose_from_labels),
+    }
+}
+
+/// Read the compose project/service labels set by docker compose
+#[cfg(feature = "docker")]
+fn compose_from_labels(
+    labels: &std::collections::HashMap<String, String>,
+) -> Option<ComposeService> {
+    Some(ComposeService {
+        project: labels.get("com.docker.compose.project")?.clone(),
+        service: labels.get("com.docker.compose.service")?.clone(),
+    })
+}
+
+/// Fill missing bind IPs from `HostConfig.PortBindings` (keyed "80/tcp")
 #[cfg(feature = "docker")]
-async fn stop_container_async(container_id: &str) -> Result<(), PortrError> {
+fn fill_bind_ips(mappings: &mut [PortMapping], bindings: &bollard::models::PortMap) {
+    for mapping in mappings.iter_mut().filter(|m| m.host_ip.is_none()) {
+        let key = format!("{}/{}", mapping.container_port, mapping.protocol);
+        let host_port = mapping.host_port.map(|p| p.to_string());
+
+        let ip = bindings
+            .get(&key)
+            .and_then(|b| b.as_ref())
+            .and_then(|b| {
+                b.iter()
+                    .find(|b| b.host_port.is_none() || b.host_port == host_port)
+            })
+            .and_then(|b| b.host_ip.clone());
+
+        // An empty HostIp means "all interfaces"
+        mapping.host_ip = ip.map(|ip| {
+            if ip.is_empty() {
+                "0.0.0.0".to_string()
+            } else {
+                ip
+            }
+        });
+    }
+}
+
+#[cfg(feature = "docker")]
+async fn stop_container_async(container_id: &str, timeout: u32) -> Result<(), PortrError> {
     use bollard::container::StopContainerOptions;
-    use bollard::Docker;
 
-    let docker = Docker::connect_with_local_defaults()
-        .map_err(|e| PortrError::DockerError(e.to_string()))?;
+    let docker = connect()?;
 
-    let options = StopContainerOptions {
-        t: 10, // 10 second timeout
-    };
+    let options = StopContainerOptions { t: timeout as i64 };
 
     docker
         .stop_container(container_id, Some(options))
@@ -306,6 +978,49 @@ async fn stop_container_async(container_id: &str) -> Result<(), PortrError> {
         .map_err(|e| PortrError::DockerError(e.to_string()))
 }
 
+#[cfg(feature = "docker")]
+async fn restart_container_async(container_id: &str, timeout: u32) -> Result<(), PortrError> {
+    use bollard::container::RestartContainerOptions;
+
+    let docker = connect()?;
+
+    let options = RestartContainerOptions {
+        t: timeout as isize,
+    };
+
+    docker
+        .restart_container(container_id, Some(options))
+        .await
+        .map_err(|e| PortrError::DockerError(e.to_string()))
+}
+
+#[cfg(feature = "docker")]
+async fn pause_container_async(container_id: &str) -> Result<(), PortrError> {
+    let docker = connect()?;
+
+    docker
+        .pause_container(container_id)
+        .await
+        .map_err(|e| PortrError::DockerError(e.to_string()))
+}
+
+#[cfg(feature = "docker")]
+async fn remove_container_async(container_id: &str) -> Result<(), PortrError> {
+    use bollard::container::RemoveContainerOptions;
+
+    let docker = connect()?;
+
+    let options = RemoveContainerOptions {
+        force: true,
+        ..Default::default()
+    };
+
+    docker
+        .remove_container(container_id, Some(options))
+        .await
+        .map_err(|e| PortrError::DockerError(e.to_string()))
+}
+
 // Stub implementations when docker feature is not enabled
 #[cfg(not(feature = "docker"))]
 async fn get_container_for_port_async(_port: u16) -> Option<ContainerInfo> {
@@ -318,7 +1033,28 @@ async fn get_all_containers_async() -> Result<Vec<ContainerInfo>, PortrError> {
 }
 
 #[cfg(not(feature = "docker"))]
-async fn stop_container_async(_container_id: &str) -> Result<(), PortrError> {
+async fn stop_container_async(_container_id: &str, _timeout: u32) -> Result<(), PortrError> {
+    Err(PortrError::DockerError(
+        "Docker feature not enabled. Rebuild with --features docker".to_string(),
+    ))
+}
+
+#[cfg(not(feature = "docker"))]
+async fn restart_container_async(_container_id: &str, _timeout: u32) -> Result<(), PortrError> {
+    Err(PortrError::DockerError(
+        "Docker feature not enabled. Rebuild with --features docker".to_string(),
+    ))
+}
+
+#[cfg(not(feature = "docker"))]
+async fn pause_container_async(_container_id: &str) -> Result<(), PortrError> {
+    Err(PortrError::DockerError(
+        "Docker feature not enabled. Rebuild with --features docker".to_string(),
+    ))
+}
+
+#[cfg(not(feature = "docker"))]
+async fn remove_container_async(_container_id: &str) -> Result<(), PortrError> {
     Err(PortrError::DockerError(
         "Docker feature not enabled. Rebuild with --features docker".to_string(),
     ))
@@ -343,17 +1079,22 @@ pub fn print_container_info(port: u16) {
         println!("    Image: {}", container.image);
         println!("    Status: {}", container.status.green());
 
-        if !container.ports.is_empty() {
-            print!("    Ports: ");
-            let port_strs: Vec<String> = container
-                .ports
-                .iter()
-                .filter_map(|p| {
-                    p.host_port
-                        .map(|hp| format!("{}:{}/{}", hp, p.container_port, p.protocol))
-                })
-                .collect();
-            println!("{}", port_strs.join(", ").yellow());
+        let published = container.published_ports();
+        if !published.is_empty() {
+            println!("    Ports: {}", published.join(", ").yellow());
+        }
+        println!("    Exposure: {}", container.exposure().label());
+
+        if container.exposure() == Exposure::AllInterfaces {
+            println!(
+                "\n  {} Exposed on {} - reachable from other machines on your network",
+                "⚠".yellow().bold(),
+                "0.0.0.0".yellow().bold()
+            );
+            println!(
+                "    {}",
+                format!("Publish as 127.0.0.1:{}:<port> to keep it local", port).dimmed()
+            );
         }
 
         println!(
@@ -382,6 +1123,7 @@ mod tests {
             name: "my-postgres".to_string(),
             image: "postgres:15".to_string(),
             status: "Up 2 hours".to_string(),
+            compose: None,
             ports: vec![],
         };
 
@@ -397,6 +1139,7 @@ mod tests {
             name: "my-postgres".to_string(),
             image: "postgres:15".to_string(),
             status: "Up 2 hours".to_string(),
+            compose: None,
             ports: vec![],
         };
 
@@ -405,6 +1148,7 @@ mod tests {
             name: "my-postgres".to_string(),
             image: "postgres:15".to_string(),
             status: "Up 1 minute".to_string(),
+            compose: None,
             ports: vec![],
         };
 
@@ -420,6 +1164,7 @@ mod tests {
             name: "my-postgres".to_string(),
             image: "postgres:15".to_string(),
             status: "Up 2 hours".to_string(),
+            compose: None,
             ports: vec![],
         };
 
@@ -428,6 +1173,7 @@ mod tests {
             name: "my-redis".to_string(),
             image: "redis:7".to_string(),
             status: "Up 1 hour".to_string(),
+            compose: None,
             ports: vec![],
         };
 
@@ -443,6 +1189,7 @@ mod tests {
             name: "my-db".to_string(),
             image: "postgres:15-alpine".to_string(),
             status: "Up".to_string(),
+            compose: None,
             ports: vec![],
         };
 
@@ -456,6 +1203,7 @@ mod tests {
             name: "my-db".to_string(),
             image: "mysql:8.0".to_string(),
             status: "Up".to_string(),
+            compose: None,
             ports: vec![],
         };
 
@@ -469,6 +1217,7 @@ mod tests {
             name: "cache".to_string(),
             image: "redis:7-alpine".to_string(),
             status: "Up".to_string(),
+            compose: None,
             ports: vec![],
         };
 
@@ -482,6 +1231,7 @@ mod tests {
             name: "my-app".to_string(),
             image: "node:20-alpine".to_string(),
             status: "Up".to_string(),
+            compose: None,
             ports: vec![],
         };
 
@@ -496,10 +1246,276 @@ mod tests {
             name: "web".to_string(),
             image: "nginx:latest".to_string(),
             status: "Up".to_string(),
+            compose: None,
             ports: vec![],
         };
 
         // Nginx is not in critical list (stateless)
         assert!(!is_critical_container(&container));
     }
+
+    fn published(name: &str, host_port: u16, protocol: &str) -> ContainerInfo {
+        ContainerInfo {
+            id: "abc123def456".to_string(),
+            name: name.to_string(),
+            image: "nginx:latest".to_string(),
+            status: "Up".to_string(),
+            compose: None,
+            ports: vec![PortMapping {
+                host_ip: Some("0.0.0.0".to_string()),
+                host_port: Some(host_port),
+                container_port: 80,
+                protocol: protocol.to_string(),
+            }],
+        }
+    }
+
+    fn proxy_port(port: u16) -> PortInfo {
+        PortInfo {
+            process_name: "docker-proxy".to_string(),
+            local_address: format!("0.0.0.0:{}", port),
+            user: Some("root".to_string()),
+            memory_mb: 4.0,
+            uptime_secs: 60,
+            ..PortInfo::test_port(port, 4242)
+        }
+    }
+
+    #[test]
+    fn test_container_for_port_matches_protocol() {
+        let containers = vec![published("web", 8080, "tcp"), published("dns", 5353, "udp")];
+
+        let web = container_for_port(&containers, 8080, "TCP").unwrap();
+        assert_eq!(web.name, "web");
+        assert!(container_for_port(&containers, 8080, "UDP").is_none());
+        assert!(container_for_port(&containers, 9999, "TCP").is_none());
+    }
+
+    #[test]
+    fn test_merge_containers_adds_ports_without_a_proxy() {
+        let mut web = published("web", 8080, "tcp");
+        // The same port again on IPv6
+        web.ports.push(PortMapping {
+            host_ip: Some("::".to_string()),
+            ..web.ports[0].clone()
+        });
+        let containers = vec![web, published("dns", 5353, "udp")];
+        let mut ports = vec![PortInfo::test_port(3000, 77), proxy_port(5353)];
+        ports[1].protocol = "UDP".to_string();
+
+        merge_containers(&mut ports, &containers);
+
+        let summary: Vec<_> = ports
+            .iter()
+            .map(|p| (p.port, p.pid, p.container.as_ref().map(|c| c.name.as_str())))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (3000, 77, None),
+                (5353, 4242, Some("dns")),
+                (8080, 0, Some("web"))
+            ]
+        );
+        assert_eq!(ports[2].local_address, "0.0.0.0:8080");
+        assert!(ports[2].is_docker());
+    }
+
+    #[test]
+    fn test_container_ref_replaces_proxy_name() {
+        let mut info = proxy_port(8080);
+        assert!(info.is_docker());
+        assert_eq!(info.display_name(), "docker-proxy");
+
+        info.container = Some(published("web", 8080, "tcp").container_ref());
+        assert_eq!(info.display_name(), "web");
+    }
+
+    fn bound_to(image: &str, host_ip: Option<&str>) -> ContainerInfo {
+        ContainerInfo {
+            id: "abc123".to_string(),
+            name: "svc".to_string(),
+            image: image.to_string(),
+            status: "Up".to_string(),
+            compose: None,
+            ports: vec![PortMapping {
+                host_ip: host_ip.map(str::to_string),
+                host_port: Some(5432),
+                container_port: 5432,
+                protocol: "tcp".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_exposure_from_bind_ip() {
+        assert!(bound_to("app", Some("127.0.0.1")).is_localhost_only());
+        assert!(bound_to("app", Some("::1")).is_localhost_only());
+        assert_eq!(
+            bound_to("app", Some("192.168.1.10")).exposure(),
+            Exposure::Interface
+        );
+        assert_eq!(
+            bound_to("app", Some("0.0.0.0")).exposure(),
+            Exposure::AllInterfaces
+        );
+        // Unknown bind address is assumed public for safety
+        assert_eq!(bound_to("app", None).exposure(), Exposure::AllInterfaces);
+    }
+
+    #[test]
+    fn test_exposure_is_widest_mapping() {
+        let mut container = bound_to("app", Some("127.0.0.1"));
+        container.ports.push(PortMapping {
+            host_ip: Some("::".to_string()),
+            host_port: Some(8080),
+            container_port: 80,
+            protocol: "tcp".to_string(),
+        });
+        assert_eq!(container.exposure(), Exposure::AllInterfaces);
+        assert!(!container.is_localhost_only());
+    }
+
+    #[test]
+    fn test_container_risk_uses_exposure() {
+        assert_eq!(
+            container_risk(&bound_to("postgres:15", Some("0.0.0.0"))),
+            RiskLevel::Critical
+        );
+        assert_eq!(
+            container_risk(&bound_to("postgres:15", Some("127.0.0.1"))),
+            RiskLevel::High
+        );
+        assert_eq!(
+            container_risk(&bound_to("node:20", Some("0.0.0.0"))),
+            RiskLevel::High
+        );
+        assert_eq!(
+            container_risk(&bound_to("node:20", Some("127.0.0.1"))),
+            RiskLevel::Low
+        );
+    }
+
+    #[test]
+    fn test_published_ports_format() {
+        assert_eq!(
+            bound_to("app", Some("127.0.0.1")).published_ports(),
+            vec!["127.0.0.1:5432->5432/tcp"]
+        );
+        assert_eq!(
+            bound_to("app", Some("::")).published_ports(),
+            vec!["[::]:5432->5432/tcp"]
+        );
+    }
+
+    #[test]
+    fn test_fill_bind_ips_from_host_config() {
+        use bollard::models::PortBinding;
+        use std::collections::HashMap;
+
+        let mut container = bound_to("app", None);
+        let mut bindings = HashMap::new();
+        bindings.insert(
+            "5432/tcp".to_string(),
+            Some(vec![PortBinding {
+                host_ip: Some("127.0.0.1".to_string()),
+                host_port: Some("5432".to_string()),
+            }]),
+        );
+
+        fill_bind_ips(&mut container.ports, &bindings);
+        assert_eq!(container.ports[0].host_ip.as_deref(), Some("127.0.0.1"));
+    }
+
+    /// Empty scratch directory standing in for $HOME and /
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("portr-endpoint-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn search_in(dir: &std::path::Path) -> EndpointSearch {
+        EndpointSearch {
+            config_dir: Some(dir.join(".docker")),
+            home: Some(dir.to_path_buf()),
+            runtime_dir: Some(dir.join("run")),
+            root: dir.join("root"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_endpoint_docker_host_wins() {
+        let dir = scratch_dir("host");
+        let search = EndpointSearch {
+            docker_host: Some("tcp://10.0.0.5:2375".to_string()),
+            ..search_in(&dir)
+        };
+
+        let endpoint = search.resolve().unwrap();
+        assert_eq!(endpoint.host, "tcp://10.0.0.5:2375");
+        assert_eq!(endpoint.source, EndpointSource::DockerHost);
+        assert!(endpoint.socket_path().is_none());
+    }
+
+    #[test]
+    fn test_endpoint_from_current_context() {
+        let dir = scratch_dir("context");
+        let docker = dir.join(".docker");
+        let meta = docker.join("contexts/meta/0a1b2c");
+        std::fs::create_dir_all(&meta).unwrap();
+        std::fs::write(
+            docker.join("config.json"),
+            r#"{"currentContext": "colima"}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            meta.join("meta.json"),
+            r#"{"Name":"colima","Endpoints":{"docker":{"Host":"unix:///tmp/colima.sock"}}}"#,
+        )
+        .unwrap();
+
+        let endpoint = search_in(&dir).resolve().unwrap();
+        assert_eq!(endpoint.host, "unix:///tmp/colima.sock");
+        assert_eq!(
+            endpoint.source,
+            EndpointSource::Context("colima".to_string())
+        );
+    }
+
+    #[test]
+    fn test_endpoint_finds_rootless_and_podman_sockets() {
+        let dir = scratch_dir("sockets");
+        assert_eq!(search_in(&dir).resolve(), None);
+
+        std::fs::create_dir_all(dir.join("run/podman")).unwrap();
+        std::fs::write(dir.join("run/podman/podman.sock"), "").unwrap();
+        let endpoint = search_in(&dir).resolve().unwrap();
+        assert_eq!(endpoint.source, EndpointSource::Podman);
+
+        // Rootless Docker is preferred over Podman when both exist
+        std::fs::write(dir.join("run/docker.sock"), "").unwrap();
+        let endpoint = search_in(&dir).resolve().unwrap();
+        assert_eq!(endpoint.source, EndpointSource::Rootless);
+        assert_eq!(endpoint.socket_path(), Some(dir.join("run/docker.sock")));
+    }
+
+    #[test]
+    fn test_in_compose_filters() {
+        let mut container = published("shop-api-1", 8081, "tcp");
+        assert!(!container.in_compose(None, None));
+
+        container.compose = Some(ComposeService {
+            project: "shop".to_string(),
+            service: "api".to_string(),
+        });
+        assert!(container.in_compose(Some("shop"), None));
+        assert!(container.in_compose(None, Some("api")));
+        assert!(container.in_compose(Some("shop"), Some("api")));
+        assert!(!container.in_compose(Some("blog"), Some("api")));
+        assert_eq!(container.display_name(), "shop/api");
+        assert_eq!(container.container_ref().label(), "shop/api");
+    }
 }
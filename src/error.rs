@@ -40,6 +40,18 @@ pub enum PortrError {
 
     #[error("System error: {0}")]
     SystemError(String),
+
+    #[error("config error: {0}")]
+    ConfigError(String),
+
+    #[error("invalid query: {0}")]
+    InvalidQuery(String),
+
+    #[error("invalid column: {0}")]
+    InvalidColumn(String),
+
+    #[error("invalid sort: {0}")]
+    InvalidSort(String),
 }
 
 impl From<std::io::Error> for PortrError {
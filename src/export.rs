@@ -1,5 +1,8 @@
 //! Export formatting for JSON, CSV, and Markdown
 
+use crate::columns::Column;
+#[cfg(feature = "docker")]
+use crate::docker::ContainerPortRow;
 use crate::error::PortrError;
 use crate::port::PortInfo;
 use serde::Serialize;
@@ -17,29 +20,84 @@ pub fn to_json<T: Serialize>(data: &T) -> Result<String, PortrError> {
     serde_json::to_string_pretty(data).map_err(|e| PortrError::ExportError(e.to_string()))
 }
 
+/// Columns of the CSV export when none are chosen
+const CSV_COLUMNS: &[Column] = &[
+    Column::Port,
+    Column::Proto,
+    Column::Pid,
+    Column::Proc,
+    Column::User,
+    Column::State,
+    Column::Addr,
+    Column::Mem,
+    Column::Cpu,
+    Column::Uptime,
+    Column::Container,
+];
+
+/// Columns of the Markdown export when none are chosen
+const MARKDOWN_COLUMNS: &[Column] = &[
+    Column::Port,
+    Column::Proto,
+    Column::Pid,
+    Column::Proc,
+    Column::User,
+    Column::State,
+    Column::Mem,
+    Column::Cpu,
+    Column::Uptime,
+];
+
+/// A port serialized as just the chosen columns, in their order
+struct ColumnRow<'a> {
+    port: &'a PortInfo,
+    columns: &'a [Column],
+}
+
+impl Serialize for ColumnRow<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.columns.len()))?;
+        for column in self.columns {
+            map.serialize_entry(column.field(), &column.json(self.port))?;
+        }
+        map.end()
+    }
+}
+
+/// Export ports to JSON, keeping only `columns` when given
+pub fn ports_to_json(ports: &[PortInfo], columns: Option<&[Column]>) -> Result<String, PortrError> {
+    let Some(columns) = columns else {
+        return to_json(&ports);
+    };
+    let rows: Vec<ColumnRow> = ports
+        .iter()
+        .map(|port| ColumnRow { port, columns })
+        .collect();
+    to_json(&rows)
+}
+
 /// Export ports to CSV format
 pub fn to_csv(ports: &[PortInfo]) -> String {
+    to_csv_with(ports, None)
+}
+
+/// Export ports to CSV with the given columns, or the default set
+pub fn to_csv_with(ports: &[PortInfo], columns: Option<&[Column]>) -> String {
+    let columns = columns.unwrap_or(CSV_COLUMNS);
     let mut csv = String::new();
 
     // Header
-    csv.push_str(
-        "port,protocol,pid,process,state,local_address,memory_mb,cpu_percent,uptime_secs\n",
-    );
+    let header: Vec<&str> = columns.iter().map(Column::field).collect();
+    csv.push_str(&header.join(","));
+    csv.push('\n');
 
     // Rows
     for p in ports {
-        csv.push_str(&format!(
-            "{},{},{},{},{},{},{:.1},{:.1},{}\n",
-            p.port,
-            p.protocol,
-            p.pid,
-            escape_csv(&p.process_name),
-            p.state,
-            p.local_address,
-            p.memory_mb,
-            p.cpu_percent,
-            p.uptime_secs
-        ));
+        let row: Vec<String> = columns.iter().map(|c| escape_csv(&c.raw(p))).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
     }
 
     csv
@@ -52,6 +110,12 @@ pub fn port_to_csv(port: &PortInfo) -> String {
 
 /// Export ports to Markdown table format
 pub fn to_markdown(ports: &[PortInfo]) -> String {
+    to_markdown_with(ports, None)
+}
+
+/// Export ports to a Markdown table with the given columns, or the default set
+pub fn to_markdown_with(ports: &[PortInfo], columns: Option<&[Column]>) -> String {
+    let columns = columns.unwrap_or(MARKDOWN_COLUMNS);
     let mut md = String::new();
 
     // Title
@@ -59,22 +123,15 @@ pub fn to_markdown(ports: &[PortInfo]) -> String {
     md.push_str(&format!("**Total ports:** {}\n\n", ports.len()));
 
     // Table header
-    md.push_str("| Port | Protocol | PID | Process | State | Memory | CPU | Uptime |\n");
-    md.push_str("|------|----------|-----|---------|-------|--------|-----|--------|\n");
+    let titles: Vec<&str> = columns.iter().map(Column::title).collect();
+    let rules: Vec<String> = titles.iter().map(|t| "-".repeat(t.len() + 2)).collect();
+    md.push_str(&format!("| {} |\n", titles.join(" | ")));
+    md.push_str(&format!("|{}|\n", rules.join("|")));
 
     // Rows
     for p in ports {
-        md.push_str(&format!(
-            "| {} | {} | {} | {} | {} | {:.1} MB | {:.1}% | {} |\n",
-            p.port,
-            p.protocol,
-            p.pid,
-            escape_md(&p.process_name),
-            p.state,
-            p.memory_mb,
-            p.cpu_percent,
-            p.uptime_display()
-        ));
+        let cells: Vec<String> = columns.iter().map(|c| escape_md(&c.display(p))).collect();
+        md.push_str(&format!("| {} |\n", cells.join(" | ")));
     }
 
     md.push_str("\n---\n");
@@ -94,6 +151,25 @@ pub fn port_to_markdown(port: &PortInfo) -> String {
     md.push_str(&format!("- **Protocol:** {}\n", port.protocol));
     md.push_str(&format!("- **PID:** {}\n", port.pid));
     md.push_str(&format!("- **Process:** {}\n", port.process_name));
+    if let Some(ref container) = port.container {
+        md.push_str(&format!(
+            "- **Container:** {} (`{}`, {})\n",
+            container.name, container.id, container.image
+        ));
+        if let Some(ref compose) = container.compose {
+            md.push_str(&format!("- **Compose:** {}\n", compose));
+        }
+    }
+    if let Some(ref forward) = port.kube_forward {
+        md.push_str(&format!("- **Port-forward:** {}\n", forward));
+    }
+    if let Some(ref tunnel) = port.tunnel {
+        md.push_str(&format!(
+            "- **SSH tunnel:** `{}` {}\n",
+            tunnel.kind.flag(),
+            tunnel
+        ));
+    }
     md.push_str(&format!("- **State:** {}\n", port.state));
     md.push_str(&format!("- **Local Address:** {}\n", port.local_address));
 
@@ -113,6 +189,90 @@ pub fn port_to_markdown(port: &PortInfo) -> String {
         md.push_str(&format!("- **User:** {}\n", user));
     }
 
+    if let Some(ref euser) = port.effective_user {
+        md.push_str(&format!("- **Effective User:** {}\n", euser));
+    }
+
+    if let Some(ref group) = port.group {
+        md.push_str(&format!("- **Group:** {}\n", group));
+    }
+
+    if let Some(ref egroup) = port.effective_group {
+        md.push_str(&format!("- **Effective Group:** {}\n", egroup));
+    }
+
+    md.push_str("\n---\n");
+    md.push_str("*Generated by [portr](https://github.com/Brutus1066/portr) - 🐸 LazyFrog | kindware.dev*\n");
+
+    md
+}
+
+/// Export container port mappings to CSV format
+#[cfg(feature = "docker")]
+pub fn containers_to_csv(rows: &[ContainerPortRow]) -> String {
+    let mut csv = String::new();
+
+    csv.push_str(
+        "container,id,image,status,host_ip,host_port,container_port,protocol,critical,compose_project,compose_service\n",
+    );
+
+    for r in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{},{}\n",
+            escape_csv(&r.container),
+            r.id,
+            escape_csv(&r.image),
+            escape_csv(&r.status),
+            r.host_ip.as_deref().unwrap_or(""),
+            r.host_port.map(|p| p.to_string()).unwrap_or_default(),
+            r.container_port.map(|p| p.to_string()).unwrap_or_default(),
+            r.protocol.as_deref().unwrap_or(""),
+            r.critical,
+            escape_csv(r.compose_project.as_deref().unwrap_or("")),
+            escape_csv(r.compose_service.as_deref().unwrap_or(""))
+        ));
+    }
+
+    csv
+}
+
+/// Export container port mappings to Markdown table format
+#[cfg(feature = "docker")]
+pub fn containers_to_markdown(rows: &[ContainerPortRow]) -> String {
+    let mut md = String::new();
+
+    md.push_str("# Docker Port Report\n\n");
+    md.push_str("| Service | Container | Image | Status | Host | Container Port | Critical |\n");
+    md.push_str("|---------|-----------|-------|--------|------|----------------|----------|\n");
+
+    for r in rows {
+        let host = match (r.host_ip.as_deref(), r.host_port) {
+            (Some(ip), Some(port)) => format!("{}:{}", ip, port),
+            (None, Some(port)) => port.to_string(),
+            _ => "-".to_string(),
+        };
+        let target = match (r.container_port, r.protocol.as_deref()) {
+            (Some(port), Some(proto)) => format!("{}/{}", port, proto),
+            _ => "-".to_string(),
+        };
+
+        let service = match (r.compose_project.as_deref(), r.compose_service.as_deref()) {
+            (Some(project), Some(service)) => format!("{}/{}", project, service),
+            _ => "-".to_string(),
+        };
+
+        md.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} |\n",
+            escape_md(&service),
+            escape_md(&r.container),
+            escape_md(&r.image),
+            escape_md(&r.status),
+            host,
+            target,
+            if r.critical { "⚠ yes" } else { "no" }
+        ));
+    }
+
     md.push_str("\n---\n");
     md.push_str("*Generated by [portr](https://github.com/Brutus1066/portr) - 🐸 LazyFrog | kindware.dev*\n");
 
@@ -141,20 +301,17 @@ mod tests {
 
     fn sample_port() -> PortInfo {
         PortInfo {
-            port: 3000,
-            protocol: "TCP".to_string(),
-            pid: 1234,
-            process_name: "node".to_string(),
             process_path: Some("/usr/bin/node".to_string()),
             local_address: "0.0.0.0:3000".to_string(),
-            remote_address: None,
             state: "LISTENING".to_string(),
             user: Some("user".to_string()),
+            effective_user: Some("user".to_string()),
+            group: Some("staff".to_string()),
+            effective_group: Some("wheel".to_string()),
             memory_mb: 156.3,
             cpu_percent: 2.4,
             uptime_secs: 12345,
-            parent_pid: None,
-            parent_name: None,
+            ..PortInfo::test_port(3000, 1234)
         }
     }
 
@@ -163,7 +320,7 @@ mod tests {
         let ports = vec![sample_port()];
         let csv = to_csv(&ports);
         assert!(csv.contains("port,protocol,pid"));
-        assert!(csv.contains("3000,TCP,1234"));
+        assert!(csv.contains("3000,TCP,1234,node,user"));
     }
 
     #[test]
@@ -175,6 +332,39 @@ mod tests {
         assert!(md.contains("portr"));
     }
 
+    #[test]
+    fn test_single_port_exports_owner() {
+        let port = sample_port();
+        let md = port_to_markdown(&port);
+        assert!(md.contains("- **Group:** staff\n"));
+        assert!(md.contains("- **Effective Group:** wheel\n"));
+
+        let json = to_json(&port).unwrap();
+        assert!(json.contains("\"effective_user\": \"user\""));
+        assert!(json.contains("\"effective_group\": \"wheel\""));
+    }
+
+    #[test]
+    fn test_exports_with_columns() {
+        let ports = vec![sample_port()];
+        let columns = [Column::Port, Column::Mem, Column::Proc];
+
+        let csv = to_csv_with(&ports, Some(&columns));
+        assert_eq!(csv, "port,memory_mb,process\n3000,156.3,node\n");
+
+        let md = to_markdown_with(&ports, Some(&columns));
+        assert!(md.contains("| Port | Memory | Process |\n|------|--------|---------|\n"));
+        assert!(md.contains("| 3000 | 156.3 MB | node |"));
+
+        // Keys follow the column order
+        let json = ports_to_json(&ports, Some(&columns)).unwrap();
+        let compact: String = json.split_whitespace().collect();
+        assert_eq!(
+            compact,
+            r#"[{"port":3000,"memory_mb":156.3,"process":"node"}]"#
+        );
+    }
+
     #[test]
     fn test_csv_escape() {
         assert_eq!(escape_csv("hello"), "hello");
@@ -0,0 +1,620 @@
+//! Persistent port history
+//!
+//! An opt-in, append-only log of bind/unbind events under the data dir.
+//! `portr watch` and the dashboard feed snapshots into a [`HistoryRecorder`],
+//! which writes one JSON line per change; `portr history` reads them back.
+//! [`SnapshotDiff`] finds the changes, here and for `portr watch --events`.
+
+use crate::config::{self, HistorySettings};
+use crate::error::PortrError;
+use crate::port::PortInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// File name of the history log inside the data dir
+pub const HISTORY_FILE: &str = "history.jsonl";
+
+/// What happened to a port
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryKind {
+    /// A process started listening
+    Bind,
+    /// The listener went away
+    Unbind,
+    /// A different process took over the port between two scans
+    OwnerChange,
+    /// Same process, different socket state
+    StateChange,
+}
+
+impl HistoryKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            HistoryKind::Bind => "bind",
+            HistoryKind::Unbind => "unbind",
+            HistoryKind::OwnerChange => "owner_change",
+            HistoryKind::StateChange => "state_change",
+        }
+    }
+}
+
+/// One change to a listener
+///
+/// The same type, and the same JSON, is written to the history log, streamed
+/// by the daemon and printed by `portr watch --events`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEvent {
+    /// Unix time in seconds
+    pub timestamp: i64,
+    pub event: HistoryKind,
+    pub port: u16,
+    pub protocol: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub local_address: String,
+    pub pid: u32,
+    pub process: String,
+    /// Command line of the process, space-joined
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// Socket state, e.g. `LISTEN`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub state: String,
+    /// Previous owner's PID (`owner_change` only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_pid: Option<u32>,
+    /// Previous owner's process (`owner_change` only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_process: Option<String>,
+    /// State before a `state_change`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_state: Option<String>,
+}
+
+impl HistoryEvent {
+    fn from_port(kind: HistoryKind, info: &PortInfo, timestamp: i64) -> Self {
+        Self {
+            timestamp,
+            event: kind,
+            port: info.port,
+            protocol: info.protocol.clone(),
+            local_address: info.local_address.clone(),
+            pid: info.pid,
+            process: info.display_name().into_owned(),
+            command: info.command.join(" "),
+            user: info.user.clone(),
+            state: info.state.clone(),
+            previous_pid: None,
+            previous_process: None,
+            previous_state: None,
+        }
+    }
+
+    /// Local time of the event, e.g. "2024-05-02 14:03:11"
+    pub fn time_display(&self) -> String {
+        chrono::DateTime::from_timestamp(self.timestamp, 0)
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_else(|| self.timestamp.to_string())
+    }
+
+    fn key(&self) -> (u16, String) {
+        (self.port, self.protocol.clone())
+    }
+}
+
+/// Path of the history log
+pub fn history_path() -> Option<PathBuf> {
+    config::data_dir().map(|dir| dir.join(HISTORY_FILE))
+}
+
+/// Current Unix time in seconds
+pub fn unix_now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// Read all events from a history log, skipping lines that don't parse
+///
+/// A missing file is an empty history.
+pub fn read_events(path: &Path) -> Result<Vec<HistoryEvent>, PortrError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(parse_events(&content))
+}
+
+fn parse_events(content: &str) -> Vec<HistoryEvent> {
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Events within the retention limits, as of `now`
+///
+/// Only the recorder rewrites the log; readers filter with this instead.
+pub fn retained(
+    mut events: Vec<HistoryEvent>,
+    settings: &HistorySettings,
+    now: i64,
+) -> Vec<HistoryEvent> {
+    let cutoff = now - i64::from(settings.retention_days) * 86400;
+    events.retain(|e| e.timestamp >= cutoff);
+    if events.len() > settings.max_events {
+        events.drain(..events.len() - settings.max_events);
+    }
+    events
+}
+
+/// Update the set of bound listeners with one event
+fn apply(bound: &mut HashMap<(u16, String), HistoryEvent>, event: HistoryEvent) {
+    match event.event {
+        HistoryKind::Bind | HistoryKind::OwnerChange | HistoryKind::StateChange => {
+            bound.insert(event.key(), event);
+        }
+        HistoryKind::Unbind => {
+            bound.remove(&event.key());
+        }
+    }
+}
+
+/// Diffs port snapshots into [`HistoryEvent`]s
+///
+/// Listeners are tracked per (port, protocol), so the IPv4 and IPv6 sockets
+/// of one server count once.
+#[derive(Debug, Default)]
+pub struct SnapshotDiff {
+    /// Listeners bound as of the last snapshot; `None` before the first
+    bound: Option<HashMap<(u16, String), HistoryEvent>>,
+}
+
+impl SnapshotDiff {
+    /// Diff whose first snapshot is the baseline and produces no events
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Diff starting from the listeners a history log left bound
+    pub fn replay(events: impl IntoIterator<Item = HistoryEvent>) -> Self {
+        let mut bound = HashMap::new();
+        for event in events {
+            apply(&mut bound, event);
+        }
+        Self { bound: Some(bound) }
+    }
+
+    /// Compare `ports` with the previous snapshot and remember them
+    pub fn update(&mut self, ports: &[PortInfo], timestamp: i64) -> Vec<HistoryEvent> {
+        let Some(bound) = self.bound.as_mut() else {
+            let mut bound = HashMap::new();
+            for info in ports {
+                bound
+                    .entry((info.port, info.protocol.clone()))
+                    .or_insert_with(|| HistoryEvent::from_port(HistoryKind::Bind, info, timestamp));
+            }
+            self.bound = Some(bound);
+            return Vec::new();
+        };
+
+        let mut events = Vec::new();
+        let mut seen = HashSet::new();
+        for info in ports {
+            let key = (info.port, info.protocol.clone());
+            if !seen.insert(key.clone()) {
+                continue;
+            }
+            match bound.get(&key) {
+                None => events.push(HistoryEvent::from_port(HistoryKind::Bind, info, timestamp)),
+                Some(prev) if prev.pid != info.pid => events.push(HistoryEvent {
+                    previous_pid: Some(prev.pid),
+                    previous_process: Some(prev.process.clone()),
+                    ..HistoryEvent::from_port(HistoryKind::OwnerChange, info, timestamp)
+                }),
+                // Logs from before states were recorded have none to compare
+                Some(prev) if !prev.state.is_empty() && prev.state != info.state => {
+                    events.push(HistoryEvent {
+                        previous_state: Some(prev.state.clone()),
+                        ..HistoryEvent::from_port(HistoryKind::StateChange, info, timestamp)
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        let mut gone: Vec<&HistoryEvent> = bound
+            .iter()
+            .filter(|(key, _)| !seen.contains(*key))
+            .map(|(_, event)| event)
+            .collect();
+        gone.sort_by_key(|e| e.port);
+        let unbound: Vec<HistoryEvent> = gone
+            .into_iter()
+            .map(|prev| HistoryEvent {
+                timestamp,
+                event: HistoryKind::Unbind,
+                previous_pid: None,
+                previous_process: None,
+                previous_state: None,
+                ..prev.clone()
+            })
+            .collect();
+        events.extend(unbound);
+
+        for event in &events {
+            apply(bound, event.clone());
+        }
+        events
+    }
+}
+
+/// Events appended between two prunes of the log
+const PRUNE_EVERY: usize = 1000;
+
+/// Exclusive claim on writing the history log, released on drop
+///
+/// An advisory lock on a file next to the log, so `portr watch`, the
+/// dashboard and the daemon never append the same change twice or prune
+/// away each other's lines.
+struct WriterLock {
+    #[cfg(unix)]
+    _lock: nix::fcntl::Flock<File>,
+    #[cfg(windows)]
+    _file: File,
+}
+
+impl WriterLock {
+    /// Take the lock without waiting; `None` while another recorder holds it
+    #[cfg(unix)]
+    fn try_acquire(path: &Path) -> Result<Option<Self>, PortrError> {
+        use nix::errno::Errno;
+        use nix::fcntl::{Flock, FlockArg};
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        match Flock::lock(file, FlockArg::LockExclusiveNonblock) {
+            Ok(lock) => Ok(Some(Self { _lock: lock })),
+            Err((_, Errno::EWOULDBLOCK)) => Ok(None),
+            Err((_, errno)) => Err(std::io::Error::from(errno).into()),
+        }
+    }
+
+    /// Take the lock without waiting; `None` while another recorder holds it
+    #[cfg(windows)]
+    fn try_acquire(path: &Path) -> Result<Option<Self>, PortrError> {
+        use std::os::windows::fs::OpenOptionsExt;
+
+        /// ERROR_SHARING_VIOLATION: another handle has the file open
+        const SHARING_VIOLATION: i32 = 32;
+
+        // No sharing: the open handle is the lock
+        match OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .share_mode(0)
+            .open(path)
+        {
+            Ok(file) => Ok(Some(Self { _file: file })),
+            Err(e) if e.raw_os_error() == Some(SHARING_VIOLATION) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Diffs port snapshots and appends the changes to the history log
+///
+/// The log stays open for the recorder's lifetime and is pruned through
+/// the same handle, so a long-running daemon keeps it within its limits.
+/// Only one recorder writes at a time: the others still diff, so their
+/// callers see the changes, and take over once the writer exits.
+pub struct HistoryRecorder {
+    file: File,
+    lock_path: PathBuf,
+    /// Held while this recorder is the one writing the log
+    lock: Option<WriterLock>,
+    settings: HistorySettings,
+    /// Listeners as last recorded
+    diff: SnapshotDiff,
+    /// Events appended since the last prune
+    appended: usize,
+    prune_every: usize,
+}
+
+impl HistoryRecorder {
+    /// Open the recorder if history is enabled in the config
+    ///
+    /// `None` as well while `portr daemon` runs, since it already records
+    /// every change.
+    pub fn from_config(settings: &HistorySettings) -> Result<Option<Self>, PortrError> {
+        if !settings.enabled || crate::daemon::is_daemon_running() {
+            return Ok(None);
+        }
+        let path = history_path()
+            .ok_or_else(|| PortrError::IoError("could not determine data directory".into()))?;
+        Self::open(path, settings).map(Some)
+    }
+
+    /// Open (and prune) the log at `path`
+    ///
+    /// Replaying the log restores which listeners were bound when portr last
+    /// ran, so restarting doesn't record every port as a new bind.
+    pub fn open(path: PathBuf, settings: &HistorySettings) -> Result<Self, PortrError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+
+        let lock_path = path.with_extension("lock");
+        let mut recorder = Self {
+            file,
+            lock: WriterLock::try_acquire(&lock_path)?,
+            lock_path,
+            settings: settings.clone(),
+            diff: SnapshotDiff::new(),
+            appended: 0,
+            prune_every: PRUNE_EVERY,
+        };
+        let events = if recorder.lock.is_some() {
+            recorder.prune()?
+        } else {
+            parse_events(&recorder.read_all()?)
+        };
+        recorder.diff = SnapshotDiff::replay(events);
+        Ok(recorder)
+    }
+
+    /// Whether this recorder is the one writing the log
+    #[cfg(test)]
+    fn is_writer(&self) -> bool {
+        self.lock.is_some()
+    }
+
+    /// The whole log as it is on disk
+    fn read_all(&mut self) -> Result<String, PortrError> {
+        let mut content = String::new();
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.read_to_string(&mut content)?;
+        Ok(content)
+    }
+
+    /// Drop events past the retention limits, returning the ones kept
+    ///
+    /// Only called with the writer lock held. The log is truncated and
+    /// rewritten in place, so readers holding it open see the new content.
+    fn prune(&mut self) -> Result<Vec<HistoryEvent>, PortrError> {
+        let content = self.read_all()?;
+        let events = parse_events(&content);
+        let total = content.lines().count();
+        let kept = retained(events, &self.settings, unix_now());
+        if kept.len() != total {
+            let mut content = String::new();
+            for event in &kept {
+                content.push_str(&serde_json::to_string(event)?);
+                content.push('\n');
+            }
+            self.file.set_len(0)?;
+            self.file.write_all(content.as_bytes())?;
+        }
+
+        self.appended = 0;
+        Ok(kept)
+    }
+
+    /// Record a snapshot of listening ports, returning the events written
+    pub fn record(&mut self, ports: &[PortInfo]) -> Result<Vec<HistoryEvent>, PortrError> {
+        self.record_at(ports, unix_now())
+    }
+
+    fn record_at(
+        &mut self,
+        ports: &[PortInfo],
+        timestamp: i64,
+    ) -> Result<Vec<HistoryEvent>, PortrError> {
+        let events = self.diff.update(ports, timestamp);
+        if events.is_empty() {
+            return Ok(events);
+        }
+        if self.lock.is_none() {
+            self.lock = WriterLock::try_acquire(&self.lock_path)?;
+        }
+        // The recorder holding the lock writes these changes
+        if self.lock.is_none() {
+            return Ok(events);
+        }
+
+        let mut line = String::new();
+        for event in &events {
+            line.push_str(&serde_json::to_string(event)?);
+            line.push('\n');
+        }
+        self.file.write_all(line.as_bytes())?;
+
+        self.appended += events.len();
+        if self.appended >= self.prune_every {
+            self.prune()?;
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(port: u16, pid: u32) -> PortInfo {
+        PortInfo {
+            user: Some("dev".to_string()),
+            command: vec!["node".to_string(), "server.js".to_string()],
+            ..PortInfo::test_port(port, pid)
+        }
+    }
+
+    fn temp_log(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("portr-history-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join(HISTORY_FILE)
+    }
+
+    #[test]
+    fn test_record_bind_unbind_and_owner_change() {
+        let path = temp_log("record");
+        let settings = HistorySettings::default();
+        let mut recorder = HistoryRecorder::open(path.clone(), &settings).unwrap();
+
+        let now = unix_now();
+        let events = recorder.record_at(&[port(3000, 1)], now).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, HistoryKind::Bind);
+        assert_eq!(events[0].command, "node server.js");
+
+        // Unchanged snapshot writes nothing
+        assert!(recorder
+            .record_at(&[port(3000, 1)], now)
+            .unwrap()
+            .is_empty());
+
+        // New owner on the same port
+        let events = recorder.record_at(&[port(3000, 2)], now + 1).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, HistoryKind::OwnerChange);
+        assert_eq!((events[0].pid, events[0].previous_pid), (2, Some(1)));
+
+        let events = recorder.record_at(&[], now + 2).unwrap();
+        assert_eq!(events[0].event, HistoryKind::Unbind);
+        assert_eq!(events[0].pid, 2);
+        assert_eq!(read_events(&path).unwrap().len(), 3);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_diff_reports_each_kind_of_change() {
+        let listener = |port: u16, pid: u32, state: &str| PortInfo {
+            process_name: format!("proc{}", pid),
+            state: state.to_string(),
+            ..PortInfo::test_port(port, pid)
+        };
+        let mut diff = SnapshotDiff::new();
+        let baseline = [listener(3000, 1, "LISTEN"), listener(5432, 2, "LISTEN")];
+        assert!(diff.update(&baseline, 1).is_empty());
+
+        // Unchanged scan is quiet
+        assert!(diff.update(&baseline, 2).is_empty());
+
+        let events = diff.update(
+            &[
+                listener(3000, 9, "LISTEN"),
+                listener(5432, 2, "CLOSE_WAIT"),
+                listener(8080, 3, "LISTEN"),
+            ],
+            3,
+        );
+        let kinds: Vec<_> = events.iter().map(|e| (e.event, e.port)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (HistoryKind::OwnerChange, 3000),
+                (HistoryKind::StateChange, 5432),
+                (HistoryKind::Bind, 8080),
+            ]
+        );
+        assert_eq!(events[0].previous_pid, Some(1));
+        assert_eq!(events[0].previous_process.as_deref(), Some("proc1"));
+        assert_eq!(events[1].previous_state.as_deref(), Some("LISTEN"));
+
+        let events = diff.update(&[listener(8080, 3, "LISTEN")], 4);
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e.event == HistoryKind::Unbind));
+
+        let line = serde_json::to_string(&events[0]).unwrap();
+        assert!(line.starts_with(r#"{"timestamp":4,"event":"unbind""#));
+        assert!(!line.contains("previous_pid"));
+    }
+
+    #[test]
+    fn test_reopen_restores_bound_ports() {
+        let path = temp_log("reopen");
+        let settings = HistorySettings::default();
+        let mut recorder = HistoryRecorder::open(path.clone(), &settings).unwrap();
+        recorder.record(&[port(8080, 7)]).unwrap();
+
+        let mut reopened = HistoryRecorder::open(path.clone(), &settings).unwrap();
+        assert!(reopened.record(&[port(8080, 7)]).unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_recorder_prunes_as_it_appends() {
+        let path = temp_log("prune");
+        let settings = HistorySettings {
+            enabled: true,
+            retention_days: 1,
+            max_events: 2,
+        };
+        let mut recorder = HistoryRecorder::open(path.clone(), &settings).unwrap();
+        recorder.prune_every = 4;
+        let now = unix_now();
+        recorder.record_at(&[port(1, 1)], now - 3 * 86400).unwrap();
+        recorder.record_at(&[port(2, 2)], now - 10).unwrap();
+        assert_eq!(read_events(&path).unwrap().len(), 3);
+
+        // The fourth append prunes, and later appends land in the same file
+        recorder.record_at(&[port(3, 3)], now).unwrap();
+        let kept = read_events(&path).unwrap();
+        assert_eq!(kept.len(), 2);
+        assert!(kept.iter().all(|e| e.timestamp >= now - 86400));
+        recorder.record_at(&[], now).unwrap();
+        assert_eq!(read_events(&path).unwrap().len(), 3);
+
+        // Readers filter without touching the log
+        let events = read_events(&path).unwrap();
+        assert_eq!(retained(events, &settings, now).len(), 2);
+        assert_eq!(read_events(&path).unwrap().len(), 3);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_only_one_recorder_writes() {
+        let path = temp_log("writer");
+        let settings = HistorySettings::default();
+        let mut first = HistoryRecorder::open(path.clone(), &settings).unwrap();
+        let mut second = HistoryRecorder::open(path.clone(), &settings).unwrap();
+        assert!(first.is_writer());
+        assert!(!second.is_writer());
+
+        // Both see the bind, only the writer logs it
+        assert_eq!(first.record_at(&[port(3000, 1)], 10).unwrap().len(), 1);
+        assert_eq!(second.record_at(&[port(3000, 1)], 10).unwrap().len(), 1);
+        assert_eq!(read_events(&path).unwrap().len(), 1);
+
+        // Once the writer is gone the other one takes over
+        drop(first);
+        assert_eq!(second.record_at(&[], 20).unwrap().len(), 1);
+        assert!(second.is_writer());
+        let events = read_events(&path).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].event, HistoryKind::Unbind);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,194 @@
+//! Kubernetes port-forward detection
+//!
+//! `kubectl port-forward` listens on local ports but the process table only
+//! shows `kubectl`. Parsing its command line recovers the namespace, the
+//! forwarded resource and the remote port.
+
+use serde::{Deserialize, Serialize};
+
+/// A `kubectl port-forward` listener
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KubeForward {
+    /// Namespace from `-n`/`--namespace`; `None` means the context's default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// Forwarded resource as given, e.g. `svc/api` or `pod/web-0`
+    pub resource: String,
+    /// Port inside the cluster
+    pub remote_port: u16,
+    /// kubeconfig context from `--context`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+}
+
+impl KubeForward {
+    /// Note shown when killing the forward
+    pub fn kill_note(&self) -> String {
+        format!(
+            "kubectl port-forward to {} - only the local forward stops, the workload keeps running",
+            self
+        )
+    }
+}
+
+impl std::fmt::Display for KubeForward {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(ref ns) = self.namespace {
+            write!(f, "{}/", ns)?;
+        }
+        write!(f, "{}:{}", self.resource, self.remote_port)
+    }
+}
+
+/// Check whether a process name is the kubectl binary
+pub fn is_kubectl(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name == "kubectl" || name == "kubectl.exe"
+}
+
+/// Global and port-forward flags that take a separate value
+const FLAGS_WITH_VALUE: &[&str] = &[
+    "-n",
+    "--namespace",
+    "--context",
+    "--kubeconfig",
+    "--cluster",
+    "--user",
+    "-s",
+    "--server",
+    "--address",
+    "--pod-running-timeout",
+    "--request-timeout",
+    "-v",
+];
+
+/// Parse a kubectl command line and return the forward for `local_port`
+///
+/// Handles `kubectl [flags] port-forward [flags] TYPE/NAME [LOCAL:]REMOTE...`.
+/// When the local port was chosen by kubectl (`:80`) and cannot be matched,
+/// a single port spec is still attributed to the listener.
+pub fn parse_port_forward(cmd: &[String], local_port: u16) -> Option<KubeForward> {
+    let program = cmd.first()?;
+    let program = program.rsplit(['/', '\\']).next().unwrap_or(program);
+    if !is_kubectl(program) {
+        return None;
+    }
+
+    let mut namespace = None;
+    let mut context = None;
+    let mut positional = Vec::new();
+    let mut args = cmd[1..].iter();
+
+    while let Some(arg) = args.next() {
+        if let Some((flag, value)) = arg.split_once('=').filter(|_| arg.starts_with('-')) {
+            match flag {
+                "-n" | "--namespace" => namespace = Some(value.to_string()),
+                "--context" => context = Some(value.to_string()),
+                _ => {}
+            }
+        } else if FLAGS_WITH_VALUE.contains(&arg.as_str()) {
+            let value = args.next().cloned();
+            match arg.as_str() {
+                "-n" | "--namespace" => namespace = value,
+                "--context" => context = value,
+                _ => {}
+            }
+        } else if let Some(ns) = arg.strip_prefix("-n").filter(|ns| !ns.is_empty()) {
+            namespace = Some(ns.to_string());
+        } else if !arg.starts_with('-') {
+            positional.push(arg.as_str());
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    if positional.next()? != "port-forward" {
+        return None;
+    }
+    let resource = positional.next()?.to_string();
+
+    let specs: Vec<(Option<u16>, u16)> = positional.filter_map(parse_port_spec).collect();
+    let remote_port = specs
+        .iter()
+        .find(|(local, _)| *local == Some(local_port))
+        .or(match specs.as_slice() {
+            [only] => Some(only),
+            _ => None,
+        })
+        .map(|(_, remote)| *remote)?;
+
+    Some(KubeForward {
+        namespace,
+        resource,
+        remote_port,
+        context,
+    })
+}
+
+/// Parse `8080:80`, `80` (same port locally) or `:80` (random local port)
+fn parse_port_spec(spec: &str) -> Option<(Option<u16>, u16)> {
+    match spec.split_once(':') {
+        Some(("", remote)) => Some((None, remote.parse().ok()?)),
+        Some((local, remote)) => Some((Some(local.parse().ok()?), remote.parse().ok()?)),
+        None => {
+            let port = spec.parse().ok()?;
+            Some((Some(port), port))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cmd(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_service_forward() {
+        let fwd = parse_port_forward(
+            &cmd("kubectl port-forward -n shop svc/api 8080:80 9090:9090"),
+            8080,
+        )
+        .unwrap();
+        assert_eq!(fwd.namespace.as_deref(), Some("shop"));
+        assert_eq!(fwd.resource, "svc/api");
+        assert_eq!(fwd.remote_port, 80);
+        assert_eq!(fwd.to_string(), "shop/svc/api:80");
+
+        let metrics = parse_port_forward(
+            &cmd("kubectl port-forward -n shop svc/api 8080:80 9090:9090"),
+            9090,
+        )
+        .unwrap();
+        assert_eq!(metrics.remote_port, 9090);
+    }
+
+    #[test]
+    fn test_parse_global_flags_and_equals_forms() {
+        let fwd = parse_port_forward(
+            &cmd("/usr/local/bin/kubectl --context=kind-dev --namespace=db port-forward --address 0.0.0.0 pod/postgres-0 5432"),
+            5432,
+        )
+        .unwrap();
+        assert_eq!(fwd.namespace.as_deref(), Some("db"));
+        assert_eq!(fwd.context.as_deref(), Some("kind-dev"));
+        assert_eq!(fwd.resource, "pod/postgres-0");
+        assert_eq!(fwd.remote_port, 5432);
+    }
+
+    #[test]
+    fn test_parse_random_local_port() {
+        let fwd = parse_port_forward(&cmd("kubectl port-forward web-7d9f :8080"), 41234).unwrap();
+        assert_eq!(fwd.namespace, None);
+        assert_eq!(fwd.resource, "web-7d9f");
+        assert_eq!(fwd.remote_port, 8080);
+    }
+
+    #[test]
+    fn test_not_a_port_forward() {
+        assert!(parse_port_forward(&cmd("kubectl proxy --port 8001"), 8001).is_none());
+        assert!(parse_port_forward(&cmd("node server.js 8080"), 8080).is_none());
+        assert!(parse_port_forward(&cmd("kubectl port-forward svc/a 1:2 3:4"), 9999).is_none());
+    }
+}
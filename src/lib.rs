@@ -12,25 +12,39 @@
 //! portr 3000-3010    # Scan port range
 //! ```
 
+pub mod columns;
 pub mod config;
+pub mod daemon;
 pub mod display;
 #[cfg(feature = "docker")]
 pub mod docker;
 pub mod error;
 pub mod export;
+pub mod history;
 pub mod interactive;
+pub mod kube;
 pub mod port;
 pub mod process;
+pub mod query;
+pub mod sampler;
 pub mod services;
 pub mod tui;
+pub mod tunnel;
 
+pub use columns::*;
 pub use config::*;
+pub use daemon::*;
 pub use display::*;
 #[cfg(feature = "docker")]
 pub use docker::*;
 pub use error::*;
 pub use export::*;
+pub use history::*;
 pub use interactive::*;
+pub use kube::*;
 pub use port::*;
 pub use process::*;
+pub use query::*;
+pub use sampler::*;
 pub use services::*;
+pub use tunnel::*;
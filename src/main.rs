@@ -2,14 +2,20 @@
 //!
 //! Never google "kill process on port" again.
 
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use clap_complete::{generate, Shell};
 use colored::Colorize;
 #[cfg(feature = "docker")]
 use portr::docker;
-use portr::{config, display, export, interactive, port, process, services, tui, PortrError};
+use portr::{
+    config, daemon, display, export, history, interactive, port, process, services, tui, Column,
+    HistoryRecorder, PortrError, Query, SortKey, TableOptions,
+};
 use std::io;
 use std::process::ExitCode;
+use std::time::Duration;
 
 /// Lightning-fast port inspector and process killer
 #[derive(Parser)]
@@ -27,8 +33,18 @@ use std::process::ExitCode;
   portr 3000 --dry-run   Show what would be killed
   portr 3000-3010        Scan port range
   portr --tcp            Show only TCP ports
+  portr --user me        Show only your own ports
+  portr --where 'port:3000-3999 mem>200'
+                         Filter with a query
+  portr --tunnels        Show only SSH tunnels
+  portr --sample 500ms   Sample CPU usage over 500ms
+  portr --columns port,proc,mem,cmd --sort mem:desc
+                         Pick columns and sort order
   portr --csv            Export as CSV
   portr --md             Export as Markdown
+  portr docker ls        List container port mappings
+  portr 8080 -k --docker-action pause
+                         Pause the container on 8080 instead of stopping it
   portr completions bash Generate shell completions
 
 🐸 LazyFrog | kindware.dev")]
@@ -65,6 +81,42 @@ struct Cli {
     #[arg(long)]
     udp: bool,
 
+    /// Show only SSH tunnels (ssh -L/-R/-D listeners)
+    #[arg(long)]
+    tunnels: bool,
+
+    /// Show only ports owned by this user ("me" for the current user)
+    #[arg(long, value_name = "USER")]
+    user: Option<String>,
+
+    /// Show only ports matching a query (e.g. "port:3000-3999 proc:node !docker")
+    #[arg(long = "where", value_name = "QUERY", value_parser = parse_query)]
+    filter: Option<Query>,
+
+    /// Verbose output with extra details
+    #[arg(short, long)]
+    verbose: bool,
+
+    #[command(flatten)]
+    format: FormatArgs,
+
+    #[command(flatten)]
+    table: TableArgs,
+
+    /// Sample CPU usage over this interval before reporting (e.g., 200ms, 1s)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    sample: Option<Duration>,
+
+    #[command(flatten)]
+    docker: DockerArgs,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+/// `--json`, `--csv` and `--md`, for commands that export
+#[derive(Args, Default)]
+struct FormatArgs {
     /// Output as JSON
     #[arg(long, conflicts_with_all = ["csv", "md"])]
     json: bool,
@@ -76,13 +128,30 @@ struct Cli {
     /// Output as Markdown
     #[arg(long, conflicts_with_all = ["json", "csv"])]
     md: bool,
+}
 
-    /// Verbose output with extra details
-    #[arg(short, long)]
-    verbose: bool,
+/// `--columns` and `--sort`, for commands that print port tables
+#[derive(Args, Default)]
+struct TableArgs {
+    /// Columns to show and export, comma separated (e.g., port,proc,mem,cmd)
+    #[arg(long, value_name = "COLUMNS", value_parser = parse_columns)]
+    columns: Option<ColumnList>,
 
-    #[command(subcommand)]
-    command: Option<Commands>,
+    /// Sort by a column, optionally with :asc or :desc (e.g., mem:desc)
+    #[arg(long, value_name = "COLUMN", value_parser = parse_sort)]
+    sort: Option<SortKey>,
+}
+
+/// `--docker-action` and `--stop-timeout`, for commands that kill
+#[derive(Args)]
+struct DockerArgs {
+    /// What to do with a Docker container when killing its port
+    #[arg(long, value_enum, value_name = "ACTION", default_value = "stop")]
+    docker_action: DockerActionArg,
+
+    /// Seconds to wait for a container to stop gracefully [default: from config, 10]
+    #[arg(long, value_name = "SECS")]
+    stop_timeout: Option<u32>,
 }
 
 #[derive(Subcommand)]
@@ -96,14 +165,42 @@ enum Commands {
         /// Show only UDP connections
         #[arg(long)]
         udp: bool,
+
+        /// Show only SSH tunnels (ssh -L/-R/-D listeners)
+        #[arg(long)]
+        tunnels: bool,
+
+        /// Show only ports owned by this user ("me" for the current user)
+        #[arg(long, value_name = "USER")]
+        user: Option<String>,
+
+        /// Show only ports matching a query (e.g. "port:3000-3999 proc:node !docker")
+        #[arg(long = "where", value_name = "QUERY", value_parser = parse_query)]
+        filter: Option<Query>,
+
+        /// Sample CPU usage over this interval before reporting (e.g., 200ms, 1s)
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+        sample: Option<Duration>,
+
+        #[command(flatten)]
+        format: FormatArgs,
+
+        #[command(flatten)]
+        table: TableArgs,
     },
 
     /// Interactive TUI mode with keyboard navigation
-    Interactive,
+    Interactive {
+        #[command(flatten)]
+        table: TableArgs,
+    },
 
     /// Full-screen TUI dashboard (beautiful real-time view)
     #[command(alias = "tui")]
-    Dashboard,
+    Dashboard {
+        #[command(flatten)]
+        table: TableArgs,
+    },
 
     /// Watch ports in real-time
     Watch {
@@ -114,6 +211,32 @@ enum Commands {
         /// Refresh interval in seconds
         #[arg(short, long, default_value = "2")]
         interval: u64,
+
+        /// Show only ports owned by this user ("me" for the current user)
+        #[arg(long, value_name = "USER")]
+        user: Option<String>,
+
+        /// Print one JSON line per change instead of redrawing a table
+        #[arg(long)]
+        events: bool,
+
+        #[command(flatten)]
+        table: TableArgs,
+    },
+
+    /// Show recorded port history (enable with [history] in the config)
+    History {
+        /// Only show events for this port
+        #[arg(value_name = "PORT")]
+        port: Option<u16>,
+
+        /// Only show events newer than this (e.g., 30m, 2h, 7d)
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+        since: Option<Duration>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Find which process is using a port
@@ -121,12 +244,26 @@ enum Commands {
         /// Port number to find
         #[arg(value_name = "PORT")]
         port: u16,
+
+        /// Verbose output with extra details
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Sample CPU usage over this interval before reporting (e.g., 200ms, 1s)
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+        sample: Option<Duration>,
+
+        #[command(flatten)]
+        format: FormatArgs,
     },
 
     /// Kill process on a specific port
     Kill {
         /// Port numbers to kill
-        #[arg(value_name = "PORTS", required = true)]
+        #[arg(
+            value_name = "PORTS",
+            required_unless_present_any = ["compose_project", "compose_service"]
+        )]
         ports: Vec<u16>,
 
         /// Force kill without confirmation
@@ -140,6 +277,17 @@ enum Commands {
         /// Use SIGKILL instead of SIGTERM (Unix only)
         #[arg(long)]
         sigkill: bool,
+
+        /// Act on every published container of this docker compose project
+        #[arg(long, value_name = "PROJECT")]
+        compose_project: Option<String>,
+
+        /// Act on the containers of this docker compose service
+        #[arg(long, value_name = "SERVICE")]
+        compose_service: Option<String>,
+
+        #[command(flatten)]
+        docker: DockerArgs,
     },
 
     /// Generate shell completions
@@ -154,6 +302,149 @@ enum Commands {
         #[command(subcommand)]
         action: ConfigAction,
     },
+
+    /// Docker containers and their published ports (requires --features docker)
+    Docker {
+        /// Show which Docker endpoint portr talks to
+        #[arg(short, long, global = true)]
+        verbose: bool,
+
+        #[command(subcommand)]
+        action: DockerCommand,
+    },
+
+    /// Background daemon that records port events and serves snapshots
+    Daemon {
+        #[command(subcommand)]
+        action: Option<DaemonAction>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DaemonAction {
+    /// Run the daemon in the foreground (the default)
+    Run {
+        /// Scan interval in seconds
+        #[arg(short, long, default_value = "2")]
+        interval: u64,
+    },
+    /// Show whether a daemon is running
+    Status {
+        /// Output the snapshot as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Stream port events from the daemon as JSON lines
+    Events,
+}
+
+/// Container action for `--docker-action`
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum DockerActionArg {
+    /// docker stop
+    Stop,
+    /// docker restart
+    Restart,
+    /// docker pause
+    Pause,
+    /// docker rm -f
+    #[value(alias = "remove")]
+    Rm,
+}
+
+#[cfg(feature = "docker")]
+impl From<DockerActionArg> for docker::ContainerAction {
+    fn from(arg: DockerActionArg) -> Self {
+        match arg {
+            DockerActionArg::Stop => docker::ContainerAction::Stop,
+            DockerActionArg::Restart => docker::ContainerAction::Restart,
+            DockerActionArg::Pause => docker::ContainerAction::Pause,
+            DockerActionArg::Rm => docker::ContainerAction::Remove,
+        }
+    }
+}
+
+/// How to treat Docker containers found on a port
+#[derive(Clone, Copy)]
+#[cfg_attr(not(feature = "docker"), allow(dead_code))]
+struct DockerOptions {
+    action: DockerActionArg,
+    stop_timeout: u32,
+}
+
+#[derive(Subcommand)]
+enum DockerCommand {
+    /// List running containers with their port mappings
+    #[command(alias = "ps")]
+    Ls {
+        #[command(flatten)]
+        format: FormatArgs,
+    },
+    /// Stop a container by published port or name
+    Stop {
+        /// Host port the container publishes, or its name/ID
+        #[arg(value_name = "PORT|NAME")]
+        target: String,
+
+        /// Skip confirmation
+        #[arg(short, long)]
+        force: bool,
+
+        /// Dry run - show what would be stopped
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+
+        /// Seconds to wait for a graceful stop [default: from config, 10]
+        #[arg(long, value_name = "SECS")]
+        stop_timeout: Option<u32>,
+    },
+    /// Restart a container by published port or name
+    Restart {
+        /// Host port the container publishes, or its name/ID
+        #[arg(value_name = "PORT|NAME")]
+        target: String,
+
+        /// Skip confirmation
+        #[arg(short, long)]
+        force: bool,
+
+        /// Dry run - show what would be restarted
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+
+        /// Seconds to wait for a graceful stop [default: from config, 10]
+        #[arg(long, value_name = "SECS")]
+        stop_timeout: Option<u32>,
+    },
+    /// Pause a container by published port or name
+    Pause {
+        /// Host port the container publishes, or its name/ID
+        #[arg(value_name = "PORT|NAME")]
+        target: String,
+
+        /// Skip confirmation
+        #[arg(short, long)]
+        force: bool,
+
+        /// Dry run - show what would be paused
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+    /// Force-remove a container by published port or name
+    #[command(alias = "remove")]
+    Rm {
+        /// Host port the container publishes, or its name/ID
+        #[arg(value_name = "PORT|NAME")]
+        target: String,
+
+        /// Skip confirmation
+        #[arg(short, long)]
+        force: bool,
+
+        /// Dry run - show what would be removed
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -167,11 +458,17 @@ enum ConfigAction {
 }
 
 fn main() -> ExitCode {
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if let Err(msg) = hoist_options(&mut cli, &matches) {
+        Cli::command()
+            .error(ErrorKind::ArgumentConflict, msg)
+            .exit();
+    }
 
     // Launch interactive mode if requested
     if cli.interactive {
-        return match interactive::run_interactive() {
+        return match interactive::run_interactive(&table_options(&cli.table)) {
             Ok(_) => ExitCode::SUCCESS,
             Err(e) => {
                 eprintln!("{} {}", "error:".red().bold(), e);
@@ -181,7 +478,10 @@ fn main() -> ExitCode {
     }
 
     // Print banner for interactive commands (no output format, no subcommand, no ports)
-    if !cli.json && !cli.csv && !cli.md && cli.command.is_none() && cli.ports.is_empty() {
+    if matches!(cli.format.format(), OutputFormat::Pretty)
+        && cli.command.is_none()
+        && cli.ports.is_empty()
+    {
         display::print_banner();
     }
 
@@ -205,40 +505,215 @@ enum OutputFormat {
     Markdown,
 }
 
-fn get_output_format(cli: &Cli) -> OutputFormat {
-    if cli.json {
-        OutputFormat::Json
-    } else if cli.csv {
-        OutputFormat::Csv
-    } else if cli.md {
-        OutputFormat::Markdown
-    } else {
-        OutputFormat::Pretty
+impl FormatArgs {
+    fn format(&self) -> OutputFormat {
+        if self.json {
+            OutputFormat::Json
+        } else if self.csv {
+            OutputFormat::Csv
+        } else if self.md {
+            OutputFormat::Markdown
+        } else {
+            OutputFormat::Pretty
+        }
+    }
+}
+
+impl FormatArgs {
+    /// Take `top`'s format when none was given here
+    fn fill_from(&mut self, top: &mut FormatArgs) {
+        if matches!(self.format(), OutputFormat::Pretty) {
+            *self = std::mem::take(top);
+        }
     }
 }
 
+impl TableArgs {
+    /// Take `top`'s columns and sort where none were given here
+    fn fill_from(&mut self, top: &mut TableArgs) {
+        if self.columns.is_none() {
+            self.columns = top.columns.take();
+        }
+        if self.sort.is_none() {
+            self.sort = top.sort.take();
+        }
+    }
+}
+
+impl DockerArgs {
+    /// The chosen action, with the stop timeout falling back to the config
+    fn options(&self, app_config: &config::Config) -> DockerOptions {
+        DockerOptions {
+            action: self.docker_action,
+            stop_timeout: self.stop_timeout.unwrap_or(app_config.docker.stop_timeout),
+        }
+    }
+}
+
+/// Columns and sort order from the command line, else the config
+///
+/// Bad flags never get here (clap rejects them); a bad `[table]` entry only
+/// warns, so `portr config show` and friends keep working.
+/// Move options given before the subcommand onto it
+///
+/// Older releases took `portr --json list` and `portr -v find 22`, so the
+/// output, table, verbose and docker options still work in front of a
+/// subcommand that uses them. In front of any other subcommand they are an
+/// error rather than silently ignored.
+fn hoist_options(cli: &mut Cli, matches: &ArgMatches) -> Result<(), String> {
+    let Some(command) = cli.command.as_mut() else {
+        return Ok(());
+    };
+    let mut format = std::mem::take(&mut cli.format);
+    let mut table = std::mem::take(&mut cli.table);
+    let mut verbose = std::mem::take(&mut cli.verbose);
+    let mut docker_action = matches.value_source("docker_action") == Some(ValueSource::CommandLine);
+
+    match command {
+        Commands::List {
+            format: f,
+            table: t,
+            ..
+        } => {
+            f.fill_from(&mut format);
+            t.fill_from(&mut table);
+        }
+        Commands::Find {
+            verbose: v,
+            format: f,
+            ..
+        } => {
+            *v |= std::mem::take(&mut verbose);
+            f.fill_from(&mut format);
+        }
+        Commands::Interactive { table: t }
+        | Commands::Dashboard { table: t }
+        | Commands::Watch { table: t, .. } => t.fill_from(&mut table),
+        Commands::Kill { docker: d, .. } => {
+            if docker_action && d.docker_action == DockerActionArg::Stop {
+                d.docker_action = cli.docker.docker_action;
+            }
+            docker_action = false;
+            if d.stop_timeout.is_none() {
+                d.stop_timeout = cli.docker.stop_timeout.take();
+            }
+        }
+        Commands::Docker { verbose: v, action } => {
+            *v |= std::mem::take(&mut verbose);
+            if let DockerCommand::Ls { format: f } = action {
+                f.fill_from(&mut format);
+            }
+        }
+        _ => {}
+    }
+
+    let unused = [
+        (format.json, "--json"),
+        (format.csv, "--csv"),
+        (format.md, "--md"),
+        (table.columns.is_some(), "--columns"),
+        (table.sort.is_some(), "--sort"),
+        (verbose, "--verbose"),
+        (docker_action, "--docker-action"),
+        (cli.docker.stop_timeout.is_some(), "--stop-timeout"),
+    ];
+    match unused.iter().find(|(set, _)| *set) {
+        Some((_, flag)) => Err(format!(
+            "{} doesn't apply to `portr {}`",
+            flag,
+            matches.subcommand_name().unwrap_or_default()
+        )),
+        None => Ok(()),
+    }
+}
+
+fn table_options(args: &TableArgs) -> TableOptions {
+    let (table, errors) = TableOptions::resolve(
+        args.columns.clone(),
+        args.sort,
+        &config::load_config().table,
+    );
+    for e in errors {
+        eprintln!("{} {}; using the default", "warning:".yellow().bold(), e);
+    }
+    table
+}
+
 fn run(cli: Cli) -> Result<(), PortrError> {
-    let format = get_output_format(&cli);
     let app_config = config::load_config();
 
     // Handle subcommands first
     if let Some(cmd) = cli.command {
         return match cmd {
-            Commands::List { tcp, udp } => cmd_list(tcp, udp, format),
-            Commands::Interactive => interactive::run_interactive(),
-            Commands::Dashboard => tui::run_dashboard(),
-            Commands::Watch { port, interval } => cmd_watch(port, interval),
-            Commands::Find { port } => cmd_find(port, format, cli.verbose, false),
+            Commands::List {
+                tcp,
+                udp,
+                tunnels,
+                user,
+                filter,
+                sample,
+                format,
+                table,
+            } => cmd_list(
+                &ListFilter {
+                    tcp_only: tcp,
+                    udp_only: udp,
+                    tunnels_only: tunnels,
+                    user: user.as_deref(),
+                    query: filter.as_ref(),
+                },
+                format.format(),
+                &table_options(&table),
+                sample,
+            ),
+            Commands::Interactive { table } => interactive::run_interactive(&table_options(&table)),
+            Commands::Dashboard { table } => tui::run_dashboard(&table_options(&table)),
+            Commands::Watch {
+                port,
+                interval,
+                user,
+                events,
+                table,
+            } => cmd_watch(
+                port,
+                interval,
+                user.as_deref(),
+                events,
+                &table_options(&table),
+                &app_config.history,
+            ),
+            Commands::History { port, since, json } => {
+                cmd_history(port, since, json, &app_config.history)
+            }
+            Commands::Find {
+                port,
+                verbose,
+                sample,
+                format,
+            } => cmd_find(port, format.format(), verbose, false, sample),
             Commands::Kill {
                 ports,
                 force,
                 dry_run,
                 sigkill,
+                compose_project,
+                compose_service,
+                docker,
             } => {
+                let docker_opts = docker.options(&app_config);
                 // Force = true if --force flag OR confirm=false in config
                 let effective_force = force || !app_config.defaults.confirm;
+                if compose_project.is_some() || compose_service.is_some() {
+                    cmd_kill_compose(
+                        compose_project.as_deref(),
+                        compose_service.as_deref(),
+                        effective_force,
+                        dry_run,
+                        docker_opts,
+                    )?;
+                }
                 for port in ports {
-                    cmd_kill(port, effective_force, dry_run, sigkill)?;
+                    cmd_kill(port, effective_force, dry_run, sigkill, docker_opts)?;
                 }
                 Ok(())
             }
@@ -248,15 +723,24 @@ fn run(cli: Cli) -> Result<(), PortrError> {
                 Ok(())
             }
             Commands::Config { action } => cmd_config(action),
+            Commands::Daemon { action } => {
+                cmd_daemon(action.unwrap_or(DaemonAction::Run { interval: 2 }))
+            }
+            Commands::Docker { verbose, action } => {
+                cmd_docker(action, app_config.docker.stop_timeout, verbose)
+            }
         };
     }
 
+    let format = cli.format.format();
+    let docker_opts = cli.docker.options(&app_config);
+
     // Handle direct port arguments
     if !cli.ports.is_empty() {
         // Check if any port is a range
         for port_arg in &cli.ports {
             if port_arg.contains('-') {
-                return cmd_range(port_arg, format);
+                return cmd_range(port_arg, format, &table_options(&cli.table), cli.sample);
             }
         }
 
@@ -282,20 +766,37 @@ fn run(cli: Cli) -> Result<(), PortrError> {
             // Force = true if --force flag OR confirm=false in config
             let effective_force = cli.force || !app_config.defaults.confirm;
             for port in &ports {
-                cmd_kill(*port, effective_force, cli.dry_run, false)?;
+                cmd_kill(*port, effective_force, cli.dry_run, false, docker_opts)?;
             }
             return Ok(());
         }
 
         // Find info for each port
         if ports.len() == 1 {
-            return cmd_find(ports[0], format, cli.verbose, cli.tree);
+            return cmd_find(ports[0], format, cli.verbose, cli.tree, cli.sample);
         }
-        return cmd_find_multiple(&ports, format, cli.verbose);
+        return cmd_find_multiple(
+            &ports,
+            format,
+            &table_options(&cli.table),
+            cli.verbose,
+            cli.sample,
+        );
     }
 
     // Default: list all ports
-    cmd_list(cli.tcp, cli.udp, format)
+    cmd_list(
+        &ListFilter {
+            tcp_only: cli.tcp,
+            udp_only: cli.udp,
+            tunnels_only: cli.tunnels,
+            user: cli.user.as_deref(),
+            query: cli.filter.as_ref(),
+        },
+        format,
+        &table_options(&cli.table),
+        cli.sample,
+    )
 }
 
 /// Handle config subcommand
@@ -348,6 +849,36 @@ fn cmd_config(action: ConfigAction) -> Result<(), PortrError> {
                 }
             }
             println!();
+            println!("  {}", "[docker]".yellow());
+            println!("    stop_timeout = {}", cfg.docker.stop_timeout);
+            println!();
+            println!("  {}", "[history]".yellow());
+            println!("    enabled = {}", cfg.history.enabled);
+            println!("    retention_days = {}", cfg.history.retention_days);
+            println!("    max_events = {}", cfg.history.max_events);
+            println!();
+            println!("  {}", "[keys]".yellow());
+            if cfg.keys.is_empty() {
+                println!("    (defaults)");
+            } else {
+                for (action, keys) in &cfg.keys {
+                    println!("    {} = \"{}\"", action, keys);
+                }
+            }
+            println!();
+            println!("  {}", "[table]".yellow());
+            match (&cfg.table.columns, &cfg.table.sort) {
+                (None, None) => println!("    (defaults)"),
+                (columns, sort) => {
+                    if let Some(columns) = columns {
+                        println!("    columns = \"{}\"", columns);
+                    }
+                    if let Some(sort) = sort {
+                        println!("    sort = \"{}\"", sort);
+                    }
+                }
+            }
+            println!();
             println!("  {}", "[theme]".yellow());
             println!("    banner_color = \"{}\"", cfg.theme.banner_color);
             println!("    success_color = \"{}\"", cfg.theme.success_color);
@@ -358,22 +889,43 @@ fn cmd_config(action: ConfigAction) -> Result<(), PortrError> {
     Ok(())
 }
 
+/// Which ports `portr list` shows
+struct ListFilter<'a> {
+    tcp_only: bool,
+    udp_only: bool,
+    tunnels_only: bool,
+    user: Option<&'a str>,
+    query: Option<&'a Query>,
+}
+
+impl ListFilter<'_> {
+    fn matches(&self, p: &port::PortInfo) -> bool {
+        let proto_match = if self.tcp_only {
+            p.protocol == "TCP"
+        } else if self.udp_only {
+            p.protocol == "UDP"
+        } else {
+            true
+        };
+        proto_match
+            && (!self.tunnels_only || p.is_tunnel())
+            && self.user.is_none_or(|u| port::matches_user(p, u))
+            && self.query.is_none_or(|q| q.matches(p))
+    }
+}
+
 /// List all listening ports
-fn cmd_list(tcp_only: bool, udp_only: bool, format: OutputFormat) -> Result<(), PortrError> {
-    let ports = port::get_listening_ports()?;
+fn cmd_list(
+    filter: &ListFilter,
+    format: OutputFormat,
+    table: &TableOptions,
+    sample: Option<Duration>,
+) -> Result<(), PortrError> {
+    let ports = scan_ports(sample)?;
 
-    let filtered: Vec<_> = ports
-        .into_iter()
-        .filter(|p| {
-            if tcp_only {
-                p.protocol == "TCP"
-            } else if udp_only {
-                p.protocol == "UDP"
-            } else {
-                true
-            }
-        })
-        .collect();
+    let mut filtered: Vec<_> = ports.into_iter().filter(|p| filter.matches(p)).collect();
+    table.sort(&mut filtered);
+    let columns = table.columns.as_deref();
 
     if filtered.is_empty() {
         println!("{}", "No listening ports found.".dimmed());
@@ -382,16 +934,16 @@ fn cmd_list(tcp_only: bool, udp_only: bool, format: OutputFormat) -> Result<(),
 
     match format {
         OutputFormat::Json => {
-            println!("{}", export::to_json(&filtered)?);
+            println!("{}", export::ports_to_json(&filtered, columns)?);
         }
         OutputFormat::Csv => {
-            print!("{}", export::to_csv(&filtered));
+            print!("{}", export::to_csv_with(&filtered, columns));
         }
         OutputFormat::Markdown => {
-            print!("{}", export::to_markdown(&filtered));
+            print!("{}", export::to_markdown_with(&filtered, columns));
         }
         OutputFormat::Pretty => {
-            display::print_port_table(&filtered);
+            display::print_port_table_with(&filtered, columns);
         }
     }
 
@@ -404,8 +956,11 @@ fn cmd_find(
     format: OutputFormat,
     verbose: bool,
     show_tree: bool,
+    sample: Option<Duration>,
 ) -> Result<(), PortrError> {
-    let info = port::get_port_info(port)?;
+    // Keep the snapshot so the process tree doesn't rescan the system
+    let mut sampler = new_sampler(sample);
+    let info = port::get_port_info_with(&mut sampler, port)?;
 
     match info {
         Some(port_info) => {
@@ -425,10 +980,15 @@ fn cmd_find(
                     services::print_service_info(port);
                     // Show Docker container info if available
                     #[cfg(feature = "docker")]
-                    docker::print_container_info(port);
+                    {
+                        docker::print_container_info(port);
+                        if verbose {
+                            docker::print_endpoint();
+                        }
+                    }
                     // Show process tree if requested
                     if show_tree {
-                        port::print_process_tree(&port_info);
+                        port::print_process_tree_with(&sampler, &port_info);
                     }
                 }
             }
@@ -456,12 +1016,20 @@ fn cmd_find(
 }
 
 /// Find what's using multiple ports
-fn cmd_find_multiple(ports: &[u16], format: OutputFormat, verbose: bool) -> Result<(), PortrError> {
-    let all_ports = port::get_listening_ports()?;
-    let found: Vec<_> = all_ports
+fn cmd_find_multiple(
+    ports: &[u16],
+    format: OutputFormat,
+    table: &TableOptions,
+    verbose: bool,
+    sample: Option<Duration>,
+) -> Result<(), PortrError> {
+    let all_ports = scan_ports(sample)?;
+    let mut found: Vec<_> = all_ports
         .into_iter()
         .filter(|p| ports.contains(&p.port))
         .collect();
+    table.sort(&mut found);
+    let columns = table.columns.as_deref();
 
     if found.is_empty() {
         println!(
@@ -474,13 +1042,13 @@ fn cmd_find_multiple(ports: &[u16], format: OutputFormat, verbose: bool) -> Resu
 
     match format {
         OutputFormat::Json => {
-            println!("{}", export::to_json(&found)?);
+            println!("{}", export::ports_to_json(&found, columns)?);
         }
         OutputFormat::Csv => {
-            print!("{}", export::to_csv(&found));
+            print!("{}", export::to_csv_with(&found, columns));
         }
         OutputFormat::Markdown => {
-            print!("{}", export::to_markdown(&found));
+            print!("{}", export::to_markdown_with(&found, columns));
         }
         OutputFormat::Pretty => {
             for port_info in &found {
@@ -505,11 +1073,25 @@ fn cmd_find_multiple(ports: &[u16], format: OutputFormat, verbose: bool) -> Resu
 }
 
 /// Kill process on a port
-fn cmd_kill(port: u16, force: bool, dry_run: bool, sigkill: bool) -> Result<(), PortrError> {
+#[cfg_attr(not(feature = "docker"), allow(unused_variables))]
+fn cmd_kill(
+    port: u16,
+    force: bool,
+    dry_run: bool,
+    sigkill: bool,
+    docker_opts: DockerOptions,
+) -> Result<(), PortrError> {
     // Check if this port is used by a Docker container
     #[cfg(feature = "docker")]
     if let Some(container) = docker::get_container_for_port(port) {
-        return kill_docker_container(port, &container, force, dry_run);
+        return run_container_action(
+            &container,
+            Some(port),
+            docker_opts.action.into(),
+            docker_opts.stop_timeout,
+            force,
+            dry_run,
+        );
     }
 
     let info = port::get_port_info(port)?;
@@ -517,12 +1099,14 @@ fn cmd_kill(port: u16, force: bool, dry_run: bool, sigkill: bool) -> Result<(),
     match info {
         Some(port_info) => {
             // Check for critical services
-            let is_critical = services::requires_confirmation(port);
+            let is_critical = port_info.requires_confirmation();
 
             // Dry run mode - just show what would happen
             if dry_run {
                 let warning = if is_critical {
                     format!(" {}", services::get_warning(port).unwrap_or_default())
+                } else if let Some(note) = port_info.kill_note() {
+                    format!(" [{}: {}]", "Low Risk".green(), note)
                 } else {
                     String::new()
                 };
@@ -544,6 +1128,9 @@ fn cmd_kill(port: u16, force: bool, dry_run: bool, sigkill: bool) -> Result<(),
                 if is_critical {
                     services::print_service_info(port);
                 }
+                if let Some(note) = port_info.kill_note() {
+                    println!("\n  {} {}: {}", "●".green(), "Low Risk".green(), note);
+                }
 
                 println!();
 
@@ -575,61 +1162,63 @@ fn cmd_kill(port: u16, force: bool, dry_run: bool, sigkill: bool) -> Result<(),
     Ok(())
 }
 
-/// Kill a Docker container that's using a port
+/// Apply an action to a Docker container, with risk-based confirmation
 #[cfg(feature = "docker")]
-fn kill_docker_container(
-    port: u16,
+fn run_container_action(
     container: &docker::ContainerInfo,
+    port: Option<u16>,
+    action: docker::ContainerAction,
+    stop_timeout: u32,
     force: bool,
     dry_run: bool,
 ) -> Result<(), PortrError> {
-    use std::io::Write;
-
     let is_critical = docker::is_critical_container(container);
+    let exposure = container.exposure();
+    let risk = docker::container_risk(container);
+    let verb = action.verb();
+    let on_port = port
+        .map(|p| format!(" on port {}", p.to_string().cyan()))
+        .unwrap_or_default();
 
     // Dry run mode
     if dry_run {
         let warning = if is_critical {
             format!(" {}", "⚠ CRITICAL DATABASE CONTAINER".red().bold())
         } else {
-            String::new()
+            format!(" [{}]", risk.colored_label())
         };
         println!(
-            "{} Would stop Docker container: {} ({}) on port {}{}",
+            "{} Would {} Docker container: {} ({}){}{}",
             "🐳".blue().bold(),
+            verb,
             container.name.cyan().bold(),
             container.id.dimmed(),
-            port.to_string().cyan(),
+            on_port,
             warning
         );
         return Ok(());
     }
 
     // Show container info
-    println!(
-        "\n  {} Docker container detected on port {}:",
-        "🐳".blue().bold(),
-        port.to_string().cyan()
-    );
+    match port {
+        Some(p) => println!(
+            "\n  {} Docker container detected on port {}:",
+            "🐳".blue().bold(),
+            p.to_string().cyan()
+        ),
+        None => println!("\n  {} Docker container:", "🐳".blue().bold()),
+    }
     println!("     Name: {}", container.name.cyan().bold());
     println!("     ID: {}", container.id.dimmed());
     println!("     Image: {}", container.image);
     println!("     Status: {}", container.status.green());
 
     // Show all port mappings
-    if !container.ports.is_empty() {
-        let port_strs: Vec<String> = container
-            .ports
-            .iter()
-            .filter_map(|p| {
-                p.host_port
-                    .map(|hp| format!("{}:{}/{}", hp, p.container_port, p.protocol))
-            })
-            .collect();
-        if !port_strs.is_empty() {
-            println!("     Ports: {}", port_strs.join(", ").yellow());
-        }
+    let published = container.published_ports();
+    if !published.is_empty() {
+        println!("     Ports: {}", published.join(", ").yellow());
     }
+    println!("     Risk: {} ({})", risk.colored_label(), exposure.label());
 
     // Show critical warning
     if is_critical {
@@ -639,60 +1228,329 @@ fn kill_docker_container(
             "⚠".red().bold(),
             "CRITICAL DATABASE".red().bold()
         );
-        println!("    Stopping may cause {}", "DATA LOSS".red().bold());
+        println!("    {}", action.critical_warning().red());
     }
     println!();
 
-    if !force {
-        // Critical containers require typing "yes"
-        if is_critical {
-            print!(
-                "  {} Type '{}' to stop this container: ",
-                "?".red().bold(),
-                "yes".yellow()
-            );
-            std::io::stdout().flush().ok();
+    if !force && !confirm_container_action(risk, verb, "this container") {
+        return Ok(());
+    }
 
-            let mut input = String::new();
-            if std::io::stdin().read_line(&mut input).is_err() {
-                return Ok(());
-            }
+    // Act on the container by NAME (more stable than ID which can change)
+    docker::apply_container_action(&container.name, action, stop_timeout)?;
 
-            if input.trim() != "yes" {
-                println!("{}", "Cancelled. (Must type 'yes' exactly)".dimmed());
-                return Ok(());
-            }
-        } else {
-            print!("  {} Stop this container? [y/N]: ", "?".yellow().bold());
-            std::io::stdout().flush().ok();
+    println!(
+        "{} {} container {}{}",
+        "✓".green().bold(),
+        action.past_tense(),
+        container.name.cyan(),
+        on_port
+    );
 
-            let mut input = String::new();
-            if std::io::stdin().read_line(&mut input).is_err() {
-                return Ok(());
-            }
+    Ok(())
+}
 
-            if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
-                println!("{}", "Cancelled.".dimmed());
-                return Ok(());
-            }
+/// Ask before acting on containers; returns true to proceed
+///
+/// High-risk containers (critical images or widely exposed) require typing "yes".
+#[cfg(feature = "docker")]
+fn confirm_container_action(risk: services::RiskLevel, verb: &str, what: &str) -> bool {
+    use std::io::Write;
+
+    if matches!(
+        risk,
+        services::RiskLevel::High | services::RiskLevel::Critical
+    ) {
+        print!(
+            "  {} Type '{}' to {} {}: ",
+            "?".red().bold(),
+            "yes".yellow(),
+            verb,
+            what
+        );
+        std::io::stdout().flush().ok();
+
+        let mut input = String::new();
+        if std::io::stdin().read_line(&mut input).is_err() {
+            return false;
+        }
+
+        if input.trim() != "yes" {
+            println!("{}", "Cancelled. (Must type 'yes' exactly)".dimmed());
+            return false;
+        }
+    } else {
+        print!(
+            "  {} {} {}? [y/N]: ",
+            "?".yellow().bold(),
+            capitalize(verb),
+            what
+        );
+        std::io::stdout().flush().ok();
+
+        let mut input = String::new();
+        if std::io::stdin().read_line(&mut input).is_err() {
+            return false;
         }
+
+        if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("{}", "Cancelled.".dimmed());
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Apply the Docker action to every published container of a compose project/service
+#[cfg(feature = "docker")]
+fn cmd_kill_compose(
+    project: Option<&str>,
+    service: Option<&str>,
+    force: bool,
+    dry_run: bool,
+    docker_opts: DockerOptions,
+) -> Result<(), PortrError> {
+    if !docker::is_docker_available() {
+        return Err(PortrError::DockerError(docker::unavailable_reason()));
     }
 
-    // Stop the container by NAME (more stable than ID which can change)
-    docker::stop_container_by_name(&container.name)?;
+    let action = docker::ContainerAction::from(docker_opts.action);
+    let verb = action.verb();
+    let containers = docker::get_all_containers()?;
+    let targets = docker::compose_containers(&containers, project, service);
+
+    if targets.is_empty() {
+        let mut filter = Vec::new();
+        if let Some(p) = project {
+            filter.push(format!("project '{}'", p));
+        }
+        if let Some(s) = service {
+            filter.push(format!("service '{}'", s));
+        }
+        return Err(PortrError::DockerError(format!(
+            "No running compose containers with published ports for {}",
+            filter.join(", ")
+        )));
+    }
 
+    let mut high_risk = false;
     println!(
-        "{} Stopped container {} on port {}",
-        "✓".green().bold(),
-        container.name.cyan(),
-        port.to_string().cyan()
+        "\n  {} {} compose container(s):",
+        "🐳".blue().bold(),
+        targets.len().to_string().yellow()
     );
+    for container in &targets {
+        let risk = docker::container_risk(container);
+        high_risk |= matches!(
+            risk,
+            services::RiskLevel::High | services::RiskLevel::Critical
+        );
+        println!(
+            "     {} ({}) {} [{}]",
+            container.display_name().cyan().bold(),
+            container.name.dimmed(),
+            container.published_ports().join(", ").yellow(),
+            risk.colored_label()
+        );
+    }
+    println!();
+
+    if dry_run {
+        println!(
+            "{} Would {} {} container(s)",
+            "🐳".blue().bold(),
+            verb,
+            targets.len()
+        );
+        return Ok(());
+    }
+
+    let risk = if high_risk {
+        services::RiskLevel::High
+    } else {
+        services::RiskLevel::Low
+    };
+    let what = format!("these {} container(s)", targets.len());
+    if !force && !confirm_container_action(risk, verb, &what) {
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for container in &targets {
+        match docker::apply_container_action(&container.name, action, docker_opts.stop_timeout) {
+            Ok(()) => println!(
+                "{} {} {} ({})",
+                "✓".green().bold(),
+                action.past_tense(),
+                container.display_name().cyan(),
+                container.name.dimmed()
+            ),
+            Err(e) => {
+                failed += 1;
+                println!(
+                    "{} Failed to {} {}: {}",
+                    "✗".red().bold(),
+                    verb,
+                    container.display_name().cyan(),
+                    e
+                );
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(PortrError::DockerError(format!(
+            "{} of {} containers failed to {}",
+            failed,
+            targets.len(),
+            verb
+        )));
+    }
 
     Ok(())
 }
 
+#[cfg(not(feature = "docker"))]
+fn cmd_kill_compose(
+    _project: Option<&str>,
+    _service: Option<&str>,
+    _force: bool,
+    _dry_run: bool,
+    _docker_opts: DockerOptions,
+) -> Result<(), PortrError> {
+    Err(PortrError::DockerError(
+        "Docker feature not enabled. Rebuild with --features docker".to_string(),
+    ))
+}
+
+/// Uppercase the first letter of a word
+#[cfg(feature = "docker")]
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Docker subcommands: list mappings, stop or restart containers
+#[cfg(feature = "docker")]
+fn cmd_docker(action: DockerCommand, stop_timeout: u32, verbose: bool) -> Result<(), PortrError> {
+    // Keep exports machine-readable
+    let exporting = matches!(&action, DockerCommand::Ls { format } if !matches!(format.format(), OutputFormat::Pretty));
+    if verbose && !exporting {
+        docker::print_endpoint();
+    }
+    if !docker::is_docker_available() {
+        return Err(PortrError::DockerError(docker::unavailable_reason()));
+    }
+
+    match action {
+        DockerCommand::Ls { format } => {
+            let containers = docker::get_all_containers()?;
+            let rows = docker::container_port_rows(&containers);
+
+            match format.format() {
+                OutputFormat::Json => println!("{}", export::to_json(&rows)?),
+                OutputFormat::Csv => print!("{}", export::containers_to_csv(&rows)),
+                OutputFormat::Markdown => print!("{}", export::containers_to_markdown(&rows)),
+                OutputFormat::Pretty => display::print_container_table(&rows),
+            }
+            Ok(())
+        }
+        DockerCommand::Stop {
+            target,
+            force,
+            dry_run,
+            stop_timeout: timeout,
+        } => docker_target_action(
+            &target,
+            docker::ContainerAction::Stop,
+            timeout.unwrap_or(stop_timeout),
+            force,
+            dry_run,
+        ),
+        DockerCommand::Restart {
+            target,
+            force,
+            dry_run,
+            stop_timeout: timeout,
+        } => docker_target_action(
+            &target,
+            docker::ContainerAction::Restart,
+            timeout.unwrap_or(stop_timeout),
+            force,
+            dry_run,
+        ),
+        DockerCommand::Pause {
+            target,
+            force,
+            dry_run,
+        } => docker_target_action(
+            &target,
+            docker::ContainerAction::Pause,
+            stop_timeout,
+            force,
+            dry_run,
+        ),
+        DockerCommand::Rm {
+            target,
+            force,
+            dry_run,
+        } => docker_target_action(
+            &target,
+            docker::ContainerAction::Remove,
+            stop_timeout,
+            force,
+            dry_run,
+        ),
+    }
+}
+
+/// Resolve a port or container name and apply an action to it
+#[cfg(feature = "docker")]
+fn docker_target_action(
+    target: &str,
+    action: docker::ContainerAction,
+    stop_timeout: u32,
+    force: bool,
+    dry_run: bool,
+) -> Result<(), PortrError> {
+    let (container, port) = match target.parse::<u16>() {
+        Ok(port) => (docker::get_container_for_port(port), Some(port)),
+        Err(_) => (docker::find_container(target)?, None),
+    };
+
+    match container {
+        Some(container) => {
+            run_container_action(&container, port, action, stop_timeout, force, dry_run)
+        }
+        None => Err(PortrError::DockerError(format!(
+            "No running container matches '{}'",
+            target
+        ))),
+    }
+}
+
+#[cfg(not(feature = "docker"))]
+fn cmd_docker(
+    _action: DockerCommand,
+    _stop_timeout: u32,
+    _verbose: bool,
+) -> Result<(), PortrError> {
+    Err(PortrError::DockerError(
+        "Docker feature not enabled. Rebuild with --features docker".to_string(),
+    ))
+}
+
 /// Scan a range of ports
-fn cmd_range(range: &str, format: OutputFormat) -> Result<(), PortrError> {
+fn cmd_range(
+    range: &str,
+    format: OutputFormat,
+    table: &TableOptions,
+    sample: Option<Duration>,
+) -> Result<(), PortrError> {
     let parts: Vec<&str> = range.split('-').collect();
     if parts.len() != 2 {
         return Err(PortrError::InvalidPortRange(range.to_string()));
@@ -709,11 +1567,13 @@ fn cmd_range(range: &str, format: OutputFormat) -> Result<(), PortrError> {
         return Err(PortrError::InvalidPortRange(range.to_string()));
     }
 
-    let all_ports = port::get_listening_ports()?;
-    let in_range: Vec<_> = all_ports
+    let all_ports = scan_ports(sample)?;
+    let mut in_range: Vec<_> = all_ports
         .into_iter()
         .filter(|p| p.port >= start && p.port <= end)
         .collect();
+    table.sort(&mut in_range);
+    let columns = table.columns.as_deref();
 
     if in_range.is_empty() {
         println!(
@@ -727,13 +1587,13 @@ fn cmd_range(range: &str, format: OutputFormat) -> Result<(), PortrError> {
 
     match format {
         OutputFormat::Json => {
-            println!("{}", export::to_json(&in_range)?);
+            println!("{}", export::ports_to_json(&in_range, columns)?);
         }
         OutputFormat::Csv => {
-            print!("{}", export::to_csv(&in_range));
+            print!("{}", export::to_csv_with(&in_range, columns));
         }
         OutputFormat::Markdown => {
-            print!("{}", export::to_markdown(&in_range));
+            print!("{}", export::to_markdown_with(&in_range, columns));
         }
         OutputFormat::Pretty => {
             println!(
@@ -743,7 +1603,7 @@ fn cmd_range(range: &str, format: OutputFormat) -> Result<(), PortrError> {
                 start.to_string().cyan(),
                 end.to_string().cyan()
             );
-            display::print_port_table(&in_range);
+            display::print_port_table_with(&in_range, columns);
         }
     }
 
@@ -751,8 +1611,27 @@ fn cmd_range(range: &str, format: OutputFormat) -> Result<(), PortrError> {
 }
 
 /// Watch ports in real-time
-fn cmd_watch(port: Option<u16>, interval: u64) -> Result<(), PortrError> {
-    use std::{thread, time::Duration};
+fn cmd_watch(
+    port: Option<u16>,
+    interval: u64,
+    user: Option<&str>,
+    events: bool,
+    table: &TableOptions,
+    history: &config::HistorySettings,
+) -> Result<(), PortrError> {
+    use std::thread;
+
+    let mut recorder = match HistoryRecorder::from_config(history) {
+        Ok(recorder) => recorder,
+        Err(e) => {
+            eprintln!("{} history disabled: {}", "warning:".yellow().bold(), e);
+            None
+        }
+    };
+
+    if events {
+        return watch_events(port, interval, user, recorder);
+    }
 
     println!(
         "{} Watching ports (refresh every {}s, Ctrl+C to stop)\n",
@@ -760,13 +1639,24 @@ fn cmd_watch(port: Option<u16>, interval: u64) -> Result<(), PortrError> {
         interval
     );
 
+    // Keep one sampler across iterations so CPU usage is real
+    let mut sampler = portr::ProcessSampler::new();
+
     loop {
         // Clear screen
         print!("\x1B[2J\x1B[1;1H");
         display::print_banner();
 
+        // History needs the full snapshot, even when watching one port
+        let all_ports = port::get_listening_ports_with(&mut sampler)?;
+        if let Some(ref mut recorder) = recorder {
+            if let Err(e) = recorder.record(&all_ports) {
+                eprintln!("{} history: {}", "warning:".yellow().bold(), e);
+            }
+        }
+
         if let Some(p) = port {
-            let info = port::get_port_info(p)?;
+            let info = all_ports.into_iter().find(|info| info.port == p);
             match info {
                 Some(port_info) => display::print_port_details(&port_info, true),
                 None => println!(
@@ -777,11 +1667,15 @@ fn cmd_watch(port: Option<u16>, interval: u64) -> Result<(), PortrError> {
                 ),
             }
         } else {
-            let ports = port::get_listening_ports()?;
+            let mut ports: Vec<_> = all_ports
+                .into_iter()
+                .filter(|p| user.map(|u| port::matches_user(p, u)).unwrap_or(true))
+                .collect();
+            table.sort(&mut ports);
             if ports.is_empty() {
                 println!("{}", "No listening ports found.".dimmed());
             } else {
-                display::print_port_table(&ports);
+                display::print_port_table_with(&ports, table.columns.as_deref());
             }
         }
 
@@ -794,6 +1688,217 @@ fn cmd_watch(port: Option<u16>, interval: u64) -> Result<(), PortrError> {
     }
 }
 
+/// `watch --events`: one JSON line per change, no screen redraws
+fn watch_events(
+    port: Option<u16>,
+    interval: u64,
+    user: Option<&str>,
+    mut recorder: Option<HistoryRecorder>,
+) -> Result<(), PortrError> {
+    use std::io::Write;
+
+    let mut sampler = portr::ProcessSampler::new();
+    let mut diff = portr::SnapshotDiff::new();
+    let stdout = std::io::stdout();
+
+    loop {
+        let all_ports = port::get_listening_ports_with(&mut sampler)?;
+        if let Some(ref mut recorder) = recorder {
+            if let Err(e) = recorder.record(&all_ports) {
+                eprintln!("{} history: {}", "warning:".yellow().bold(), e);
+            }
+        }
+
+        let ports: Vec<_> = all_ports
+            .into_iter()
+            .filter(|p| port.is_none_or(|wanted| p.port == wanted))
+            .filter(|p| user.is_none_or(|u| port::matches_user(p, u)))
+            .collect();
+
+        let mut out = stdout.lock();
+        for event in diff.update(&ports, history::unix_now()) {
+            let line = serde_json::to_string(&event)?;
+            match writeln!(out, "{}", line).and_then(|_| out.flush()) {
+                Ok(()) => {}
+                // The reader (jq, head, ...) went away
+                Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => return Ok(()),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        drop(out);
+
+        std::thread::sleep(Duration::from_secs(interval));
+    }
+}
+
+/// Run or query the background daemon
+fn cmd_daemon(action: DaemonAction) -> Result<(), PortrError> {
+    match action {
+        DaemonAction::Run { interval } => {
+            if let Some(path) = daemon::socket_path() {
+                println!(
+                    "{} portr daemon scanning every {}s, serving {}",
+                    "👁".bold(),
+                    interval,
+                    path.display().to_string().cyan()
+                );
+            }
+            daemon::run_daemon(Duration::from_secs(interval.max(1)))
+        }
+        DaemonAction::Status { json } => match daemon::query_daemon() {
+            Some(mut snapshot) => {
+                if json {
+                    // Command lines stay out of JSON output, as in exports
+                    snapshot.commands.clear();
+                    return display::print_json(&snapshot);
+                }
+                println!(
+                    "{} Daemon running (PID {}), {} port(s), last scan {}s ago",
+                    "●".green().bold(),
+                    snapshot.pid.to_string().yellow(),
+                    snapshot.ports.len().to_string().yellow(),
+                    history::unix_now() - snapshot.updated
+                );
+                Ok(())
+            }
+            None => {
+                println!("{} No daemon running", "○".dimmed());
+                Ok(())
+            }
+        },
+        DaemonAction::Events => {
+            #[cfg(unix)]
+            {
+                let path = daemon::socket_path().ok_or_else(|| {
+                    PortrError::IoError("could not determine data directory".into())
+                })?;
+                daemon::subscribe_events(&path, |event| {
+                    serde_json::to_string(&event)
+                        .map(|line| println!("{}", line))
+                        .is_ok()
+                })
+                .map_err(|e| PortrError::SystemError(format!("no daemon running ({})", e)))
+            }
+            #[cfg(not(unix))]
+            {
+                Err(PortrError::SystemError(
+                    "daemon mode needs Unix domain sockets".to_string(),
+                ))
+            }
+        }
+    }
+}
+
+/// Show recorded bind/unbind events
+fn cmd_history(
+    port: Option<u16>,
+    since: Option<Duration>,
+    json: bool,
+    settings: &config::HistorySettings,
+) -> Result<(), PortrError> {
+    let path = history::history_path()
+        .ok_or_else(|| PortrError::IoError("could not determine data directory".into()))?;
+    let cutoff = since.map(|d| {
+        history::unix_now().saturating_sub(i64::try_from(d.as_secs()).unwrap_or(i64::MAX))
+    });
+
+    // Filtered in memory: rewriting the log here could drop a recorder's appends
+    let events: Vec<_> =
+        history::retained(history::read_events(&path)?, settings, history::unix_now())
+            .into_iter()
+            .filter(|e| port.is_none_or(|p| e.port == p))
+            .filter(|e| cutoff.is_none_or(|c| e.timestamp >= c))
+            .collect();
+
+    if json {
+        return display::print_json(&events);
+    }
+    if events.is_empty() && !settings.enabled {
+        println!("{}", "No port history recorded.".dimmed());
+        println!(
+            "Set {} in the config file (see {}), then run {} or the dashboard.",
+            "[history] enabled = true".cyan(),
+            "portr config path".cyan(),
+            "portr watch".cyan()
+        );
+    } else {
+        display::print_history(&events);
+    }
+
+    Ok(())
+}
+
+/// Create a process sampler, primed over `sample` when CPU usage is wanted
+fn new_sampler(sample: Option<Duration>) -> portr::ProcessSampler {
+    match sample {
+        Some(interval) => portr::ProcessSampler::primed(interval),
+        None => portr::ProcessSampler::new(),
+    }
+}
+
+/// Scan listening ports, optionally sampling CPU usage first
+///
+/// A running `portr daemon` with a fresh snapshot saves the scan, unless a
+/// CPU sample was asked for.
+fn scan_ports(sample: Option<Duration>) -> Result<Vec<port::PortInfo>, PortrError> {
+    if sample.is_none() {
+        if let Some(ports) = daemon::fresh_ports() {
+            return Ok(ports);
+        }
+    }
+    port::get_listening_ports_with(&mut new_sampler(sample))
+}
+
+/// `--columns` value; an alias so clap takes the list as one value
+type ColumnList = Vec<Column>;
+
+/// Parse `--columns`
+fn parse_columns(s: &str) -> Result<ColumnList, String> {
+    portr::parse_columns(s).map_err(|e| e.to_string())
+}
+
+/// Parse `--sort`
+fn parse_sort(s: &str) -> Result<SortKey, String> {
+    SortKey::parse(s).map_err(|e| e.to_string())
+}
+
+/// Parse a `--where` query, keeping clap's error short
+fn parse_query(s: &str) -> Result<Query, String> {
+    Query::parse(s).map_err(|e| match e {
+        PortrError::InvalidQuery(message) => message,
+        other => other.to_string(),
+    })
+}
+
+/// Parse a human-friendly duration such as "200ms", "1.5s", "2m", "2h" or "1d".
+/// A bare number is taken as seconds.
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let value: f64 = num
+        .parse()
+        .map_err(|_| format!("invalid duration '{}'", s))?;
+
+    let secs = match unit.trim() {
+        "ms" => value / 1000.0,
+        "" | "s" | "sec" | "secs" => value,
+        "m" | "min" | "mins" => value * 60.0,
+        "h" | "hr" | "hrs" => value * 3600.0,
+        "d" | "day" | "days" => value * 86400.0,
+        other => {
+            return Err(format!(
+                "invalid duration unit '{}' (use ms, s, m, h or d)",
+                other
+            ))
+        }
+    };
+
+    Duration::try_from_secs_f64(secs).map_err(|_| format!("duration '{}' is too large", s))
+}
+
 /// Get current time as string (simple implementation without chrono)
 fn chrono_now() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -839,3 +1944,31 @@ fn confirm_kill(info: &port::PortInfo, is_critical: bool) -> bool {
         matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("200ms"), Ok(Duration::from_millis(200)));
+        assert_eq!(parse_duration("1.5s"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("3"), Ok(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn test_parse_duration_invalid() {
+        assert!(parse_duration("fast").is_err());
+        assert!(parse_duration("10parsecs").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_too_large() {
+        assert_eq!(
+            parse_duration("99999999999999999999999h"),
+            Err("duration '99999999999999999999999h' is too large".to_string())
+        );
+    }
+}
@@ -1,21 +1,39 @@
 //! Port detection and information gathering
 
 use crate::error::PortrError;
-use serde::Serialize;
-use sysinfo::{Pid, System};
+use crate::kube::{self, KubeForward};
+use crate::sampler::ProcessSampler;
+use crate::services;
+use crate::tunnel::{self, SshTunnel};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::time::Duration;
+use sysinfo::Pid;
 
 /// Information about a port and its associated process
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PortInfo {
     pub port: u16,
     pub protocol: String,
+    /// Owning process; 0 for a container port Docker forwards with iptables
     pub pid: u32,
     pub process_name: String,
     pub process_path: Option<String>,
     pub local_address: String,
     pub remote_address: Option<String>,
     pub state: String,
+    /// Real user name of the owning process
     pub user: Option<String>,
+    /// Effective user name (differs from `user` for setuid or sudo processes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_user: Option<String>,
+    /// Real group name of the owning process
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Effective group name (differs from `group` for setgid processes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_group: Option<String>,
     pub memory_mb: f64,
     pub cpu_percent: f32,
     pub uptime_secs: u64,
@@ -25,6 +43,59 @@ pub struct PortInfo {
     /// Parent process name (if available)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent_name: Option<String>,
+    /// Docker container publishing this port (requires the `docker` feature)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container: Option<ContainerRef>,
+    /// `kubectl port-forward` target, when the owner is a port-forward
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kube_forward: Option<KubeForward>,
+    /// SSH tunnel behind this listener (`ssh -L`/`-D` or a client's `-R`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tunnel: Option<SshTunnel>,
+    /// Command line of the owning process; kept out of exports since
+    /// arguments can carry secrets
+    #[serde(skip)]
+    pub command: Vec<String>,
+}
+
+/// Docker container that a host port is published by
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContainerRef {
+    /// Container ID (short form)
+    pub id: String,
+    /// Container name
+    pub name: String,
+    /// Image the container runs
+    pub image: String,
+    /// Compose project and service, for containers started by docker compose
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compose: Option<ComposeService>,
+}
+
+impl ContainerRef {
+    /// "project/service" for compose containers, else the container name
+    pub fn label(&self) -> Cow<'_, str> {
+        match self.compose {
+            Some(ref compose) => Cow::Owned(compose.to_string()),
+            None => Cow::Borrowed(&self.name),
+        }
+    }
+}
+
+/// Compose project and service a container belongs to
+///
+/// Read from the `com.docker.compose.project` and `com.docker.compose.service`
+/// labels that docker compose puts on every container it creates.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ComposeService {
+    pub project: String,
+    pub service: String,
+}
+
+impl std::fmt::Display for ComposeService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.project, self.service)
+    }
 }
 
 /// Process tree node for display
@@ -36,7 +107,114 @@ pub struct ProcessTreeNode {
     pub children: Vec<ProcessTreeNode>,
 }
 
+/// An established TCP connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Connection {
+    /// Local "address:port"
+    pub local_address: String,
+    pub local_port: u16,
+    /// Peer "address:port"
+    pub remote_address: String,
+    /// Owning process, when visible to the current user
+    pub pid: Option<u32>,
+    pub process_name: String,
+    pub state: String,
+}
+
+/// One row of a flattened process tree: ancestors, the port's owner, then
+/// its children
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessTreeRow {
+    pub pid: u32,
+    pub name: String,
+    /// Indentation, with the root ancestor at 0
+    pub depth: usize,
+    pub memory_mb: f64,
+    pub cpu_percent: f32,
+    /// Listening ports this process holds
+    pub ports: Vec<u16>,
+    /// Whether this is the process that owns the inspected port
+    pub is_target: bool,
+}
+
 impl PortInfo {
+    /// Name to show for this port: the compose service or container if
+    /// known, else the process
+    pub fn display_name(&self) -> Cow<'_, str> {
+        if let Some(ref container) = self.container {
+            return container.label();
+        }
+        if let Some(ref forward) = self.kube_forward {
+            return Cow::Owned(format!("{} {}", self.process_name, forward));
+        }
+        match self.tunnel {
+            Some(ref tunnel) => Cow::Owned(format!("{} {}", self.process_name, tunnel)),
+            None => Cow::Borrowed(&self.process_name),
+        }
+    }
+
+    /// Whether killing this port's owner needs the extra critical-service
+    /// confirmation
+    ///
+    /// A port-forward or SSH tunnel reuses the service's port number
+    /// locally, but killing it only closes the tunnel.
+    pub fn requires_confirmation(&self) -> bool {
+        self.kill_note().is_none() && services::requires_confirmation(self.port)
+    }
+
+    /// Low-risk note for owners whose kill only closes a forward
+    pub fn kill_note(&self) -> Option<String> {
+        if let Some(ref forward) = self.kube_forward {
+            return Some(forward.kill_note());
+        }
+        self.tunnel.as_ref().and_then(SshTunnel::kill_note)
+    }
+
+    /// Risk of killing this port's owner; forwards and tunnels are always
+    /// low risk, and `None` means the port isn't a known service
+    pub fn risk(&self) -> Option<services::RiskLevel> {
+        if self.kill_note().is_some() {
+            return Some(services::RiskLevel::Low);
+        }
+        services::lookup(self.port).map(|s| s.risk)
+    }
+
+    /// Case-insensitive substring search over the fields shown in tables
+    ///
+    /// `search` must already be lowercase.
+    pub fn matches_text(&self, search: &str) -> bool {
+        self.port.to_string().contains(search)
+            || self.process_name.to_lowercase().contains(search)
+            || services::short_name(self.port).is_some_and(|s| s.to_lowercase().contains(search))
+            || self.container.as_ref().is_some_and(|c| {
+                c.label().to_lowercase().contains(search)
+                    || c.name.to_lowercase().contains(search)
+                    || c.image.to_lowercase().contains(search)
+            })
+            || self
+                .tunnel
+                .as_ref()
+                .is_some_and(|t| t.to_string().to_lowercase().contains(search))
+            || self.pid.to_string().contains(search)
+            || self.local_address.to_lowercase().contains(search)
+            || self.protocol.to_lowercase().contains(search)
+    }
+
+    /// Whether this port is an SSH tunnel
+    pub fn is_tunnel(&self) -> bool {
+        self.tunnel.is_some()
+    }
+
+    /// Compose project and service publishing this port, if any
+    pub fn compose(&self) -> Option<&ComposeService> {
+        self.container.as_ref().and_then(|c| c.compose.as_ref())
+    }
+
+    /// Whether this port belongs to Docker (mapped container or Docker's forwarder)
+    pub fn is_docker(&self) -> bool {
+        self.container.is_some() || is_docker_process(&self.process_name)
+    }
+
     /// Format uptime as human-readable string
     pub fn uptime_display(&self) -> String {
         let secs = self.uptime_secs;
@@ -52,17 +230,77 @@ impl PortInfo {
     }
 }
 
+#[cfg(test)]
+impl PortInfo {
+    /// A `node` TCP listener on 127.0.0.1 with nothing else known; tests
+    /// override the fields they care about with struct-update syntax
+    pub(crate) fn test_port(port: u16, pid: u32) -> Self {
+        Self {
+            port,
+            protocol: "TCP".to_string(),
+            pid,
+            process_name: "node".to_string(),
+            process_path: None,
+            local_address: format!("127.0.0.1:{}", port),
+            remote_address: None,
+            state: "LISTEN".to_string(),
+            user: None,
+            effective_user: None,
+            group: None,
+            effective_group: None,
+            memory_mb: 0.0,
+            cpu_percent: 0.0,
+            uptime_secs: 0,
+            parent_pid: None,
+            parent_name: None,
+            container: None,
+            kube_forward: None,
+            tunnel: None,
+            command: Vec::new(),
+        }
+    }
+}
+
 /// Get all listening ports on the system
+///
+/// CPU usage is not sampled and reads as 0%. Use
+/// [`get_listening_ports_sampled`] or keep a [`ProcessSampler`] alive across
+/// calls to [`get_listening_ports_with`] for real values.
 pub fn get_listening_ports() -> Result<Vec<PortInfo>, PortrError> {
-    let mut sys = System::new_all();
-    sys.refresh_all();
+    get_listening_ports_with(&mut ProcessSampler::new())
+}
 
+/// Get all listening ports, sampling CPU usage over `interval`
+pub fn get_listening_ports_sampled(interval: Duration) -> Result<Vec<PortInfo>, PortrError> {
+    get_listening_ports_with(&mut ProcessSampler::primed(interval))
+}
+
+/// Get all listening ports using a long-lived sampler
+///
+/// The sampler is refreshed on every call, so CPU usage is accurate from the
+/// second call onwards.
+pub fn get_listening_ports_with(sampler: &mut ProcessSampler) -> Result<Vec<PortInfo>, PortrError> {
     let connections = get_network_connections()?;
+
+    // Only socket owners need memory, path and user details
+    let mut owners: Vec<Pid> = connections
+        .iter()
+        .filter_map(|c| c.pid.map(Pid::from_u32))
+        .collect();
+    owners.sort_unstable();
+    owners.dedup();
+    sampler.refresh_owners(&owners);
     let mut results = Vec::new();
 
     for conn in connections {
         if let Some(pid) = conn.pid {
-            let process_info = get_process_info(&sys, pid);
+            let process_info = get_process_info(sampler, pid);
+            let tunnel = tunnel::detect_tunnel(
+                &process_info.name,
+                process_info.parent_name.as_deref(),
+                &process_info.cmd,
+                conn.local_port,
+            );
 
             results.push(PortInfo {
                 port: conn.local_port,
@@ -76,11 +314,18 @@ pub fn get_listening_ports() -> Result<Vec<PortInfo>, PortrError> {
                     .map(|a| format!("{}:{}", a, conn.remote_port.unwrap_or(0))),
                 state: conn.state.clone(),
                 user: process_info.user,
+                effective_user: process_info.effective_user,
+                group: process_info.group,
+                effective_group: process_info.effective_group,
                 memory_mb: process_info.memory_mb,
                 cpu_percent: process_info.cpu_percent,
                 uptime_secs: process_info.uptime_secs,
                 parent_pid: process_info.parent_pid,
                 parent_name: process_info.parent_name,
+                container: None,
+                kube_forward: kube::parse_port_forward(&process_info.cmd, conn.local_port),
+                tunnel,
+                command: process_info.cmd,
             });
         }
     }
@@ -92,6 +337,11 @@ pub fn get_listening_ports() -> Result<Vec<PortInfo>, PortrError> {
     let mut seen = std::collections::HashSet::new();
     results.retain(|p| seen.insert(p.port));
 
+    // Published container ports show up as Docker's forwarder, or not at
+    // all when iptables forwards them; map them back
+    #[cfg(feature = "docker")]
+    crate::docker::attach_containers(&mut results);
+
     Ok(results)
 }
 
@@ -101,20 +351,85 @@ pub fn get_port_info(port: u16) -> Result<Option<PortInfo>, PortrError> {
     Ok(ports.into_iter().find(|p| p.port == port))
 }
 
+/// Get information about a specific port using a long-lived sampler
+pub fn get_port_info_with(
+    sampler: &mut ProcessSampler,
+    port: u16,
+) -> Result<Option<PortInfo>, PortrError> {
+    let ports = get_listening_ports_with(sampler)?;
+    Ok(ports.into_iter().find(|p| p.port == port))
+}
+
+/// Check whether a process name belongs to Docker's port forwarding
+///
+/// Published ports are held by `docker-proxy` (Linux), `com.docker.backend`
+/// or `vpnkit` (Docker Desktop) and `rootlesskit` (rootless Docker) rather than
+/// the container's own process.
+pub fn is_docker_process(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.contains("docker")
+        || name.contains("containerd")
+        || name.contains("vpnkit")
+        || name.starts_with("rootlesskit")
+        || name == "rootlessport"
+}
+
+/// Check whether a port is owned by the given user.
+///
+/// `user` is matched case-insensitively against both the real and the
+/// effective user name. The special value `me` matches the current user.
+pub fn matches_user(info: &PortInfo, user: &str) -> bool {
+    let wanted = if user.eq_ignore_ascii_case("me") {
+        match current_user() {
+            Some(name) => name,
+            None => return false,
+        }
+    } else {
+        user.to_string()
+    };
+
+    [&info.user, &info.effective_user].iter().any(|u| {
+        u.as_deref()
+            .is_some_and(|u| u.eq_ignore_ascii_case(&wanted))
+    })
+}
+
+/// Get the name of the user running portr
+pub fn current_user() -> Option<String> {
+    #[cfg(unix)]
+    {
+        nix::unistd::User::from_uid(nix::unistd::geteuid())
+            .ok()
+            .flatten()
+            .map(|u| u.name)
+            .or_else(|| std::env::var("USER").ok())
+    }
+
+    #[cfg(windows)]
+    {
+        std::env::var("USERNAME").ok()
+    }
+}
+
 /// Internal: Process information
 struct ProcessInfo {
     name: String,
     path: Option<String>,
     user: Option<String>,
+    effective_user: Option<String>,
+    group: Option<String>,
+    effective_group: Option<String>,
     memory_mb: f64,
     cpu_percent: f32,
     uptime_secs: u64,
     parent_pid: Option<u32>,
     parent_name: Option<String>,
+    cmd: Vec<String>,
 }
 
 /// Get process information by PID
-fn get_process_info(sys: &System, pid: u32) -> ProcessInfo {
+fn get_process_info(sampler: &ProcessSampler, pid: u32) -> ProcessInfo {
+    let sys = sampler.system();
     let pid = Pid::from_u32(pid);
 
     if let Some(process) = sys.process(pid) {
@@ -133,28 +448,41 @@ fn get_process_info(sys: &System, pid: u32) -> ProcessInfo {
         ProcessInfo {
             name: process.name().to_string_lossy().to_string(),
             path: process.exe().map(|p| p.to_string_lossy().to_string()),
-            user: process.user_id().map(|u| format!("{:?}", u)),
+            user: process.user_id().map(|u| sampler.user_name(u)),
+            effective_user: process.effective_user_id().map(|u| sampler.user_name(u)),
+            group: process.group_id().map(|g| sampler.group_name(g)),
+            effective_group: process.effective_group_id().map(|g| sampler.group_name(g)),
             memory_mb: process.memory() as f64 / 1024.0 / 1024.0,
             cpu_percent: process.cpu_usage(),
             uptime_secs: process.run_time(),
             parent_pid,
             parent_name,
+            cmd: process
+                .cmd()
+                .iter()
+                .map(|a| a.to_string_lossy().to_string())
+                .collect(),
         }
     } else {
         ProcessInfo {
             name: "<unknown>".to_string(),
             path: None,
             user: None,
+            effective_user: None,
+            group: None,
+            effective_group: None,
             memory_mb: 0.0,
             cpu_percent: 0.0,
             uptime_secs: 0,
             parent_pid: None,
             parent_name: None,
+            cmd: Vec::new(),
         }
     }
 }
 
 /// Internal: Network connection information
+#[derive(Debug, PartialEq)]
 struct NetConnection {
     protocol: String,
     local_addr: String,
@@ -381,8 +709,198 @@ fn get_network_connections() -> Result<Vec<NetConnection>, PortrError> {
     Ok(connections)
 }
 
-/// Parse Windows address format (e.g., "0.0.0.0:3000" or "[::]:3000")
+/// Get established TCP connections, with owning process names from `sampler`
+///
+/// Owners missing from the sampler's snapshot are looked up one by one.
+pub fn get_connections_with(sampler: &mut ProcessSampler) -> Result<Vec<Connection>, PortrError> {
+    let established = get_established_connections()?;
+    let mut missing: Vec<Pid> = established
+        .iter()
+        .filter_map(|c| c.pid.map(Pid::from_u32))
+        .filter(|pid| sampler.system().process(*pid).is_none())
+        .collect();
+    missing.sort_unstable();
+    missing.dedup();
+    sampler.refresh_pids(&missing);
+
+    let sys = sampler.system();
+    let mut connections: Vec<Connection> = established
+        .into_iter()
+        .map(|conn| {
+            let process_name = conn
+                .pid
+                .and_then(|pid| sys.process(Pid::from_u32(pid)))
+                .map(|p| p.name().to_string_lossy().to_string())
+                .unwrap_or_else(|| "-".to_string());
+            Connection {
+                local_address: format!("{}:{}", conn.local_addr, conn.local_port),
+                local_port: conn.local_port,
+                remote_address: match (conn.remote_addr, conn.remote_port) {
+                    (Some(addr), Some(port)) => format!("{}:{}", addr, port),
+                    (Some(addr), None) => addr,
+                    _ => "*".to_string(),
+                },
+                pid: conn.pid,
+                process_name,
+                state: conn.state,
+            }
+        })
+        .collect();
+    connections
+        .sort_by(|a, b| (a.local_port, &a.remote_address).cmp(&(b.local_port, &b.remote_address)));
+    Ok(connections)
+}
+
+/// Count connections per local port
+///
+/// Inbound connections to a listener show up under the listener's port, so
+/// this is the number of clients connected to each server.
+pub fn established_counts(connections: &[Connection]) -> HashMap<u16, usize> {
+    let mut counts = HashMap::new();
+    for conn in connections {
+        *counts.entry(conn.local_port).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Established TCP connections
 #[cfg(target_os = "windows")]
+fn get_established_connections() -> Result<Vec<NetConnection>, PortrError> {
+    use std::process::Command;
+
+    let output = Command::new("netstat")
+        .args(["-ano", "-p", "TCP"])
+        .output()
+        .map_err(|e| PortrError::NetworkError(e.to_string()))?;
+
+    Ok(parse_netstat_established(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Parse `netstat -ano -p TCP` output into established connections
+#[cfg(any(target_os = "windows", test))]
+fn parse_netstat_established(stdout: &str) -> Vec<NetConnection> {
+    let mut connections = Vec::new();
+
+    for line in stdout.lines().skip(4) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() >= 5 && parts[3] == "ESTABLISHED" {
+            if let (Some((local_addr, local_port)), Some((remote_addr, remote_port))) =
+                (parse_address(parts[1]), parse_address(parts[2]))
+            {
+                let pid: u32 = parts[4].parse().unwrap_or(0);
+                connections.push(NetConnection {
+                    protocol: "TCP".to_string(),
+                    local_addr,
+                    local_port,
+                    remote_addr: Some(remote_addr),
+                    remote_port: Some(remote_port),
+                    state: "ESTABLISHED".to_string(),
+                    pid: if pid > 0 { Some(pid) } else { None },
+                });
+            }
+        }
+    }
+
+    connections
+}
+
+/// Established TCP connections
+#[cfg(target_os = "linux")]
+fn get_established_connections() -> Result<Vec<NetConnection>, PortrError> {
+    use std::process::Command;
+
+    let output = Command::new("ss")
+        .args(["-tnp", "state", "established"])
+        .output()
+        .map_err(|e| PortrError::NetworkError(e.to_string()))?;
+
+    Ok(parse_ss_established(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Parse `ss -tnp state established` output into connections
+#[cfg(any(target_os = "linux", test))]
+fn parse_ss_established(stdout: &str) -> Vec<NetConnection> {
+    // With a state filter ss drops the State column:
+    // Recv-Q Send-Q Local:Port Peer:Port [Process]
+    let mut connections = Vec::new();
+
+    for line in stdout.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() >= 4 {
+            if let (Some((local_addr, local_port)), Some((remote_addr, remote_port))) =
+                (parse_linux_address(parts[2]), parse_linux_address(parts[3]))
+            {
+                connections.push(NetConnection {
+                    protocol: "TCP".to_string(),
+                    local_addr,
+                    local_port,
+                    remote_addr: Some(remote_addr),
+                    remote_port: Some(remote_port),
+                    state: "ESTAB".to_string(),
+                    pid: extract_pid_from_ss(&parts[4..].join(" ")),
+                });
+            }
+        }
+    }
+
+    connections
+}
+
+/// Established TCP connections
+#[cfg(target_os = "macos")]
+fn get_established_connections() -> Result<Vec<NetConnection>, PortrError> {
+    use std::process::Command;
+
+    let output = Command::new("lsof")
+        .args(["-iTCP", "-sTCP:ESTABLISHED", "-n", "-P"])
+        .output()
+        .map_err(|e| PortrError::NetworkError(e.to_string()))?;
+
+    Ok(parse_lsof_established(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Parse `lsof -iTCP -sTCP:ESTABLISHED -n -P` output into connections
+#[cfg(any(target_os = "macos", test))]
+fn parse_lsof_established(stdout: &str) -> Vec<NetConnection> {
+    let mut connections = Vec::new();
+
+    for line in stdout.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() >= 9 {
+            let pid: u32 = parts[1].parse().unwrap_or(0);
+            // e.g. "127.0.0.1:3000->127.0.0.1:52144"
+            let Some((local, remote)) = parts[8].split_once("->") else {
+                continue;
+            };
+            if let Some(port) = extract_port_from_lsof(local) {
+                let (remote_addr, remote_port) = match remote.rsplit_once(':') {
+                    Some((addr, port)) => (Some(addr.to_string()), port.parse().ok()),
+                    None => (None, None),
+                };
+                connections.push(NetConnection {
+                    protocol: "TCP".to_string(),
+                    local_addr: local.rsplit_once(':').map_or("*", |(a, _)| a).to_string(),
+                    local_port: port,
+                    remote_addr,
+                    remote_port,
+                    state: "ESTABLISHED".to_string(),
+                    pid: if pid > 0 { Some(pid) } else { None },
+                });
+            }
+        }
+    }
+
+    connections
+}
+
+/// Parse Windows address format (e.g., "0.0.0.0:3000" or "[::]:3000")
+#[cfg(any(target_os = "windows", test))]
 fn parse_address(addr: &str) -> Option<(String, u16)> {
     // Handle IPv6 with brackets
     if addr.starts_with('[') {
@@ -408,7 +926,7 @@ fn parse_address(addr: &str) -> Option<(String, u16)> {
 }
 
 /// Parse Linux ss address format
-#[cfg(target_os = "linux")]
+#[cfg(any(target_os = "linux", test))]
 fn parse_linux_address(addr: &str) -> Option<(String, u16)> {
     // Format: *:port, 0.0.0.0:port, [::]:port, :::port
     if let Some(colon_pos) = addr.rfind(':') {
@@ -423,7 +941,7 @@ fn parse_linux_address(addr: &str) -> Option<(String, u16)> {
 }
 
 /// Extract PID from ss output
-#[cfg(target_os = "linux")]
+#[cfg(any(target_os = "linux", test))]
 fn extract_pid_from_ss(users_str: &str) -> Option<u32> {
     // Format: users:(("node",pid=12345,fd=21))
     if let Some(pid_start) = users_str.find("pid=") {
@@ -436,7 +954,7 @@ fn extract_pid_from_ss(users_str: &str) -> Option<u32> {
 }
 
 /// Extract port from lsof output
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", test))]
 fn extract_port_from_lsof(name_part: &str) -> Option<u16> {
     // Format: "*:3000" or "localhost:3000"
     if let Some(colon_pos) = name_part.rfind(':') {
@@ -453,9 +971,14 @@ fn extract_port_from_lsof(name_part: &str) -> Option<u16> {
 
 /// Get process tree for a given PID (parent chain)
 pub fn get_process_tree(pid: u32) -> Vec<(u32, String)> {
-    let mut sys = System::new_all();
-    sys.refresh_all();
+    let mut sampler = ProcessSampler::new();
+    sampler.refresh();
+    get_process_tree_with(&sampler, pid)
+}
 
+/// Get process tree for a given PID from an existing snapshot
+pub fn get_process_tree_with(sampler: &ProcessSampler, pid: u32) -> Vec<(u32, String)> {
+    let sys = sampler.system();
     let mut tree = Vec::new();
     let mut current_pid = Some(Pid::from_u32(pid));
 
@@ -479,13 +1002,17 @@ pub fn get_process_tree(pid: u32) -> Vec<(u32, String)> {
 
 /// Get child processes for a given PID
 pub fn get_child_processes(pid: u32) -> Vec<(u32, String)> {
-    let mut sys = System::new_all();
-    sys.refresh_all();
+    let mut sampler = ProcessSampler::new();
+    sampler.refresh();
+    get_child_processes_with(&sampler, pid)
+}
 
+/// Get child processes for a given PID from an existing snapshot
+pub fn get_child_processes_with(sampler: &ProcessSampler, pid: u32) -> Vec<(u32, String)> {
     let target_pid = Pid::from_u32(pid);
     let mut children = Vec::new();
 
-    for (child_pid, process) in sys.processes() {
+    for (child_pid, process) in sampler.system().processes() {
         if process.parent() == Some(target_pid) {
             children.push((
                 child_pid.as_u32(),
@@ -494,15 +1021,80 @@ pub fn get_child_processes(pid: u32) -> Vec<(u32, String)> {
         }
     }
 
+    children.sort_by_key(|(pid, _)| *pid);
     children
 }
 
+/// Flatten the process tree around `pid` for display
+///
+/// Rows run from the root ancestor down to `pid`, followed by its direct
+/// children. `ports` marks which nodes hold sockets. Memory is read for the
+/// tree's processes only.
+pub fn process_tree_rows_with(
+    sampler: &mut ProcessSampler,
+    pid: u32,
+    ports: &[PortInfo],
+) -> Vec<ProcessTreeRow> {
+    let chain = get_process_tree_with(sampler, pid);
+    let children = get_child_processes_with(sampler, pid);
+    let target_depth = chain.len().saturating_sub(1);
+
+    let nodes: Vec<(u32, String, usize)> = chain
+        .into_iter()
+        .rev()
+        .enumerate()
+        .map(|(depth, (pid, name))| (pid, name, depth))
+        .chain(
+            children
+                .into_iter()
+                .map(|(pid, name)| (pid, name, target_depth + 1)),
+        )
+        .collect();
+
+    let pids: Vec<Pid> = nodes
+        .iter()
+        .map(|(pid, _, _)| Pid::from_u32(*pid))
+        .collect();
+    sampler.refresh_memory(&pids);
+
+    let sys = sampler.system();
+    nodes
+        .into_iter()
+        .map(|(node_pid, name, depth)| {
+            let process = sys.process(Pid::from_u32(node_pid));
+            let mut held: Vec<u16> = ports
+                .iter()
+                .filter(|p| p.pid == node_pid)
+                .map(|p| p.port)
+                .collect();
+            held.sort_unstable();
+            held.dedup();
+            ProcessTreeRow {
+                pid: node_pid,
+                name,
+                depth,
+                memory_mb: process.map_or(0.0, |p| p.memory() as f64 / 1024.0 / 1024.0),
+                cpu_percent: process.map_or(0.0, |p| p.cpu_usage()),
+                ports: held,
+                is_target: node_pid == pid,
+            }
+        })
+        .collect()
+}
+
 /// Build and print a process tree view (ASCII art)
 pub fn print_process_tree(port_info: &PortInfo) {
+    let mut sampler = ProcessSampler::new();
+    sampler.refresh();
+    print_process_tree_with(&sampler, port_info);
+}
+
+/// Print a process tree view using an existing snapshot
+pub fn print_process_tree_with(sampler: &ProcessSampler, port_info: &PortInfo) {
     use colored::Colorize;
 
-    let parent_chain = get_process_tree(port_info.pid);
-    let children = get_child_processes(port_info.pid);
+    let parent_chain = get_process_tree_with(sampler, port_info.pid);
+    let children = get_child_processes_with(sampler, port_info.pid);
 
     println!("\n{}", " Process Tree ".cyan().bold());
     println!("{}", "─".repeat(40).dimmed());
@@ -591,23 +1183,103 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    fn tcp(
+        local: (&str, u16),
+        remote: (&str, u16),
+        state: &str,
+        pid: Option<u32>,
+    ) -> NetConnection {
+        NetConnection {
+            protocol: "TCP".to_string(),
+            local_addr: local.0.to_string(),
+            local_port: local.1,
+            remote_addr: Some(remote.0.to_string()),
+            remote_port: Some(remote.1),
+            state: state.to_string(),
+            pid,
+        }
+    }
+
+    #[test]
+    fn test_parse_netstat_established() {
+        let stdout = "\r
+Active Connections\r
+\r
+  Proto  Local Address          Foreign Address        State           PID\r
+  TCP    0.0.0.0:135            0.0.0.0:0              LISTENING       1044\r
+  TCP    127.0.0.1:3000         127.0.0.1:52144        ESTABLISHED     4242\r
+  TCP    [::1]:5432             [::1]:60311            ESTABLISHED     0\r
+  TCP    10.0.0.5:50012         140.82.112.4:443       TIME_WAIT       0\r
+";
+        assert_eq!(
+            parse_netstat_established(stdout),
+            vec![
+                tcp(
+                    ("127.0.0.1", 3000),
+                    ("127.0.0.1", 52144),
+                    "ESTABLISHED",
+                    Some(4242)
+                ),
+                tcp(("::1", 5432), ("::1", 60311), "ESTABLISHED", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_ss_established_without_state_column() {
+        let stdout = "\
+Recv-Q Send-Q   Local Address:Port    Peer Address:Port Process
+0      0            127.0.0.1:3000       127.0.0.1:52144 users:((\"node\",pid=4242,fd=21))
+0      0      [::ffff:10.0.0.5]:8080 [::ffff:10.0.0.9]:41000
+0      0                [::1]:5432           [::1]:60311 users:((\"postgres\",pid=77,fd=9))
+";
+        assert_eq!(
+            parse_ss_established(stdout),
+            vec![
+                tcp(
+                    ("127.0.0.1", 3000),
+                    ("127.0.0.1", 52144),
+                    "ESTAB",
+                    Some(4242)
+                ),
+                tcp(
+                    ("::ffff:10.0.0.5", 8080),
+                    ("::ffff:10.0.0.9", 41000),
+                    "ESTAB",
+                    None
+                ),
+                tcp(("::1", 5432), ("::1", 60311), "ESTAB", Some(77)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_lsof_established() {
+        let stdout = "\
+COMMAND   PID USER   FD   TYPE             DEVICE SIZE/OFF NODE NAME
+node     4242 dev    21u  IPv4 0x1234567890abcdef      0t0  TCP 127.0.0.1:3000->127.0.0.1:52144 (ESTABLISHED)
+postgres   77 dev     9u  IPv6 0x1234567890abcdf0      0t0  TCP [::1]:5432->[::1]:60311 (ESTABLISHED)
+launchd     1 root    8u  IPv4 0x1234567890abcdf1      0t0  TCP *:22 (LISTEN)
+";
+        assert_eq!(
+            parse_lsof_established(stdout),
+            vec![
+                tcp(
+                    ("127.0.0.1", 3000),
+                    ("127.0.0.1", 52144),
+                    "ESTABLISHED",
+                    Some(4242)
+                ),
+                tcp(("[::1]", 5432), ("[::1]", 60311), "ESTABLISHED", Some(77)),
+            ]
+        );
+    }
+
     #[test]
     fn test_uptime_display_seconds() {
         let info = PortInfo {
-            port: 3000,
-            protocol: "TCP".to_string(),
-            pid: 1234,
-            process_name: "test".to_string(),
-            process_path: None,
-            local_address: "0.0.0.0:3000".to_string(),
-            remote_address: None,
-            state: "LISTENING".to_string(),
-            user: None,
-            memory_mb: 0.0,
-            cpu_percent: 0.0,
             uptime_secs: 45,
-            parent_pid: None,
-            parent_name: None,
+            ..PortInfo::test_port(3000, 1234)
         };
         assert_eq!(info.uptime_display(), "45s");
     }
@@ -615,20 +1287,8 @@ mod tests {
     #[test]
     fn test_uptime_display_minutes() {
         let info = PortInfo {
-            port: 3000,
-            protocol: "TCP".to_string(),
-            pid: 1234,
-            process_name: "test".to_string(),
-            process_path: None,
-            local_address: "0.0.0.0:3000".to_string(),
-            remote_address: None,
-            state: "LISTENING".to_string(),
-            user: None,
-            memory_mb: 0.0,
-            cpu_percent: 0.0,
             uptime_secs: 125,
-            parent_pid: None,
-            parent_name: None,
+            ..PortInfo::test_port(3000, 1234)
         };
         assert_eq!(info.uptime_display(), "2m 5s");
     }
@@ -636,20 +1296,8 @@ mod tests {
     #[test]
     fn test_uptime_display_hours() {
         let info = PortInfo {
-            port: 3000,
-            protocol: "TCP".to_string(),
-            pid: 1234,
-            process_name: "test".to_string(),
-            process_path: None,
-            local_address: "0.0.0.0:3000".to_string(),
-            remote_address: None,
-            state: "LISTENING".to_string(),
-            user: None,
-            memory_mb: 0.0,
-            cpu_percent: 0.0,
             uptime_secs: 7384,
-            parent_pid: None,
-            parent_name: None,
+            ..PortInfo::test_port(3000, 1234)
         };
         assert_eq!(info.uptime_display(), "2h 3m");
     }
@@ -657,21 +1305,32 @@ mod tests {
     #[test]
     fn test_uptime_display_days() {
         let info = PortInfo {
-            port: 3000,
-            protocol: "TCP".to_string(),
-            pid: 1234,
-            process_name: "test".to_string(),
-            process_path: None,
-            local_address: "0.0.0.0:3000".to_string(),
-            remote_address: None,
-            state: "LISTENING".to_string(),
-            user: None,
-            memory_mb: 0.0,
-            cpu_percent: 0.0,
             uptime_secs: 180000,
-            parent_pid: None,
-            parent_name: None,
+            ..PortInfo::test_port(3000, 1234)
         };
         assert_eq!(info.uptime_display(), "2d 2h");
     }
+
+    #[test]
+    fn test_matches_user_real_and_effective() {
+        let info = PortInfo {
+            user: Some("alice".to_string()),
+            effective_user: Some("root".to_string()),
+            group: Some("staff".to_string()),
+            ..PortInfo::test_port(3000, 1234)
+        };
+        assert!(matches_user(&info, "alice"));
+        assert!(matches_user(&info, "ROOT"));
+        assert!(!matches_user(&info, "bob"));
+    }
+
+    #[test]
+    fn test_docker_process_detection() {
+        assert!(is_docker_process("docker-proxy"));
+        assert!(is_docker_process("com.docker.backend"));
+        assert!(is_docker_process("vpnkit.exe"));
+        assert!(is_docker_process("rootlesskit"));
+        assert!(!is_docker_process("node"));
+        assert!(!is_docker_process("nginx"));
+    }
 }
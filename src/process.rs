@@ -15,6 +15,17 @@ pub fn kill_process(pid: u32, force: bool) -> Result<(), PortrError> {
     }
 }
 
+/// What `kill_process` sends, for display before killing
+pub fn kill_signal_name(force: bool) -> &'static str {
+    if cfg!(windows) {
+        "taskkill /F"
+    } else if force {
+        "SIGKILL (9)"
+    } else {
+        "SIGTERM (15)"
+    }
+}
+
 /// Unix implementation using signals
 #[cfg(unix)]
 fn kill_unix(pid: u32, force: bool) -> Result<(), PortrError> {
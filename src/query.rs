@@ -0,0 +1,468 @@
+//! Filter query language for the dashboard search and `--where`
+//!
+//! A query is a list of whitespace-separated terms that must all match:
+//!
+//! ```text
+//! port:3000-3999 proc:node user:me mem>200 state:listen !docker risk>=high
+//! ```
+//!
+//! - `field:value` matches a field; numeric fields also take `low-high` ranges
+//! - `field>value`, `>=`, `<`, `<=` and `=` compare numbers and risk levels
+//! - `docker`, `tunnel`, `kube` and `critical` are flags
+//! - `!` in front of any term negates it
+//! - any other word is a substring search over port, process, service, PID,
+//!   address and protocol, like the plain search always was
+//! - values containing spaces can be quoted: `proc:"my app"`
+
+use crate::error::PortrError;
+use crate::port::{self, PortInfo};
+use crate::services::{self, RiskLevel};
+use std::fmt;
+use std::str::FromStr;
+
+/// Parsed filter query; the empty query matches every port
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    negated: bool,
+    condition: Condition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    /// Lowercase substring searched across the table's fields
+    Text(String),
+    Flag(Flag),
+    Compare(Field, Op, f64),
+    /// Inclusive numeric range
+    Range(Field, f64, f64),
+    /// Lowercase substring of a text field
+    Contains(Field, String),
+    User(String),
+    Risk(Op, RiskLevel),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Flag {
+    Docker,
+    Tunnel,
+    Kube,
+    Critical,
+}
+
+impl Flag {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "docker" => Some(Flag::Docker),
+            "tunnel" | "tunnels" => Some(Flag::Tunnel),
+            "kube" | "k8s" => Some(Flag::Kube),
+            "critical" => Some(Flag::Critical),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Port,
+    Pid,
+    Memory,
+    Cpu,
+    Process,
+    User,
+    State,
+    Protocol,
+    Address,
+    Service,
+    Risk,
+}
+
+/// Field names and their aliases
+const FIELDS: &[(&str, Field)] = &[
+    ("port", Field::Port),
+    ("pid", Field::Pid),
+    ("mem", Field::Memory),
+    ("memory", Field::Memory),
+    ("cpu", Field::Cpu),
+    ("proc", Field::Process),
+    ("process", Field::Process),
+    ("user", Field::User),
+    ("state", Field::State),
+    ("proto", Field::Protocol),
+    ("protocol", Field::Protocol),
+    ("addr", Field::Address),
+    ("address", Field::Address),
+    ("service", Field::Service),
+    ("svc", Field::Service),
+    ("risk", Field::Risk),
+];
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        FIELDS.iter().find(|(n, _)| *n == name).map(|(_, f)| *f)
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(self, Field::Port | Field::Pid | Field::Memory | Field::Cpu)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    /// `:`, which also accepts ranges
+    Match,
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Op {
+    /// Split the operator off the front of `rest`
+    fn split(rest: &str) -> (Op, &str) {
+        for (symbol, op) in [
+            (">=", Op::Ge),
+            ("<=", Op::Le),
+            (">", Op::Gt),
+            ("<", Op::Lt),
+            ("=", Op::Eq),
+            (":", Op::Match),
+        ] {
+            if let Some(value) = rest.strip_prefix(symbol) {
+                return (op, value);
+            }
+        }
+        (Op::Match, rest)
+    }
+
+    fn compare<T: PartialOrd>(&self, actual: T, wanted: T) -> bool {
+        match self {
+            Op::Match | Op::Eq => actual == wanted,
+            Op::Gt => actual > wanted,
+            Op::Ge => actual >= wanted,
+            Op::Lt => actual < wanted,
+            Op::Le => actual <= wanted,
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Op::Match => ":",
+            Op::Eq => "=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+        })
+    }
+}
+
+impl Query {
+    /// Parse a query, reporting the first bad term
+    pub fn parse(input: &str) -> Result<Self, PortrError> {
+        let terms = tokenize(input)?
+            .iter()
+            .map(|token| parse_term(token))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { terms })
+    }
+
+    /// Whether the query has no terms
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Whether a port matches every term
+    pub fn matches(&self, info: &PortInfo) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.condition.matches(info) != term.negated)
+    }
+}
+
+impl FromStr for Query {
+    type Err = PortrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Query::parse(s)
+    }
+}
+
+fn invalid(message: String) -> PortrError {
+    PortrError::InvalidQuery(message)
+}
+
+/// Split on whitespace, keeping double-quoted runs together
+fn tokenize(input: &str) -> Result<Vec<String>, PortrError> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut started = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if started {
+                    tokens.push(std::mem::take(&mut current));
+                    started = false;
+                }
+            }
+            c => {
+                current.push(c);
+                started = true;
+            }
+        }
+    }
+    if quoted {
+        return Err(invalid("unterminated quote".to_string()));
+    }
+    if started {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+fn parse_term(token: &str) -> Result<Term, PortrError> {
+    let (negated, body) = match token.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, token),
+    };
+    if body.is_empty() {
+        return Err(invalid(format!("'{}' is missing a term after '!'", token)));
+    }
+
+    if let Some(flag) = Flag::from_name(body) {
+        return Ok(Term {
+            negated,
+            condition: Condition::Flag(flag),
+        });
+    }
+
+    // Only a word in front of the operator makes a field, so addresses like
+    // "127.0.0.1:8080" or "::1" stay plain searches
+    let condition = match body.find([':', '=', '<', '>']) {
+        Some(i) if i > 0 && body[..i].chars().all(|c| c.is_ascii_alphabetic()) => {
+            let name = &body[..i];
+            let field = Field::from_name(name).ok_or_else(|| {
+                // First name of each field, without the aliases
+                let mut names: Vec<&str> = Vec::new();
+                for (i, (n, field)) in FIELDS.iter().enumerate() {
+                    if i == 0 || FIELDS[i - 1].1 != *field {
+                        names.push(n);
+                    }
+                }
+                invalid(format!(
+                    "unknown field '{}' (expected one of: {})",
+                    name,
+                    names.join(", ")
+                ))
+            })?;
+            let (op, value) = Op::split(&body[i..]);
+            if value.is_empty() {
+                return Err(invalid(format!("'{}' is missing a value", body)));
+            }
+            parse_condition(field, name, op, value)?
+        }
+        _ => Condition::Text(body.to_lowercase()),
+    };
+
+    Ok(Term { negated, condition })
+}
+
+fn parse_condition(field: Field, name: &str, op: Op, value: &str) -> Result<Condition, PortrError> {
+    if field == Field::Risk {
+        let level = RiskLevel::from_name(value).ok_or_else(|| {
+            invalid(format!(
+                "unknown risk level '{}' (expected low, medium, high or critical)",
+                value
+            ))
+        })?;
+        return Ok(Condition::Risk(op, level));
+    }
+
+    if field.is_numeric() {
+        // A leading '-' can't start a range: none of the fields go negative
+        if let (Op::Match | Op::Eq, Some((low, high))) = (op, value.split_once('-')) {
+            let (low, high) = (
+                parse_number(field, name, low)?,
+                parse_number(field, name, high)?,
+            );
+            if low > high {
+                return Err(invalid(format!("empty range '{}{}{}'", name, op, value)));
+            }
+            return Ok(Condition::Range(field, low, high));
+        }
+        return Ok(Condition::Compare(
+            field,
+            op,
+            parse_number(field, name, value)?,
+        ));
+    }
+
+    if !matches!(op, Op::Match | Op::Eq) {
+        return Err(invalid(format!(
+            "'{}' can't be compared with '{}' (use {}:value)",
+            name, op, name
+        )));
+    }
+    Ok(match field {
+        Field::User => Condition::User(value.to_string()),
+        _ => Condition::Contains(field, value.to_lowercase()),
+    })
+}
+
+/// Parse a number for a numeric field; memory takes MB or GB, CPU a
+/// trailing '%'
+fn parse_number(field: Field, name: &str, value: &str) -> Result<f64, PortrError> {
+    let lower = value.to_lowercase();
+    let parsed = match field {
+        Field::Port => lower.parse::<u16>().ok().map(f64::from),
+        Field::Pid => lower.parse::<u32>().ok().map(f64::from),
+        Field::Memory => match lower.strip_suffix("gb").or_else(|| lower.strip_suffix('g')) {
+            Some(gb) => gb.parse::<f64>().ok().map(|v| v * 1024.0),
+            None => lower
+                .strip_suffix("mb")
+                .or_else(|| lower.strip_suffix('m'))
+                .unwrap_or(&lower)
+                .parse::<f64>()
+                .ok(),
+        },
+        _ => lower.trim_end_matches('%').parse::<f64>().ok(),
+    };
+    parsed
+        .filter(|v| v.is_finite() && *v >= 0.0)
+        .ok_or_else(|| invalid(format!("invalid number '{}' for '{}'", value, name)))
+}
+
+impl Condition {
+    fn matches(&self, info: &PortInfo) -> bool {
+        match self {
+            Condition::Text(search) => info.matches_text(search),
+            Condition::Flag(Flag::Docker) => info.is_docker(),
+            Condition::Flag(Flag::Tunnel) => info.is_tunnel(),
+            Condition::Flag(Flag::Kube) => info.kube_forward.is_some(),
+            Condition::Flag(Flag::Critical) => info.requires_confirmation(),
+            Condition::Compare(field, op, wanted) => op.compare(number(info, *field), *wanted),
+            Condition::Range(field, low, high) => {
+                let actual = number(info, *field);
+                actual >= *low && actual <= *high
+            }
+            Condition::Contains(field, value) => match field {
+                Field::Process => info.display_name().to_lowercase().contains(value),
+                Field::State => info.state.to_lowercase().contains(value),
+                Field::Protocol => info.protocol.eq_ignore_ascii_case(value),
+                Field::Address => info.local_address.to_lowercase().contains(value),
+                Field::Service => services::short_name(info.port)
+                    .is_some_and(|s| s.to_lowercase().contains(value)),
+                _ => false,
+            },
+            Condition::User(user) => port::matches_user(info, user),
+            // Ports that aren't a known service count as low risk
+            Condition::Risk(op, level) => op.compare(info.risk().unwrap_or(RiskLevel::Low), *level),
+        }
+    }
+}
+
+fn number(info: &PortInfo, field: Field) -> f64 {
+    match field {
+        Field::Port => f64::from(info.port),
+        Field::Pid => f64::from(info.pid),
+        Field::Memory => info.memory_mb,
+        Field::Cpu => f64::from(info.cpu_percent),
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(port: u16, process: &str, memory_mb: f64) -> PortInfo {
+        PortInfo {
+            process_name: process.to_string(),
+            local_address: "127.0.0.1".to_string(),
+            user: Some("alice".to_string()),
+            memory_mb,
+            cpu_percent: 1.5,
+            uptime_secs: 60,
+            ..PortInfo::test_port(port, 1000 + u32::from(port % 1000))
+        }
+    }
+
+    fn matching(query: &str, ports: &[PortInfo]) -> Vec<u16> {
+        let query = Query::parse(query).unwrap();
+        ports
+            .iter()
+            .filter(|p| query.matches(p))
+            .map(|p| p.port)
+            .collect()
+    }
+
+    #[test]
+    fn test_query_fields_and_operators() {
+        let ports = vec![
+            port(3000, "node", 250.0),
+            port(3500, "node", 80.0),
+            port(5432, "postgres", 300.0),
+            port(8080, "docker-proxy", 20.0),
+        ];
+
+        assert_eq!(matching("", &ports), vec![3000, 3500, 5432, 8080]);
+        assert_eq!(
+            matching("port:3000-3999 proc:node", &ports),
+            vec![3000, 3500]
+        );
+        assert_eq!(matching("mem>200", &ports), vec![3000, 5432]);
+        assert_eq!(matching("mem<=80 !docker", &ports), vec![3500]);
+        assert_eq!(matching("mem>0.25gb", &ports), vec![5432]);
+        assert_eq!(
+            matching("port=5432 state:listen user:ALICE", &ports),
+            vec![5432]
+        );
+        assert_eq!(matching("risk>=high", &ports), vec![5432]);
+        assert_eq!(
+            matching("!risk>=high proto:tcp", &ports),
+            vec![3000, 3500, 8080]
+        );
+        assert_eq!(matching("svc:postgres", &ports), vec![5432]);
+        assert_eq!(matching("docker", &ports), vec![8080]);
+    }
+
+    #[test]
+    fn test_query_plain_words_search_like_before() {
+        let ports = vec![port(3000, "node", 10.0), port(6379, "redis-server", 10.0)];
+
+        assert_eq!(matching("REDIS", &ports), vec![6379]);
+        assert_eq!(matching("300", &ports), vec![3000]);
+        // Not a field, so an address is searched as text
+        assert_eq!(matching("127.0.0.1:3000", &ports), Vec::<u16>::new());
+        assert_eq!(matching("\"redis-server\" port:6379", &ports), vec![6379]);
+    }
+
+    #[test]
+    fn test_query_errors() {
+        let error = |query: &str| Query::parse(query).unwrap_err().to_string();
+
+        assert!(error("colour:red").contains("unknown field 'colour'"));
+        assert!(error("port:abc").contains("invalid number 'abc' for 'port'"));
+        assert!(error("port:99999").contains("invalid number"));
+        assert!(error("port:4000-3000").contains("empty range"));
+        assert!(error("mem>").contains("'mem>' is missing a value"));
+        assert!(error("proc>node").contains("'proc' can't be compared with '>'"));
+        assert!(error("risk>=severe").contains("unknown risk level 'severe'"));
+        assert!(error("proc:\"my app").contains("unterminated quote"));
+        assert!(error("!").contains("missing a term"));
+    }
+}
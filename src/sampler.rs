@@ -0,0 +1,212 @@
+//! Long-lived process sampler
+//!
+//! sysinfo derives CPU usage from the difference between two refreshes, so a
+//! `System` that is created and refreshed once always reports 0%. A
+//! `ProcessSampler` keeps one `System` alive across refreshes so that
+//! long-running views (dashboard, interactive mode, `portr watch`) show real
+//! CPU percentages. One-shot commands can take two samples a short interval
+//! apart with [`ProcessSampler::primed`].
+//!
+//! Refreshes are incremental: every process gets a cheap pass (name, parent,
+//! CPU times), while the expensive details (memory, executable path, owner
+//! and command line) are only read for the processes that own sockets. The
+//! same snapshot then serves port lookups and process tree walks.
+
+use std::time::{Duration, Instant};
+use sysinfo::{
+    Gid, Groups, Pid, ProcessRefreshKind, ProcessesToUpdate, System, Uid, UpdateKind, Users,
+    MINIMUM_CPU_UPDATE_INTERVAL,
+};
+
+/// Process table that is reused across refreshes
+pub struct ProcessSampler {
+    sys: System,
+    users: Users,
+    groups: Groups,
+    refreshes: u32,
+    last_refresh: Option<Instant>,
+}
+
+impl ProcessSampler {
+    /// Create an empty sampler. Nothing is read until the first refresh.
+    pub fn new() -> Self {
+        Self {
+            sys: System::new(),
+            users: Users::new_with_refreshed_list(),
+            groups: Groups::new_with_refreshed_list(),
+            refreshes: 0,
+            last_refresh: None,
+        }
+    }
+
+    /// Create a sampler that already holds one sample taken `interval` ago.
+    ///
+    /// The next [`refresh`](Self::refresh) yields accurate CPU usage. The
+    /// interval is clamped to sysinfo's minimum update interval.
+    pub fn primed(interval: Duration) -> Self {
+        let mut sampler = Self::new();
+        sampler.refresh();
+        std::thread::sleep(interval.max(MINIMUM_CPU_UPDATE_INTERVAL));
+        sampler
+    }
+
+    /// Refresh names, parents and CPU usage of all processes
+    ///
+    /// This is the cheap pass; it is enough for process tree walks.
+    pub fn refresh(&mut self) {
+        self.sys.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::new().with_cpu(),
+        );
+        self.refreshes = self.refreshes.saturating_add(1);
+        self.last_refresh = Some(Instant::now());
+    }
+
+    /// Refresh all processes, then read full details for `pids` only
+    ///
+    /// Used with the PIDs that own sockets, so memory, executable path, owner
+    /// and command line are never read for the thousands of unrelated
+    /// processes. The first pass still covers every process: sysinfo only
+    /// computes CPU usage when all processes are refreshed, and owners need
+    /// their parents' names.
+    pub fn refresh_owners(&mut self, pids: &[Pid]) {
+        self.refresh();
+        if pids.is_empty() {
+            return;
+        }
+        self.sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(pids),
+            false,
+            ProcessRefreshKind::new()
+                .with_memory()
+                .with_exe(UpdateKind::OnlyIfNotSet)
+                .with_user(UpdateKind::OnlyIfNotSet)
+                .with_cmd(UpdateKind::OnlyIfNotSet),
+        );
+    }
+
+    /// Refresh names and parents of `pids` only, without CPU usage
+    ///
+    /// For naming a few processes when no full pass is due, such as the
+    /// owners of established connections while a daemon does the port scan.
+    pub fn refresh_pids(&mut self, pids: &[Pid]) {
+        if pids.is_empty() {
+            return;
+        }
+        self.sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(pids),
+            false,
+            ProcessRefreshKind::new(),
+        );
+    }
+
+    /// Read memory usage for `pids`, which the cheap pass leaves out
+    ///
+    /// Used for process tree nodes that don't own sockets.
+    pub fn refresh_memory(&mut self, pids: &[Pid]) {
+        if pids.is_empty() {
+            return;
+        }
+        self.sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(pids),
+            false,
+            ProcessRefreshKind::new().with_memory(),
+        );
+    }
+
+    /// Whether CPU usage values are meaningful (at least two samples taken)
+    pub fn has_cpu_data(&self) -> bool {
+        self.refreshes >= 2
+    }
+
+    /// Time of the most recent refresh
+    pub fn last_refresh(&self) -> Option<Instant> {
+        self.last_refresh
+    }
+
+    /// The underlying process table
+    pub fn system(&self) -> &System {
+        &self.sys
+    }
+
+    /// Resolve a user ID to its name, falling back to the numeric ID
+    pub fn user_name(&self, uid: &Uid) -> String {
+        self.users
+            .get_user_by_id(uid)
+            .map(|u| u.name().to_string())
+            .unwrap_or_else(|| uid_to_string(uid))
+    }
+
+    /// Resolve a group ID to its name, falling back to the numeric ID
+    pub fn group_name(&self, gid: Gid) -> String {
+        self.groups
+            .list()
+            .iter()
+            .find(|g| *g.id() == gid)
+            .map(|g| g.name().to_string())
+            .unwrap_or_else(|| gid.to_string())
+    }
+}
+
+impl Default for ProcessSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(unix)]
+fn uid_to_string(uid: &Uid) -> String {
+    uid.to_string()
+}
+
+#[cfg(not(unix))]
+fn uid_to_string(uid: &Uid) -> String {
+    format!("{:?}", uid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_data_needs_two_samples() {
+        let mut sampler = ProcessSampler::new();
+        assert!(!sampler.has_cpu_data());
+        sampler.refresh();
+        assert!(!sampler.has_cpu_data());
+        sampler.refresh();
+        assert!(sampler.has_cpu_data());
+        assert!(sampler.last_refresh().is_some());
+    }
+
+    #[test]
+    fn test_primed_sampler_sees_own_process() {
+        let mut sampler = ProcessSampler::primed(Duration::from_millis(10));
+        sampler.refresh();
+        let pid = sysinfo::Pid::from_u32(std::process::id());
+        assert!(sampler.system().process(pid).is_some());
+    }
+
+    #[test]
+    fn test_refresh_pids_names_without_full_pass() {
+        let mut sampler = ProcessSampler::new();
+        let pid = Pid::from_u32(std::process::id());
+        sampler.refresh_pids(&[pid]);
+
+        assert!(sampler.system().process(pid).is_some());
+        assert_eq!(sampler.system().processes().len(), 1);
+        assert!(sampler.last_refresh().is_none());
+    }
+
+    #[test]
+    fn test_refresh_owners_reads_details_for_owners_only() {
+        let mut sampler = ProcessSampler::new();
+        let pid = Pid::from_u32(std::process::id());
+        sampler.refresh_owners(&[pid]);
+
+        let me = sampler.system().process(pid).expect("own process");
+        assert!(me.memory() > 0);
+        assert!(me.parent().is_some());
+    }
+}
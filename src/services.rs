@@ -19,8 +19,8 @@ pub struct ServiceInfo {
     pub process_hints: &'static [&'static str],
 }
 
-/// Risk level for killing a service
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Risk level for killing a service, ordered from low to critical
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum RiskLevel {
     /// Safe to kill - development/test services
     Low,
@@ -33,6 +33,17 @@ pub enum RiskLevel {
 }
 
 impl RiskLevel {
+    /// Parse a level name ("low", "medium", "high", "critical")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "low" => Some(RiskLevel::Low),
+            "medium" | "med" => Some(RiskLevel::Medium),
+            "high" => Some(RiskLevel::High),
+            "critical" | "crit" => Some(RiskLevel::Critical),
+            _ => None,
+        }
+    }
+
     /// Get colored warning string
     pub fn warning(&self) -> String {
         match self {
@@ -91,9 +102,9 @@ static KNOWN_SERVICES: &[ServiceInfo] = &[
     ServiceInfo {
         port: 8443,
         name: "HTTPS Alt",
-        description: "Alternative HTTPS",
+        description: "Alternative HTTPS or minikube API server",
         risk: RiskLevel::Low,
-        process_hints: &["java", "node"],
+        process_hints: &["java", "node", "minikube"],
     },
     // Databases
     ServiceInfo {
@@ -242,9 +253,23 @@ static KNOWN_SERVICES: &[ServiceInfo] = &[
     ServiceInfo {
         port: 6443,
         name: "Kubernetes",
-        description: "Kubernetes API server",
+        description: "Kubernetes API server (kind, k3s)",
         risk: RiskLevel::Critical,
-        process_hints: &["kube-apiserver", "k8s"],
+        process_hints: &["kube-apiserver", "k8s", "k3s"],
+    },
+    ServiceInfo {
+        port: 6550,
+        name: "k3d API",
+        description: "k3d cluster API server",
+        risk: RiskLevel::Medium,
+        process_hints: &["k3d", "docker-proxy"],
+    },
+    ServiceInfo {
+        port: 10254,
+        name: "Ingress NGINX",
+        description: "ingress-nginx controller health and metrics",
+        risk: RiskLevel::Medium,
+        process_hints: &["nginx-ingress-controller"],
     },
     ServiceInfo {
         port: 10250,
@@ -452,6 +477,28 @@ pub fn requires_confirmation(port: u16) -> bool {
         .unwrap_or(false)
 }
 
+/// Check if a container image runs a critical (stateful) service
+pub fn is_critical_image(image: &str) -> bool {
+    let critical_images = [
+        "postgres",
+        "mysql",
+        "mariadb",
+        "mongo",
+        "redis",
+        "elasticsearch",
+        "rabbitmq",
+        "kafka",
+        "zookeeper",
+        "consul",
+        "vault",
+        "etcd",
+        "minio",
+    ];
+
+    let image_lower = image.to_lowercase();
+    critical_images.iter().any(|&c| image_lower.contains(c))
+}
+
 /// Get a short service name for display
 pub fn short_name(port: u16) -> Option<&'static str> {
     lookup(port).map(|s| s.name)
@@ -511,4 +558,11 @@ mod tests {
         assert_eq!(short_name(11434), Some("Ollama"));
         assert_eq!(short_name(65432), None);
     }
+
+    #[test]
+    fn test_local_cluster_ports() {
+        assert_eq!(short_name(6550), Some("k3d API"));
+        assert!(lookup(6443).unwrap().description.contains("kind"));
+        assert!(lookup(8443).unwrap().description.contains("minikube"));
+    }
 }
@@ -2,10 +2,21 @@
 //!
 //! Manages ports, selection, filters, and all UI state.
 
+use super::keymap::Keymap;
+use super::metrics::Metrics;
+use super::tabs::{self, ContainerRow, Tab, TabView};
+use crate::columns::{Column, SortKey, TableOptions};
+use crate::config;
+use crate::error::PortrError;
 use crate::export;
-use crate::port::{self, PortInfo};
+use crate::history::{self, HistoryEvent, HistoryRecorder, SnapshotDiff};
+use crate::port::{self, Connection, ContainerRef, PortInfo, ProcessTreeRow};
 use crate::process;
+use crate::query::Query;
+use crate::sampler::ProcessSampler;
 use crate::services;
+use ratatui::layout::Rect;
+use std::collections::{HashSet, VecDeque};
 
 /// Filter mode for port display
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -15,13 +26,26 @@ pub enum FilterMode {
     UdpOnly,
 }
 
-/// Sort mode for port display
+/// Port table columns when none are chosen
+pub const DASHBOARD_COLUMNS: &[Column] = &[
+    Column::Port,
+    Column::Proto,
+    Column::Pid,
+    Column::Proc,
+    Column::Mem,
+    Column::Uptime,
+    Column::State,
+];
+
+/// Screen layout the app is drawn with
+///
+/// Both profiles share all state, keys and dialogs; only the chrome differs.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum SortMode {
-    Port,
-    Process,
-    Memory,
-    Pid,
+pub enum Profile {
+    /// Full dashboard (`portr dashboard`): banner, side details panel
+    Dashboard,
+    /// One-line header and footer, details below the table (`portr -i`)
+    Compact,
 }
 
 /// Export format for TUI export
@@ -58,6 +82,136 @@ impl ExportFormat {
     }
 }
 
+/// Kill waiting in the confirmation dialog
+///
+/// Opened for a critical service, or for the marked ports as a batch.
+#[derive(Debug, Clone)]
+pub struct KillConfirmation {
+    /// Ports as they were when the dialog opened
+    pub targets: Vec<PortInfo>,
+    /// Parent chain of a single target's process, target first
+    pub tree: Vec<(u32, String)>,
+    /// Direct children of a single target's process
+    pub children: Vec<(u32, String)>,
+    /// Marked container ports left out of the batch
+    pub skipped: usize,
+    /// Send SIGKILL instead of SIGTERM
+    pub force: bool,
+    /// What the user has typed so far
+    pub input: String,
+}
+
+impl KillConfirmation {
+    /// Dialog for `targets`, with a single target's tree read from `sampler`
+    fn new(targets: Vec<PortInfo>, sampler: &ProcessSampler) -> Self {
+        let (tree, children) = match targets.as_slice() {
+            [only] => (
+                port::get_process_tree_with(sampler, only.pid),
+                port::get_child_processes_with(sampler, only.pid),
+            ),
+            _ => (Vec::new(), Vec::new()),
+        };
+        Self {
+            targets,
+            tree,
+            children,
+            skipped: 0,
+            force: false,
+            input: String::new(),
+        }
+    }
+
+    /// Whether "yes" must be typed (a high-risk or critical service is included)
+    pub fn needs_yes(&self) -> bool {
+        self.targets.iter().any(|p| p.requires_confirmation())
+    }
+
+    /// Number of targets per risk level, highest first; `None` is unknown
+    pub fn risk_summary(&self) -> Vec<(Option<services::RiskLevel>, usize)> {
+        use services::RiskLevel;
+
+        let levels = [
+            Some(RiskLevel::Critical),
+            Some(RiskLevel::High),
+            Some(RiskLevel::Medium),
+            Some(RiskLevel::Low),
+            None,
+        ];
+        levels
+            .into_iter()
+            .map(|level| {
+                let count = self.targets.iter().filter(|p| p.risk() == level).count();
+                (level, count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+}
+
+/// Signal to a process tree node, waiting for confirmation
+#[derive(Debug, Clone)]
+pub struct ProcessSignal {
+    /// Node as it was when the dialog opened
+    pub node: ProcessTreeRow,
+    /// Node is a parent of the port's process
+    pub ancestor: bool,
+    /// Node holds a critical service's port
+    pub critical: bool,
+    /// Send SIGKILL instead of SIGTERM
+    pub force: bool,
+    /// What the user has typed so far
+    pub input: String,
+}
+
+impl ProcessSignal {
+    /// Whether "yes" must be typed: killing a parent takes down everything
+    /// it started, and critical services ask like they do in the table
+    pub fn needs_yes(&self) -> bool {
+        self.ancestor || self.critical
+    }
+}
+
+/// Container action waiting for the user to confirm it
+#[derive(Debug, Clone)]
+pub struct ContainerConfirmation {
+    /// Container as it was when the action was picked
+    pub container: ContainerRef,
+    /// Key of the picked entry in [`CONTAINER_ACTIONS`]
+    pub key: char,
+    /// What the user has typed so far
+    pub input: String,
+}
+
+impl ContainerConfirmation {
+    /// Whether "yes" must be typed: the image runs a critical service
+    pub fn needs_yes(&self) -> bool {
+        services::is_critical_image(&self.container.image)
+    }
+
+    /// Label and description of the picked action
+    pub fn action(&self) -> (&'static str, &'static str) {
+        CONTAINER_ACTIONS
+            .iter()
+            .find(|(key, _, _)| key.starts_with(self.key))
+            .map_or(("Stop", ""), |(_, label, desc)| (*label, *desc))
+    }
+}
+
+/// Screen regions from the last draw, for mouse hit-testing
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HitAreas {
+    /// Port table, including its border
+    pub table: Rect,
+    /// Index of the first visible table row
+    pub table_offset: usize,
+    /// Footer with key hints
+    pub footer: Rect,
+    /// Quick menu popup, while open
+    pub menu: Option<Rect>,
+    /// Tab bar
+    pub tabs: Rect,
+}
+
 /// Main application state
 pub struct App {
     /// All ports (unfiltered)
@@ -71,9 +225,13 @@ pub struct App {
     /// Current filter mode
     pub filter_mode: FilterMode,
     /// Current sort mode
-    pub sort_mode: SortMode,
+    pub sort: SortKey,
+    /// Columns chosen with `--columns` or `[table]`; also used by exports
+    pub columns: Option<Vec<Column>>,
     /// Text filter/search
     pub filter_text: String,
+    /// `filter_text` parsed as a query
+    pub query: Query,
     /// Filter input buffer (while typing)
     pub filter_input: String,
     /// Are we in input mode?
@@ -90,6 +248,12 @@ pub struct App {
     pub docker_only: bool,
     /// Filter critical only?
     pub critical_only: bool,
+    /// Filter SSH tunnels only?
+    pub tunnels_only: bool,
+    /// Filter to ports owned by the current user?
+    pub mine_only: bool,
+    /// Name of the user running the dashboard
+    pub current_user: Option<String>,
     /// Status message
     pub status_message: Option<String>,
     /// Status message timestamp
@@ -100,8 +264,60 @@ pub struct App {
     pub show_export: bool,
     /// Selected export format
     pub export_format: ExportFormat,
+    /// Process sampler kept across refreshes for real CPU usage
+    pub sampler: ProcessSampler,
+    /// Show container action dialog?
+    pub show_container_dialog: bool,
+    /// Seconds Docker waits for a graceful container stop
+    pub stop_timeout: u32,
+    /// Container action awaiting confirmation
+    pub container_confirm: Option<ContainerConfirmation>,
+    /// Port history recorder, when history is enabled in the config
+    pub history: Option<HistoryRecorder>,
+    /// Kill awaiting confirmation
+    pub kill_confirm: Option<KillConfirmation>,
+    /// Ports marked for batch actions, by (port, pid)
+    pub marked: HashSet<(u16, u32)>,
+    /// Where things were drawn last frame
+    pub hit_areas: HitAreas,
+    /// Active keybindings
+    pub keymap: Keymap,
+    /// Layout the app is drawn with
+    pub profile: Profile,
+    /// Show the process tree pane?
+    pub show_tree: bool,
+    /// Do navigation keys move through the tree instead of the table?
+    pub tree_focus: bool,
+    /// Process tree of the selected port, flattened
+    pub tree: Vec<ProcessTreeRow>,
+    /// PID the tree was built for
+    pub tree_pid: Option<u32>,
+    /// Selected row among the visible tree rows
+    pub tree_selected: usize,
+    /// PIDs whose subtree is collapsed
+    pub tree_collapsed: HashSet<u32>,
+    /// Signal to a tree node awaiting confirmation
+    pub process_signal: Option<ProcessSignal>,
+    /// Recent memory, CPU and connection samples per port
+    pub metrics: Metrics,
+    /// Active tab
+    pub tab: Tab,
+    /// Filter, sort and selection per tab, by [`Tab::index`]; the Listening
+    /// tab uses the fields above instead
+    pub views: [TabView; 4],
+    /// Established connections from the last refresh
+    pub connections: Vec<Connection>,
+    /// Docker containers, refreshed while the Containers tab is open
+    pub containers: Vec<ContainerRow>,
+    /// Binds and unbinds seen since the dashboard started, oldest first
+    pub events: VecDeque<HistoryEvent>,
+    /// Previous scan, for detecting binds and unbinds
+    pub event_diff: SnapshotDiff,
 }
 
+/// Events kept in the History tab
+pub const EVENT_LOG_LEN: usize = 500;
+
 /// Menu items - updated with Export option
 pub const MENU_ITEMS: &[(&str, &str, &str)] = &[
     ("1", "Dashboard", "Full TUI with details panel"),
@@ -115,38 +331,157 @@ pub const MENU_ITEMS: &[(&str, &str, &str)] = &[
     ("0", "Quit", "Exit portr"),
 ];
 
+/// Index of the menu item whose key is `c`
+pub fn menu_item_for_key(c: char) -> Option<usize> {
+    MENU_ITEMS.iter().position(|(key, _, _)| key.starts_with(c))
+}
+
+/// Container action dialog entries (key, label, description)
+pub const CONTAINER_ACTIONS: &[(&str, &str, &str)] = &[
+    ("s", "Stop", "docker stop, then kill after timeout"),
+    ("r", "Restart", "docker restart"),
+    ("p", "Pause", "docker pause - freeze processes"),
+    ("x", "Remove", "docker rm -f - delete the container"),
+];
+
 impl App {
     /// Create a new app with default state
     pub fn new() -> Self {
+        Self::with_profile(Profile::Dashboard)
+    }
+
+    /// Create a new app drawn with the given profile
+    pub fn with_profile(profile: Profile) -> Self {
+        let app_config = config::load_config();
         Self {
             all_ports: Vec::new(),
             ports: Vec::new(),
             selected: 0,
             running: true,
             filter_mode: FilterMode::All,
-            sort_mode: SortMode::Port,
+            sort: SortKey::default(),
+            columns: None,
             filter_text: String::new(),
+            query: Query::default(),
             filter_input: String::new(),
             input_mode: false,
-            show_details: true,
+            // The compact layout has no room for details until asked
+            show_details: profile == Profile::Dashboard,
             show_help: false,
             show_menu: false,
             menu_selected: 0,
             docker_only: false,
             critical_only: false,
+            tunnels_only: false,
+            mine_only: false,
+            current_user: port::current_user(),
             status_message: None,
             status_time: std::time::Instant::now(),
             tick: 0,
             show_export: false,
             export_format: ExportFormat::Json,
+            sampler: ProcessSampler::new(),
+            show_container_dialog: false,
+            container_confirm: None,
+            stop_timeout: app_config.docker.stop_timeout,
+            // A broken history file shouldn't keep the dashboard from starting
+            history: HistoryRecorder::from_config(&app_config.history)
+                .ok()
+                .flatten(),
+            kill_confirm: None,
+            marked: HashSet::new(),
+            hit_areas: HitAreas::default(),
+            // tui::run reports invalid [keys] before the app is built
+            keymap: Keymap::from_config(&app_config.keys).unwrap_or_default(),
+            profile,
+            show_tree: false,
+            tree_focus: false,
+            tree: Vec::new(),
+            tree_pid: None,
+            tree_selected: 0,
+            tree_collapsed: HashSet::new(),
+            process_signal: None,
+            metrics: Metrics::default(),
+            tab: Tab::Listening,
+            views: Default::default(),
+            connections: Vec::new(),
+            containers: Vec::new(),
+            events: VecDeque::new(),
+            event_diff: SnapshotDiff::new(),
+        }
+    }
+
+    /// Refresh on request, confirming it in the status bar
+    pub fn refresh(&mut self) {
+        match self.rescan(true) {
+            Ok(()) => self.set_status("Refreshed"),
+            Err(e) => self.set_status(&format!("✗ History: {}", e)),
         }
     }
 
-    /// Refresh port list from system
+    /// Refresh port list from system on the timer
+    ///
+    /// Leaves the status bar alone unless recording history fails, so the
+    /// outcome of a kill stays visible. Only this refresh adds a metrics
+    /// sample, so the sparklines keep an even time scale.
     pub fn refresh_ports(&mut self) {
-        self.all_ports = port::get_listening_ports().unwrap_or_default();
+        let recorded = self.rescan(true);
+        self.metrics.record(
+            &self.all_ports,
+            &port::established_counts(&self.connections),
+        );
+        if let Err(e) = recorded {
+            self.set_status(&format!("✗ History: {}", e));
+        }
+    }
+
+    /// Rescan after a kill or container action, without the daemon
+    ///
+    /// Its snapshot can predate the change by a whole interval.
+    fn refresh_after_change(&mut self) {
+        if let Err(e) = self.rescan(false) {
+            self.set_status(&format!("✗ History: {}", e));
+        }
+    }
+
+    /// Rescan ports and connections; the error is from the history log
+    ///
+    /// With `use_daemon`, a running daemon's fresh snapshot saves the scan.
+    fn rescan(&mut self, use_daemon: bool) -> Result<(), PortrError> {
+        let snapshot = use_daemon.then(crate::daemon::fresh_ports).flatten();
+        self.all_ports = match snapshot {
+            Some(ports) => ports,
+            None => port::get_listening_ports_with(&mut self.sampler).unwrap_or_default(),
+        };
+        // A failed connection scan only empties the Connections tab
+        self.connections = port::get_connections_with(&mut self.sampler).unwrap_or_default();
+        // The recorder's diff feeds the History tab when history is on
+        let (events, recorded) = match self.history {
+            Some(ref mut history) => match history.record(&self.all_ports) {
+                Ok(events) => (events, Ok(())),
+                Err(e) => (Vec::new(), Err(e)),
+            },
+            None => (
+                self.event_diff.update(&self.all_ports, history::unix_now()),
+                Ok(()),
+            ),
+        };
+        for event in events {
+            if self.events.len() == EVENT_LOG_LEN {
+                self.events.pop_front();
+            }
+            self.events.push_back(event);
+        }
+        if self.tab == Tab::Containers {
+            self.refresh_containers();
+        }
+        // Marks on listeners that went away would never show again
+        let all_ports = &self.all_ports;
+        self.marked
+            .retain(|(port, pid)| all_ports.iter().any(|p| p.port == *port && p.pid == *pid));
         self.apply_filters();
-        self.set_status("Refreshed");
+        self.refresh_tree();
+        recorded
     }
 
     /// Apply all filters and sorting
@@ -162,53 +497,43 @@ impl App {
                     FilterMode::UdpOnly => p.protocol.to_uppercase() == "UDP",
                 };
 
-                // Text filter (search)
-                let text_match = if self.filter_text.is_empty() {
-                    true
-                } else {
-                    let search = self.filter_text.to_lowercase();
-                    p.port.to_string().contains(&search)
-                        || p.process_name.to_lowercase().contains(&search)
-                        || p.pid.to_string().contains(&search)
-                        || p.local_address.to_lowercase().contains(&search)
-                        || p.protocol.to_lowercase().contains(&search)
-                };
+                // Search query
+                let text_match = self.query.matches(p);
 
-                // Docker filter - check if process is Docker-related
-                let docker_match = if self.docker_only {
-                    let name = p.process_name.to_lowercase();
-                    name.contains("docker")
-                        || name.contains("containerd")
-                        || name.contains("com.docker")
-                        || name == "vpnkit.exe"
-                        || name == "vpnkit"
+                // Docker filter - mapped containers and Docker's forwarders
+                let docker_match = !self.docker_only || p.is_docker();
+
+                // Critical filter
+                let critical_match = if self.critical_only {
+                    p.requires_confirmation()
                 } else {
                     true
                 };
 
-                // Critical filter
-                let critical_match = if self.critical_only {
-                    services::requires_confirmation(p.port)
+                // Tunnel filter
+                let tunnel_match = !self.tunnels_only || p.is_tunnel();
+
+                // Ownership filter
+                let mine_match = if self.mine_only {
+                    self.current_user
+                        .as_deref()
+                        .map(|u| port::matches_user(p, u))
+                        .unwrap_or(false)
                 } else {
                     true
                 };
 
-                proto_match && text_match && docker_match && critical_match
+                proto_match
+                    && text_match
+                    && docker_match
+                    && critical_match
+                    && tunnel_match
+                    && mine_match
             })
             .cloned()
             .collect();
 
-        // Sort
-        match self.sort_mode {
-            SortMode::Port => filtered.sort_by_key(|p| p.port),
-            SortMode::Process => filtered.sort_by(|a, b| a.process_name.cmp(&b.process_name)),
-            SortMode::Memory => filtered.sort_by(|a, b| {
-                b.memory_mb
-                    .partial_cmp(&a.memory_mb)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            }),
-            SortMode::Pid => filtered.sort_by_key(|p| p.pid),
-        }
+        self.sort.sort(&mut filtered);
 
         self.ports = filtered;
 
@@ -222,6 +547,27 @@ impl App {
 
     /// Apply filter from input
     pub fn apply_filter(&mut self) {
+        if self.tab != Tab::Listening {
+            let filter = self.filter_input.clone();
+            let view = self.view_mut();
+            view.filter = filter;
+            view.selected = 0;
+            let msg = match (self.view().filter.is_empty(), self.tab_len()) {
+                (true, n) => format!("Search cleared ({} rows)", n),
+                (false, 0) => format!("No matches for '{}'", self.filter_input),
+                (false, n) => format!("Found {} for '{}'", n, self.filter_input),
+            };
+            self.set_status(&msg);
+            return;
+        }
+        // A bad query keeps the previous filter and reports the error
+        match Query::parse(&self.filter_input) {
+            Ok(query) => self.query = query,
+            Err(e) => {
+                self.set_status(&e.to_string());
+                return;
+            }
+        }
         self.filter_text = self.filter_input.clone();
         self.apply_filters();
         if self.filter_text.is_empty() {
@@ -240,11 +586,124 @@ impl App {
     /// Clear search filter
     pub fn clear_filter(&mut self) {
         self.filter_text.clear();
+        self.query = Query::default();
         self.filter_input.clear();
         self.apply_filters();
         self.set_status(&format!("Filter cleared ({} ports)", self.ports.len()));
     }
 
+    /// Step back: clear marks, then filters, then quit
+    pub fn back(&mut self) {
+        if !self.marked.is_empty() {
+            self.marked.clear();
+            self.set_status("Marks cleared");
+        } else if !self.filter_text.is_empty()
+            || self.docker_only
+            || self.critical_only
+            || self.tunnels_only
+            || self.mine_only
+        {
+            self.clear_filter();
+            self.docker_only = false;
+            self.critical_only = false;
+            self.tunnels_only = false;
+            self.mine_only = false;
+            self.apply_filters();
+            self.set_status(&format!("Filters cleared ({} ports)", self.ports.len()));
+        } else {
+            self.running = false;
+        }
+    }
+
+    /// Filter, sort and selection of the active tab
+    pub fn view(&self) -> &TabView {
+        &self.views[self.tab.index()]
+    }
+
+    pub fn view_mut(&mut self) -> &mut TabView {
+        &mut self.views[self.tab.index()]
+    }
+
+    /// Number of rows the active tab shows
+    pub fn tab_len(&self) -> usize {
+        let view = self.view();
+        match self.tab {
+            Tab::Listening => self.ports.len(),
+            Tab::Connections => tabs::visible_rows(&self.connections, self.tab, view).len(),
+            Tab::Containers => tabs::visible_rows(&self.containers, self.tab, view).len(),
+            Tab::History => tabs::visible_rows(&self.events, self.tab, view).len(),
+        }
+    }
+
+    /// Switch to a tab, refreshing containers when it is the Containers tab
+    pub fn set_tab(&mut self, tab: Tab) {
+        self.tab = tab;
+        if tab == Tab::Containers {
+            self.refresh_containers();
+        }
+        // Rows may have come and gone while the tab was hidden
+        let last = self.tab_len().saturating_sub(1);
+        let view = self.view_mut();
+        view.selected = view.selected.min(last);
+    }
+
+    /// Move the active list tab's selection by `delta` rows
+    pub fn tab_move(&mut self, delta: isize) {
+        let last = self.tab_len().saturating_sub(1);
+        let view = self.view_mut();
+        view.selected = view.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Sort the active list tab by its next sortable column
+    pub fn cycle_tab_sort(&mut self) {
+        let columns = self.tab.sort_columns();
+        if columns.is_empty() {
+            return;
+        }
+        let view = self.view_mut();
+        view.sort = (view.sort + 1) % columns.len();
+        let name = self.tab.columns()[columns[self.view().sort]];
+        self.set_status(&format!("Sorted by {}", name.to_lowercase()));
+    }
+
+    /// Sort the active list tab by a column, if it is sortable
+    pub fn set_tab_sort_column(&mut self, column: usize) {
+        if let Some(i) = self.tab.sort_columns().iter().position(|c| *c == column) {
+            self.view_mut().sort = i;
+        }
+    }
+
+    /// Esc on a list tab: clear its filter, then go back to Listening
+    pub fn tab_back(&mut self) {
+        if self.view().filter.is_empty() {
+            self.set_tab(Tab::Listening);
+        } else {
+            self.view_mut().filter.clear();
+            self.set_status("Search cleared");
+        }
+    }
+
+    /// Reload the Containers tab from Docker
+    pub fn refresh_containers(&mut self) {
+        #[cfg(feature = "docker")]
+        {
+            use crate::docker;
+
+            self.containers = docker::get_all_containers()
+                .unwrap_or_default()
+                .iter()
+                .map(|c| ContainerRow {
+                    name: c.name.clone(),
+                    image: c.image.clone(),
+                    ports: c.published_ports(),
+                    compose: c.compose.as_ref().map(|s| s.to_string()),
+                    status: c.status.clone(),
+                    critical: services::is_critical_image(&c.image),
+                })
+                .collect();
+        }
+    }
+
     /// Move selection down
     pub fn next(&mut self) {
         if !self.ports.is_empty() {
@@ -301,22 +760,42 @@ impl App {
         self.set_status(&msg);
     }
 
-    /// Cycle through sort modes
+    /// Use the columns and sort order from the command line or config
+    pub fn set_table_options(&mut self, table: &TableOptions) {
+        self.columns = table.columns.clone();
+        self.sort = table.sort.unwrap_or_default();
+        self.apply_filters();
+    }
+
+    /// Columns of the port table
+    pub fn table_columns(&self) -> &[Column] {
+        self.columns.as_deref().unwrap_or(DASHBOARD_COLUMNS)
+    }
+
+    /// Sort by the next column of the table
     pub fn cycle_sort(&mut self) {
-        self.sort_mode = match self.sort_mode {
-            SortMode::Port => SortMode::Process,
-            SortMode::Process => SortMode::Memory,
-            SortMode::Memory => SortMode::Pid,
-            SortMode::Pid => SortMode::Port,
-        };
+        let columns = self.table_columns();
+        let next = columns
+            .iter()
+            .position(|c| *c == self.sort.column)
+            .map_or(0, |i| (i + 1) % columns.len());
+        self.set_sort(SortKey::new(columns[next]));
+    }
+
+    /// Sort by `column`; again reverses the order
+    pub fn sort_by_column(&mut self, column: Column) {
+        if self.sort.column == column {
+            self.set_sort(self.sort.reversed());
+        } else {
+            self.set_sort(SortKey::new(column));
+        }
+    }
+
+    /// Sort by `key`
+    pub fn set_sort(&mut self, key: SortKey) {
+        self.sort = key;
         self.apply_filters();
-        let msg = match self.sort_mode {
-            SortMode::Port => "Sort: PORT",
-            SortMode::Process => "Sort: PROCESS",
-            SortMode::Memory => "Sort: MEMORY",
-            SortMode::Pid => "Sort: PID",
-        };
-        self.set_status(msg);
+        self.set_status(&format!("Sort: {}", self.sort_label()));
     }
 
     /// Toggle Docker only filter
@@ -351,11 +830,240 @@ impl App {
         }
     }
 
+    /// Toggle SSH tunnels only filter
+    pub fn toggle_tunnel_filter(&mut self) {
+        self.tunnels_only = !self.tunnels_only;
+        self.apply_filters();
+        if self.tunnels_only {
+            if self.ports.is_empty() {
+                self.set_status("Tunnels: No SSH tunnels found");
+            } else {
+                self.set_status(&format!("Tunnels: {} SSH tunnels", self.ports.len()));
+            }
+        } else {
+            self.set_status(&format!("Tunnel filter OFF ({} ports)", self.ports.len()));
+        }
+    }
+
+    /// Toggle "mine only" filter (hide ports owned by other users)
+    pub fn toggle_mine_filter(&mut self) {
+        self.mine_only = !self.mine_only;
+        self.apply_filters();
+        if self.mine_only {
+            let user = self.current_user.as_deref().unwrap_or("unknown user");
+            self.set_status(&format!(
+                "Mine: {} ports owned by {}",
+                self.ports.len(),
+                user
+            ));
+        } else {
+            self.set_status(&format!("Mine filter OFF ({} ports)", self.ports.len()));
+        }
+    }
+
     /// Toggle details panel
     pub fn toggle_details(&mut self) {
         self.show_details = !self.show_details;
     }
 
+    /// Open and focus the process tree pane, or close it when focused
+    pub fn toggle_tree(&mut self) {
+        if !self.show_tree {
+            self.show_tree = true;
+            self.tree_focus = true;
+            self.tree_pid = None;
+            self.refresh_tree();
+        } else if !self.tree_focus {
+            self.tree_focus = true;
+        } else {
+            self.show_tree = false;
+            self.tree_focus = false;
+        }
+    }
+
+    /// Rebuild the process tree for the selected port
+    ///
+    /// The selected node is kept across refreshes of the same port; a new
+    /// port starts on its own process with everything expanded.
+    pub fn refresh_tree(&mut self) {
+        if !self.show_tree {
+            return;
+        }
+        let Some(pid) = self.get_selected().map(|p| p.pid) else {
+            self.tree.clear();
+            self.tree_pid = None;
+            return;
+        };
+
+        self.refresh_sampler();
+        let previous = self.visible_tree().get(self.tree_selected).map(|r| r.pid);
+        self.tree = port::process_tree_rows_with(&mut self.sampler, pid, &self.all_ports);
+
+        let keep = if self.tree_pid == Some(pid) {
+            previous
+        } else {
+            self.tree_collapsed.clear();
+            Some(pid)
+        };
+        self.tree_pid = Some(pid);
+        self.tree_selected = keep
+            .and_then(|keep| self.visible_tree().iter().position(|r| r.pid == keep))
+            .unwrap_or(0);
+    }
+
+    /// Refresh the process sampler for the tree unless the port scan just did
+    ///
+    /// With a daemon running, the port scan doesn't touch the sampler, so the
+    /// full pass only happens while the tree pane is open.
+    fn refresh_sampler(&mut self) {
+        let stale = self
+            .sampler
+            .last_refresh()
+            .is_none_or(|t| t.elapsed() > std::time::Duration::from_secs(1));
+        if stale {
+            self.sampler.refresh();
+        }
+    }
+
+    /// Tree rows not hidden under a collapsed node
+    pub fn visible_tree(&self) -> Vec<&ProcessTreeRow> {
+        let mut hidden_below: Option<usize> = None;
+        let mut rows = Vec::new();
+        for row in &self.tree {
+            if hidden_below.is_some_and(|depth| row.depth > depth) {
+                continue;
+            }
+            hidden_below = self.tree_collapsed.contains(&row.pid).then_some(row.depth);
+            rows.push(row);
+        }
+        rows
+    }
+
+    /// Whether a tree row has rows nested under it
+    pub fn tree_has_children(&self, pid: u32) -> bool {
+        let mut rows = self.tree.iter().skip_while(|r| r.pid != pid);
+        match (rows.next(), rows.next()) {
+            (Some(row), Some(next)) => next.depth > row.depth,
+            _ => false,
+        }
+    }
+
+    /// Move the tree selection by `delta` rows
+    pub fn tree_move(&mut self, delta: isize) {
+        let last = self.visible_tree().len().saturating_sub(1);
+        self.tree_selected = self.tree_selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Collapse or expand the selected tree node
+    pub fn toggle_tree_node(&mut self) {
+        let Some(pid) = self.visible_tree().get(self.tree_selected).map(|r| r.pid) else {
+            return;
+        };
+        if !self.tree_has_children(pid) {
+            return;
+        }
+        if !self.tree_collapsed.remove(&pid) {
+            self.tree_collapsed.insert(pid);
+        }
+    }
+
+    /// Open the signal dialog for the selected tree node
+    pub fn signal_tree_node(&mut self) {
+        let Some(node) = self
+            .visible_tree()
+            .get(self.tree_selected)
+            .map(|r| (*r).clone())
+        else {
+            return;
+        };
+        if node.pid <= 1 || node.pid == std::process::id() {
+            self.set_status(&format!(
+                "⚠ Refusing to signal PID {} ({})",
+                node.pid, node.name
+            ));
+            return;
+        }
+
+        // Same redirect as the table: forwarders go through their container
+        if let Some(index) = self
+            .ports
+            .iter()
+            .position(|p| p.pid == node.pid && p.container.is_some())
+        {
+            self.selected = index;
+            self.tree_focus = false;
+            self.show_container_dialog = true;
+            return;
+        }
+        if port::is_docker_process(&node.name) {
+            self.set_status(&format!(
+                "⚠ PID {} ({}) forwards container traffic - stop its container instead",
+                node.pid, node.name
+            ));
+            return;
+        }
+
+        let target_depth = self.tree.iter().find(|r| r.is_target).map(|r| r.depth);
+        self.process_signal = Some(ProcessSignal {
+            ancestor: target_depth.is_some_and(|depth| node.depth < depth),
+            critical: self
+                .all_ports
+                .iter()
+                .any(|p| p.pid == node.pid && p.requires_confirmation()),
+            node,
+            force: false,
+            input: String::new(),
+        });
+    }
+
+    /// Submit the tree signal dialog
+    ///
+    /// The node is left alone if its PID now belongs to another program.
+    pub fn confirm_signal(&mut self) {
+        let Some(signal) = self.process_signal.take() else {
+            return;
+        };
+        if signal.needs_yes() && !signal.input.trim().eq_ignore_ascii_case("yes") {
+            self.set_status("Cancelled - type yes to confirm");
+            return;
+        }
+
+        let (pid, name) = (signal.node.pid, &signal.node.name);
+        self.sampler.refresh();
+        let current = self
+            .sampler
+            .system()
+            .process(sysinfo::Pid::from_u32(pid))
+            .map(|p| p.name().to_string_lossy().to_string());
+        if current.as_ref() != Some(name) {
+            self.set_status(&format!(
+                "✗ PID {} is no longer {} - not signaled",
+                pid, name
+            ));
+            return;
+        }
+
+        match process::kill_process(pid, signal.force) {
+            Ok(_) => {
+                self.set_status(&format!(
+                    "✓ Sent {} to PID {} ({})",
+                    process::kill_signal_name(signal.force),
+                    pid,
+                    name
+                ));
+                self.refresh_after_change();
+            }
+            Err(e) => self.set_status(&format!("✗ Failed to signal: {}", e)),
+        }
+    }
+
+    /// Close the tree signal dialog without signaling
+    pub fn cancel_signal(&mut self) {
+        if self.process_signal.take().is_some() {
+            self.set_status("Cancelled");
+        }
+    }
+
     /// Toggle menu overlay
     pub fn toggle_menu(&mut self) {
         self.show_menu = !self.show_menu;
@@ -454,34 +1162,294 @@ impl App {
         }
     }
 
-    /// Kill selected port's process
+    /// Kill selected port's process, or open the batch dialog for marked ports
+    ///
+    /// Ports published by a Docker container open the container action
+    /// dialog instead of killing Docker's forwarder process.
     pub fn kill_selected(&mut self) {
-        if let Some(port_info) = self.get_selected() {
-            let pid = port_info.pid;
-            let port = port_info.port;
-            let name = port_info.process_name.clone();
+        if !self.marked.is_empty() {
+            self.kill_marked();
+            return;
+        }
+
+        let Some(port_info) = self.get_selected().cloned() else {
+            return;
+        };
+        if port_info.container.is_some() {
+            self.show_container_dialog = true;
+            return;
+        }
 
-            // Check if critical
-            if services::requires_confirmation(port) {
+        // An unmapped forwarder still carries some container's traffic
+        if port::is_docker_process(&port_info.process_name) {
+            let msg = format!(
+                "⚠ Port {} is forwarded by {} - stop its container instead",
+                port_info.port, port_info.process_name
+            );
+            self.set_status(&msg);
+            return;
+        }
+
+        // Critical services ask for "yes" in a dialog, like the CLI does;
+        // the compact profile asks y/N for every kill
+        if port_info.requires_confirmation() || self.profile == Profile::Compact {
+            // The tree comes from the last scan; when a daemon did that scan
+            // the sampler may not know the target yet
+            let pid = sysinfo::Pid::from_u32(port_info.pid);
+            if self.sampler.system().process(pid).is_none() {
+                self.sampler.refresh();
+            }
+            self.kill_confirm = Some(KillConfirmation::new(vec![port_info], &self.sampler));
+            return;
+        }
+
+        self.kill_port(&port_info, false);
+    }
+
+    /// Open one combined confirmation for every marked port
+    ///
+    /// Container ports are left out: they need a container action.
+    fn kill_marked(&mut self) {
+        let (containers, targets): (Vec<PortInfo>, Vec<PortInfo>) = self
+            .all_ports
+            .iter()
+            .filter(|p| self.marked.contains(&(p.port, p.pid)))
+            .cloned()
+            .partition(|p| p.is_docker());
+
+        if targets.is_empty() {
+            self.set_status("⚠ Only container ports are marked - use K on each");
+            return;
+        }
+        self.kill_confirm = Some(KillConfirmation {
+            skipped: containers.len(),
+            ..KillConfirmation::new(targets, &self.sampler)
+        });
+    }
+
+    /// Submit the kill confirmation dialog
+    ///
+    /// High-risk targets need a typed "yes". Ports whose process changed
+    /// since the dialog opened are left alone.
+    pub fn confirm_kill(&mut self) {
+        let Some(confirm) = self.kill_confirm.take() else {
+            return;
+        };
+        if confirm.needs_yes() && !confirm.input.trim().eq_ignore_ascii_case("yes") {
+            self.set_status("Cancelled - type yes to kill a critical service");
+            return;
+        }
+
+        let (current, gone): (Vec<&PortInfo>, Vec<&PortInfo>) =
+            confirm.targets.iter().partition(|target| {
+                self.all_ports
+                    .iter()
+                    .any(|p| p.port == target.port && p.pid == target.pid)
+            });
+
+        if let [target] = confirm.targets.as_slice() {
+            if gone.is_empty() {
+                let target = target.clone();
+                self.kill_port(&target, confirm.force);
+            } else {
                 self.set_status(&format!(
-                    "⚠ Port {} is critical! Use CLI: portr {} --kill",
-                    port, port
+                    "✗ Port {} is no longer held by PID {} - not killed",
+                    target.port, target.pid
                 ));
-                return;
             }
+            return;
+        }
 
-            match process::kill_process(pid, false) {
-                Ok(_) => {
-                    self.set_status(&format!("✓ Killed PID {} ({}) on port {}", pid, name, port));
-                    self.refresh_ports();
-                }
-                Err(e) => {
-                    self.set_status(&format!("✗ Failed to kill: {}", e));
+        // Several ports can share one process
+        let mut pids: Vec<u32> = current.iter().map(|p| p.pid).collect();
+        pids.sort_unstable();
+        pids.dedup();
+        let failed: Vec<(u32, PortrError)> = pids
+            .iter()
+            .filter_map(|&pid| {
+                process::kill_process(pid, confirm.force)
+                    .err()
+                    .map(|e| (pid, e))
+            })
+            .collect();
+
+        let mark = if failed.is_empty() { "✓" } else { "✗" };
+        let mut msg = format!(
+            "{} Killed {} of {} processes",
+            mark,
+            pids.len() - failed.len(),
+            pids.len()
+        );
+        if let Some((_, first)) = failed.first() {
+            let failed_pids: Vec<String> = failed.iter().map(|(pid, _)| pid.to_string()).collect();
+            msg.push_str(&format!(
+                ", failed PID {} ({})",
+                failed_pids.join(", "),
+                first
+            ));
+        }
+        if !gone.is_empty() {
+            msg.push_str(&format!(", {} port(s) changed owner", gone.len()));
+        }
+        if confirm.skipped > 0 {
+            msg.push_str(&format!(", {} container port(s) skipped", confirm.skipped));
+        }
+        self.marked.clear();
+        self.set_status(&msg);
+        self.refresh_after_change();
+    }
+
+    /// Close the kill confirmation dialog without killing
+    pub fn cancel_kill(&mut self) {
+        if self.kill_confirm.take().is_some() {
+            self.set_status("Cancelled");
+        }
+    }
+
+    /// Send the kill signal to the process behind `port_info`
+    fn kill_port(&mut self, port_info: &PortInfo, force: bool) {
+        let (pid, port) = (port_info.pid, port_info.port);
+
+        match process::kill_process(pid, force) {
+            Ok(_) => {
+                match (&port_info.kube_forward, &port_info.tunnel) {
+                    (Some(fwd), _) => self.set_status(&format!(
+                        "✓ Closed port-forward {} on port {} (PID {})",
+                        fwd, port, pid
+                    )),
+                    (None, Some(tunnel)) => self.set_status(&format!(
+                        "✓ Closed SSH tunnel {} on port {} (PID {})",
+                        tunnel, port, pid
+                    )),
+                    (None, None) => self.set_status(&format!(
+                        "✓ Killed PID {} ({}) on port {}",
+                        pid, port_info.process_name, port
+                    )),
                 }
+                self.refresh_after_change();
             }
+            Err(e) => {
+                self.set_status(&format!("✗ Failed to kill: {}", e));
+            }
+        }
+    }
+
+    /// Mark or unmark the selected port for batch actions
+    pub fn toggle_mark(&mut self) {
+        if let Some(p) = self.get_selected() {
+            let key = (p.port, p.pid);
+            if !self.marked.remove(&key) {
+                self.marked.insert(key);
+            }
+            self.next();
         }
     }
 
+    /// Mark every port matching the current filters, or unmark them if all
+    /// already are
+    pub fn toggle_mark_all(&mut self) {
+        let visible: Vec<(u16, u32)> = self.ports.iter().map(|p| (p.port, p.pid)).collect();
+        if !visible.is_empty() && visible.iter().all(|key| self.marked.contains(key)) {
+            for key in &visible {
+                self.marked.remove(key);
+            }
+            self.set_status("Unmarked all");
+        } else {
+            self.marked.extend(visible);
+            self.set_status(&format!("{} ports marked", self.marked.len()));
+        }
+    }
+
+    /// Whether a port is marked
+    pub fn is_marked(&self, port_info: &PortInfo) -> bool {
+        self.marked.contains(&(port_info.port, port_info.pid))
+    }
+
+    /// Handle a key in the container action dialog; unknown keys cancel
+    ///
+    /// Actions on critical images (databases, brokers) open a confirmation
+    /// that needs a typed "yes"; removing any other container asks y/N.
+    pub fn container_dialog_key(&mut self, key: Option<char>) {
+        self.show_container_dialog = false;
+
+        let key = key.filter(|key| {
+            CONTAINER_ACTIONS
+                .iter()
+                .any(|(k, _, _)| k.starts_with(*key))
+        });
+        let (Some(key), Some(container)) =
+            (key, self.get_selected().and_then(|p| p.container.clone()))
+        else {
+            self.set_status("Cancelled");
+            return;
+        };
+
+        if key == 'x' || services::is_critical_image(&container.image) {
+            self.container_confirm = Some(ContainerConfirmation {
+                container,
+                key,
+                input: String::new(),
+            });
+            return;
+        }
+        self.container_action(&container, key);
+    }
+
+    /// Submit the container action confirmation
+    pub fn confirm_container_action(&mut self) {
+        let Some(confirm) = self.container_confirm.take() else {
+            return;
+        };
+        if confirm.needs_yes() && !confirm.input.trim().eq_ignore_ascii_case("yes") {
+            self.set_status("Cancelled - type yes to confirm");
+            return;
+        }
+        self.container_action(&confirm.container, confirm.key);
+    }
+
+    /// Close the container action confirmation without acting
+    pub fn cancel_container_action(&mut self) {
+        if self.container_confirm.take().is_some() {
+            self.set_status("Cancelled");
+        }
+    }
+
+    /// Apply the action picked with `key` to a container
+    #[cfg(feature = "docker")]
+    fn container_action(&mut self, container: &ContainerRef, key: char) {
+        use crate::docker::{self, ContainerAction};
+
+        let action = match key {
+            'r' => ContainerAction::Restart,
+            'p' => ContainerAction::Pause,
+            'x' => ContainerAction::Remove,
+            _ => ContainerAction::Stop,
+        };
+        match docker::apply_container_action(&container.name, action, self.stop_timeout) {
+            Ok(()) => {
+                self.set_status(&format!(
+                    "✓ {} container {}",
+                    action.past_tense(),
+                    container.name
+                ));
+                self.refresh_after_change();
+            }
+            Err(e) => {
+                self.set_status(&format!(
+                    "✗ Failed to {} {}: {}",
+                    action.verb(),
+                    container.name,
+                    e
+                ));
+            }
+        }
+    }
+
+    #[cfg(not(feature = "docker"))]
+    fn container_action(&mut self, _container: &ContainerRef, _key: char) {
+        self.set_status("Docker feature not enabled");
+    }
+
     /// Get currently selected port info
     pub fn get_selected(&self) -> Option<&PortInfo> {
         self.ports.get(self.selected)
@@ -497,6 +1465,11 @@ impl App {
     pub fn on_tick(&mut self) {
         self.tick = self.tick.wrapping_add(1);
 
+        // The tree follows the table selection
+        if self.show_tree && self.get_selected().map(|p| p.pid) != self.tree_pid {
+            self.refresh_tree();
+        }
+
         // Clear old status messages
         if self.status_message.is_some() && self.status_time.elapsed().as_secs() > 3 {
             self.status_message = None;
@@ -512,14 +1485,10 @@ impl App {
         }
     }
 
-    /// Get sort mode display string
-    pub fn sort_mode_str(&self) -> &'static str {
-        match self.sort_mode {
-            SortMode::Port => "PORT",
-            SortMode::Process => "PROC",
-            SortMode::Memory => "MEM",
-            SortMode::Pid => "PID",
-        }
+    /// Sort column and direction, e.g. "MEMORY ↓"
+    pub fn sort_label(&self) -> String {
+        let arrow = if self.sort.descending { "↓" } else { "↑" };
+        format!("{} {}", self.sort.column.header(), arrow)
     }
 
     /// Toggle export popup
@@ -540,7 +1509,18 @@ impl App {
     pub fn do_export(&mut self) {
         use std::fs;
 
-        if self.ports.is_empty() {
+        // Marked ports when there are any, otherwise everything visible
+        let ports: Vec<PortInfo> = if self.marked.is_empty() {
+            self.ports.clone()
+        } else {
+            self.all_ports
+                .iter()
+                .filter(|p| self.is_marked(p))
+                .cloned()
+                .collect()
+        };
+
+        if ports.is_empty() {
             self.set_status("No ports to export");
             self.show_export = false;
             return;
@@ -555,20 +1535,15 @@ impl App {
         );
 
         let content = match self.export_format {
-            ExportFormat::Json => {
-                export::to_json(&self.ports).unwrap_or_else(|e| format!("Error: {}", e))
-            }
-            ExportFormat::Csv => export::to_csv(&self.ports),
-            ExportFormat::Markdown => export::to_markdown(&self.ports),
+            ExportFormat::Json => export::ports_to_json(&ports, self.columns.as_deref())
+                .unwrap_or_else(|e| format!("Error: {}", e)),
+            ExportFormat::Csv => export::to_csv_with(&ports, self.columns.as_deref()),
+            ExportFormat::Markdown => export::to_markdown_with(&ports, self.columns.as_deref()),
         };
 
         match fs::write(&filename, &content) {
             Ok(_) => {
-                self.set_status(&format!(
-                    "✓ Exported {} ports to {}",
-                    self.ports.len(),
-                    filename
-                ));
+                self.set_status(&format!("✓ Exported {} ports to {}", ports.len(), filename));
             }
             Err(e) => {
                 self.set_status(&format!("✗ Export failed: {}", e));
@@ -589,6 +1564,27 @@ impl Default for App {
 mod tests {
     use super::*;
 
+    fn sample_port(port: u16) -> PortInfo {
+        PortInfo {
+            local_address: format!("0.0.0.0:{}", port),
+            memory_mb: 10.0,
+            uptime_secs: 60,
+            ..PortInfo::test_port(port, 1000 + u32::from(port))
+        }
+    }
+
+    fn container_port(port: u16, image: &str) -> PortInfo {
+        let mut info = sample_port(port);
+        info.process_name = "docker-proxy".to_string();
+        info.container = Some(crate::port::ContainerRef {
+            id: "abc123".to_string(),
+            name: "web".to_string(),
+            image: image.to_string(),
+            compose: None,
+        });
+        info
+    }
+
     #[test]
     fn test_app_new() {
         let app = App::new();
@@ -609,11 +1605,391 @@ mod tests {
         assert_eq!(app.filter_mode, FilterMode::All);
     }
 
+    #[test]
+    fn test_mine_filter_hides_other_users() {
+        let mut app = App::new();
+        app.current_user = Some("alice".to_string());
+        let mut mine = sample_port(3000);
+        mine.user = Some("alice".to_string());
+        let mut other = sample_port(5432);
+        other.user = Some("postgres".to_string());
+        app.all_ports = vec![mine, other];
+
+        app.toggle_mine_filter();
+        assert_eq!(app.ports.len(), 1);
+        assert_eq!(app.ports[0].port, 3000);
+
+        app.toggle_mine_filter();
+        assert_eq!(app.ports.len(), 2);
+    }
+
+    #[test]
+    fn test_tunnel_filter() {
+        let mut app = App::new();
+        let mut tunnel = sample_port(5432);
+        tunnel.process_name = "ssh".to_string();
+        tunnel.tunnel = crate::tunnel::detect_tunnel(
+            "ssh",
+            None,
+            &["ssh", "-L", "5432:db:5432", "bastion"].map(String::from),
+            5432,
+        );
+        app.all_ports = vec![sample_port(3000), tunnel];
+
+        app.toggle_tunnel_filter();
+        assert_eq!(app.ports.len(), 1);
+        assert_eq!(app.ports[0].port, 5432);
+        // A tunnel on a database port is not a critical service
+        assert!(!app.ports[0].requires_confirmation());
+
+        app.toggle_tunnel_filter();
+        assert_eq!(app.ports.len(), 2);
+    }
+
+    #[test]
+    fn test_search_matches_service_names() {
+        let mut app = App::new();
+        app.all_ports = vec![sample_port(5432), sample_port(3000)];
+        app.filter_input = "postgres".to_string();
+        app.apply_filter();
+        assert_eq!(app.ports.len(), 1);
+        assert_eq!(app.ports[0].port, 5432);
+    }
+
+    #[test]
+    fn test_search_query_errors_keep_filter() {
+        let mut app = App::new();
+        app.all_ports = vec![sample_port(5432), sample_port(3000)];
+        app.filter_input = "port:3000-3999".to_string();
+        app.apply_filter();
+        assert_eq!(app.ports.len(), 1);
+
+        app.filter_input = "port>abc".to_string();
+        app.apply_filter();
+        assert_eq!(app.filter_text, "port:3000-3999");
+        assert_eq!(app.ports.len(), 1);
+        assert!(app
+            .status_message
+            .as_ref()
+            .is_some_and(|msg| msg.contains("invalid number 'abc' for 'port'")));
+    }
+
     #[test]
     fn test_sort_cycle() {
         let mut app = App::new();
-        assert_eq!(app.sort_mode, SortMode::Port);
+        assert_eq!(app.sort, SortKey::new(Column::Port));
         app.cycle_sort();
-        assert_eq!(app.sort_mode, SortMode::Process);
+        assert_eq!(app.sort, SortKey::new(Column::Proto));
+        app.sort_by_column(Column::Mem);
+        assert!(app.sort.descending);
+        app.sort_by_column(Column::Mem);
+        assert!(!app.sort.descending);
+
+        // Cycling follows the chosen columns
+        app.set_table_options(&TableOptions {
+            columns: Some(vec![Column::Cpu, Column::Port]),
+            sort: None,
+        });
+        app.cycle_sort();
+        assert_eq!(app.sort, SortKey::new(Column::Cpu));
+    }
+
+    #[test]
+    fn test_kill_container_port_opens_dialog() {
+        let mut app = App::new();
+        app.ports = vec![container_port(8080, "nginx")];
+
+        app.kill_selected();
+        assert!(app.show_container_dialog);
+
+        app.container_dialog_key(None);
+        assert!(!app.show_container_dialog);
+        assert_eq!(app.status_message.as_deref(), Some("Cancelled"));
+    }
+
+    #[test]
+    fn test_container_remove_needs_confirmation() {
+        let mut app = App::new();
+        app.ports = vec![container_port(8080, "nginx")];
+
+        // Other actions run straight away; remove asks y/N first
+        app.kill_selected();
+        app.container_dialog_key(Some('x'));
+        let confirm = app.container_confirm.as_ref().expect("confirmation open");
+        assert_eq!(confirm.action().0, "Remove");
+        assert!(!confirm.needs_yes());
+        app.cancel_container_action();
+        assert_eq!(app.status_message.as_deref(), Some("Cancelled"));
+    }
+
+    #[test]
+    fn test_critical_container_action_needs_typed_yes() {
+        let mut app = App::new();
+        app.ports = vec![container_port(5432, "postgres:15")];
+
+        app.kill_selected();
+        app.container_dialog_key(Some('s'));
+        assert!(!app.show_container_dialog);
+        let confirm = app.container_confirm.as_mut().expect("confirmation open");
+        assert_eq!(confirm.key, 's');
+        assert!(confirm.needs_yes());
+
+        // A single y is not enough
+        confirm.input.push('y');
+        app.confirm_container_action();
+        assert!(app.container_confirm.is_none());
+        assert!(app
+            .status_message
+            .as_deref()
+            .is_some_and(|m| m.starts_with("Cancelled")));
+    }
+
+    #[test]
+    fn test_critical_kill_needs_typed_yes() {
+        let mut app = App::new();
+        app.ports = vec![sample_port(5432)];
+
+        app.kill_selected();
+        let confirm = app.kill_confirm.as_mut().expect("dialog open");
+        confirm.input.push('y');
+        app.confirm_kill();
+        assert!(app.kill_confirm.is_none());
+        assert!(app
+            .status_message
+            .as_deref()
+            .is_some_and(|m| m.starts_with("Cancelled")));
+
+        // "yes" on a port whose owner went away kills nothing
+        app.kill_selected();
+        app.kill_confirm.as_mut().unwrap().input = "yes".to_string();
+        app.confirm_kill();
+        assert!(app
+            .status_message
+            .as_deref()
+            .is_some_and(|m| m.contains("no longer held by PID 6432")));
+    }
+
+    #[test]
+    fn test_compact_profile_confirms_every_kill() {
+        let mut app = App::with_profile(Profile::Compact);
+        app.ports = vec![sample_port(3000)];
+
+        app.kill_selected();
+        let confirm = app.kill_confirm.as_ref().expect("dialog open");
+        assert!(!confirm.needs_yes());
+        app.cancel_kill();
+        assert_eq!(app.status_message.as_deref(), Some("Cancelled"));
+    }
+
+    #[test]
+    fn test_manual_refresh_adds_no_metrics_sample() {
+        let mut app = App::new();
+        app.refresh();
+
+        assert_eq!(app.status_message.as_deref(), Some("Refreshed"));
+        assert!(app
+            .all_ports
+            .iter()
+            .all(|p| app.metrics.history(p).is_none()));
+    }
+
+    #[test]
+    fn test_mark_all_and_batch_confirmation() {
+        let mut app = App::new();
+        app.all_ports = vec![
+            sample_port(3000),
+            sample_port(5432),
+            container_port(8080, "nginx"),
+        ];
+        app.apply_filters();
+
+        app.toggle_mark();
+        assert_eq!(app.marked.len(), 1);
+        app.toggle_mark_all();
+        assert_eq!(app.marked.len(), 3);
+
+        app.kill_selected();
+        let confirm = app.kill_confirm.as_ref().expect("batch dialog open");
+        assert_eq!(confirm.targets.len(), 2);
+        assert_eq!(confirm.skipped, 1);
+        // PostgreSQL in the batch means "yes" must be typed
+        assert!(confirm.needs_yes());
+        assert_eq!(
+            confirm.risk_summary(),
+            vec![
+                (Some(services::RiskLevel::Critical), 1),
+                (Some(services::RiskLevel::Low), 1)
+            ]
+        );
+
+        app.confirm_kill();
+        assert!(app.kill_confirm.is_none());
+        assert_eq!(app.marked.len(), 3);
+
+        // Marking everything again unmarks it
+        app.toggle_mark_all();
+        assert!(app.marked.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_batch_kill_reports_failures() {
+        // PIDs above any pid_max, so nothing is signaled
+        let mut app = App::new();
+        app.all_ports = vec![
+            PortInfo {
+                pid: 2_000_000_000,
+                ..sample_port(3000)
+            },
+            PortInfo {
+                pid: 2_000_000_001,
+                ..sample_port(3001)
+            },
+        ];
+        app.apply_filters();
+        app.toggle_mark_all();
+
+        app.kill_selected();
+        app.confirm_kill();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some(
+                "✗ Killed 0 of 2 processes, failed PID 2000000000, 2000000001 \
+                 (process not found: PID 2000000000)"
+            )
+        );
+    }
+
+    #[test]
+    fn test_process_tree_pane() {
+        // The test binary stands in for the port's process
+        let mut app = App::new();
+        app.all_ports = vec![PortInfo {
+            pid: std::process::id(),
+            ..sample_port(3000)
+        }];
+        app.apply_filters();
+        app.toggle_tree();
+        assert!(app.show_tree && app.tree_focus);
+
+        let target = app.visible_tree()[app.tree_selected].clone();
+        assert!(target.is_target);
+        assert_eq!(target.ports, vec![3000]);
+        assert!(target.memory_mb > 0.0);
+
+        // Our own process is never signaled
+        app.signal_tree_node();
+        assert!(app.process_signal.is_none());
+    }
+
+    fn tree_row(pid: u32, name: &str, depth: usize, ports: Vec<u16>) -> ProcessTreeRow {
+        ProcessTreeRow {
+            pid,
+            name: name.to_string(),
+            depth,
+            memory_mb: 10.0,
+            cpu_percent: 0.0,
+            ports,
+            is_target: false,
+        }
+    }
+
+    /// shell -> node (port 3000) -> postgres (port 5432), with node selected
+    fn tree_app() -> App {
+        let mut app = App::new();
+        app.all_ports = vec![sample_port(3000), sample_port(5432)];
+        app.apply_filters();
+        app.tree = vec![
+            tree_row(900, "bash", 0, vec![]),
+            ProcessTreeRow {
+                is_target: true,
+                ..tree_row(4000, "node", 1, vec![3000])
+            },
+            tree_row(6432, "postgres", 2, vec![5432]),
+        ];
+        app.tree_pid = Some(4000);
+        app.show_tree = true;
+        app.tree_focus = true;
+        app.tree_selected = 1;
+        app
+    }
+
+    #[test]
+    fn test_tree_signal_confirmation() {
+        let mut app = tree_app();
+
+        // The port's own process asks like the table does
+        app.signal_tree_node();
+        let signal = app.process_signal.take().expect("dialog open");
+        assert_eq!(signal.node.pid, 4000);
+        assert!(!signal.needs_yes());
+
+        // A parent needs a typed yes
+        app.tree_move(-1);
+        app.signal_tree_node();
+        let signal = app.process_signal.take().expect("dialog open");
+        assert!(signal.ancestor);
+        assert!(signal.needs_yes());
+
+        // So does a process holding a critical service's port
+        app.tree_move(2);
+        app.signal_tree_node();
+        let signal = app.process_signal.as_mut().expect("dialog open");
+        assert!(!signal.ancestor && signal.critical);
+        signal.input.push('y');
+        app.confirm_signal();
+        assert!(app.process_signal.is_none());
+        assert!(app
+            .status_message
+            .as_deref()
+            .is_some_and(|m| m.starts_with("Cancelled")));
+
+        // Collapsing the parent hides its subtree
+        app.tree_selected = 0;
+        app.toggle_tree_node();
+        assert_eq!(app.visible_tree().len(), 1);
+        app.toggle_tree_node();
+        assert_eq!(app.visible_tree().len(), 3);
+    }
+
+    #[test]
+    fn test_tree_signal_redirects_docker() {
+        let mut app = tree_app();
+        app.all_ports.push(container_port(8080, "nginx"));
+        app.apply_filters();
+        app.tree.push(tree_row(9080, "docker-proxy", 1, vec![8080]));
+
+        // A forwarder with a container opens the container dialog for its port
+        app.tree_selected = 3;
+        app.signal_tree_node();
+        assert!(app.process_signal.is_none());
+        assert!(app.show_container_dialog && !app.tree_focus);
+        assert_eq!(app.get_selected().map(|p| p.port), Some(8080));
+
+        // One without is refused
+        app.show_container_dialog = false;
+        app.all_ports.retain(|p| p.port != 8080);
+        app.apply_filters();
+        app.signal_tree_node();
+        assert!(app.process_signal.is_none() && !app.show_container_dialog);
+        assert!(app
+            .status_message
+            .as_deref()
+            .is_some_and(|m| m.contains("stop its container instead")));
+    }
+
+    #[test]
+    fn test_kill_refuses_unmapped_docker_proxy() {
+        let mut app = App::new();
+        let mut proxy = sample_port(8080);
+        proxy.process_name = "docker-proxy".to_string();
+        app.ports = vec![proxy];
+
+        app.kill_selected();
+        assert!(!app.show_container_dialog);
+        assert!(app
+            .status_message
+            .as_deref()
+            .is_some_and(|m| m.contains("stop its container instead")));
     }
 }
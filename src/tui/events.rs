@@ -2,79 +2,342 @@
 //!
 //! Keyboard and mouse event processing.
 
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use super::app::App;
+use super::tabs::Tab;
+use super::ui;
+use crossterm::event::{KeyCode, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
 
 /// Keyboard action that can be performed
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Action {
     Quit,
+    /// Clear marks, then filters, then quit
+    Back,
     MoveUp,
     MoveDown,
+    PageUp,
+    PageDown,
     MoveToFirst,
     MoveToLast,
+    NextTab,
+    PrevTab,
     Kill,
+    ToggleMark,
+    MarkAll,
     Refresh,
     CycleFilter,
     CycleSort,
     ToggleDetails,
+    ToggleTree,
     ToggleHelp,
     ToggleMenu,
     ToggleCritical,
     ToggleDocker,
+    ToggleTunnels,
+    ToggleMine,
     StartSearch,
+    Export,
     MenuSelect(usize),
     None,
 }
 
-/// Convert a key event to an action
-pub fn key_to_action(key: KeyEvent, in_menu: bool) -> Action {
-    if in_menu {
-        // Menu-specific keybindings
-        return match key.code {
-            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('m') => Action::ToggleMenu,
-            KeyCode::Char('j') | KeyCode::Down => Action::MoveDown,
-            KeyCode::Char('k') | KeyCode::Up => Action::MoveUp,
-            KeyCode::Enter => Action::MenuSelect(0), // Placeholder, actual index from app
-            KeyCode::Char('1') => Action::MenuSelect(0),
-            KeyCode::Char('2') => Action::MenuSelect(1),
-            KeyCode::Char('3') => Action::MenuSelect(2),
-            KeyCode::Char('4') => Action::MenuSelect(3),
-            KeyCode::Char('5') => Action::MenuSelect(4),
-            _ => Action::None,
-        };
+/// Config names of the bindable actions
+const ACTION_NAMES: &[(Action, &str)] = &[
+    (Action::Quit, "quit"),
+    (Action::Back, "back"),
+    (Action::MoveUp, "move_up"),
+    (Action::MoveDown, "move_down"),
+    (Action::PageUp, "page_up"),
+    (Action::PageDown, "page_down"),
+    (Action::MoveToFirst, "move_to_first"),
+    (Action::MoveToLast, "move_to_last"),
+    (Action::NextTab, "next_tab"),
+    (Action::PrevTab, "prev_tab"),
+    (Action::Kill, "kill"),
+    (Action::ToggleMark, "toggle_mark"),
+    (Action::MarkAll, "mark_all"),
+    (Action::Refresh, "refresh"),
+    (Action::CycleFilter, "cycle_filter"),
+    (Action::CycleSort, "cycle_sort"),
+    (Action::ToggleDetails, "toggle_details"),
+    (Action::ToggleTree, "toggle_tree"),
+    (Action::ToggleHelp, "toggle_help"),
+    (Action::ToggleMenu, "toggle_menu"),
+    (Action::ToggleCritical, "toggle_critical"),
+    (Action::ToggleDocker, "toggle_docker"),
+    (Action::ToggleTunnels, "toggle_tunnels"),
+    (Action::ToggleMine, "toggle_mine"),
+    (Action::StartSearch, "search"),
+    (Action::Export, "export"),
+];
+
+impl Action {
+    /// Name used in the `[keys]` config section
+    pub fn name(&self) -> &'static str {
+        ACTION_NAMES
+            .iter()
+            .find(|(a, _)| a == self)
+            .map_or("none", |(_, name)| name)
     }
 
-    match key.code {
-        // Quit
-        KeyCode::Char('q') => Action::Quit,
-        KeyCode::Esc => Action::Quit,
-        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+    /// Look up an action by its config name
+    pub fn from_name(name: &str) -> Option<Action> {
+        ACTION_NAMES
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(a, _)| *a)
+    }
 
-        // Navigation
-        KeyCode::Char('j') | KeyCode::Down => Action::MoveDown,
-        KeyCode::Char('k') | KeyCode::Up => Action::MoveUp,
-        KeyCode::Char('g') => Action::MoveToFirst,
-        KeyCode::Char('G') => Action::MoveToLast,
-        KeyCode::Home => Action::MoveToFirst,
-        KeyCode::End => Action::MoveToLast,
+    /// One-line description for the help popup
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit portr",
+            Action::Back => "Clear marks / filters, then exit",
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::PageUp => "Page up",
+            Action::PageDown => "Page down",
+            Action::MoveToFirst => "Go to first",
+            Action::MoveToLast => "Go to last",
+            Action::NextTab => "Next tab",
+            Action::PrevTab => "Previous tab",
+            Action::Kill => "Kill process / container actions",
+            Action::ToggleMark => "Mark port for batch kill/export",
+            Action::MarkAll => "Mark all matching / unmark all",
+            Action::Refresh => "Refresh port list",
+            Action::CycleFilter => "Cycle filter (All/TCP/UDP)",
+            Action::CycleSort => "Sort by the next column",
+            Action::ToggleDetails => "Toggle details panel",
+            Action::ToggleTree => "Process tree pane (open/close)",
+            Action::ToggleHelp => "Toggle this help",
+            Action::ToggleMenu => "Open quick menu",
+            Action::ToggleCritical => "Critical services only",
+            Action::ToggleDocker => "Docker only",
+            Action::ToggleTunnels => "SSH tunnels only (-L/-R/-D)",
+            Action::ToggleMine => "Mine only (hide other users' ports)",
+            Action::StartSearch => "Search/filter by text",
+            Action::Export => "Export ports (JSON/CSV/MD)",
+            Action::MenuSelect(_) => "Select menu item",
+            Action::None => "",
+        }
+    }
+}
 
-        // Actions
-        KeyCode::Char('K') => Action::Kill,
-        KeyCode::Char('r') | KeyCode::F(5) => Action::Refresh,
+/// Run an action on the app
+pub fn perform(app: &mut App, action: Action) {
+    // A focused tree pane takes navigation, Enter (collapse) and kill
+    if app.tree_focus && app.tab == Tab::Listening {
+        match action {
+            Action::MoveUp => return app.tree_move(-1),
+            Action::MoveDown => return app.tree_move(1),
+            Action::PageUp | Action::MoveToFirst => return app.tree_move(isize::MIN),
+            Action::PageDown | Action::MoveToLast => return app.tree_move(isize::MAX),
+            Action::ToggleDetails => return app.toggle_tree_node(),
+            Action::Kill => return app.signal_tree_node(),
+            Action::Back => {
+                app.tree_focus = false;
+                return;
+            }
+            _ => {}
+        }
+    }
+
+    // List tabs have their own selection and sort; port actions stay on
+    // the Listening tab
+    if app.tab != Tab::Listening {
+        match action {
+            Action::MoveUp => return app.tab_move(-1),
+            Action::MoveDown => return app.tab_move(1),
+            Action::PageUp => return app.tab_move(-10),
+            Action::PageDown => return app.tab_move(10),
+            Action::MoveToFirst => return app.tab_move(isize::MIN),
+            Action::MoveToLast => return app.tab_move(isize::MAX),
+            Action::CycleSort => return app.cycle_tab_sort(),
+            Action::Back => return app.tab_back(),
+            Action::Kill
+            | Action::ToggleMark
+            | Action::MarkAll
+            | Action::ToggleDetails
+            | Action::ToggleTree
+            | Action::Export => {
+                return app.set_status("Switch to the Listening tab to act on ports");
+            }
+            _ => {}
+        }
+    }
+
+    match action {
+        Action::Quit => app.running = false,
+        Action::Back => app.back(),
+        Action::MoveUp => app.previous(),
+        Action::MoveDown => app.next(),
+        Action::PageUp => app.page_up(),
+        Action::PageDown => app.page_down(),
+        Action::MoveToFirst => app.first(),
+        Action::MoveToLast => app.last(),
+        Action::NextTab => app.set_tab(app.tab.next()),
+        Action::PrevTab => app.set_tab(app.tab.previous()),
+        Action::Kill => app.kill_selected(),
+        Action::ToggleMark => app.toggle_mark(),
+        Action::MarkAll => app.toggle_mark_all(),
+        Action::Refresh => app.refresh(),
+        Action::CycleFilter => app.cycle_filter(),
+        Action::CycleSort => app.cycle_sort(),
+        Action::ToggleDetails => app.toggle_details(),
+        Action::ToggleTree => app.toggle_tree(),
+        Action::ToggleHelp => app.show_help = !app.show_help,
+        Action::ToggleMenu => app.toggle_menu(),
+        Action::ToggleCritical => app.toggle_critical_filter(),
+        Action::ToggleDocker => app.toggle_docker_filter(),
+        Action::ToggleTunnels => app.toggle_tunnel_filter(),
+        Action::ToggleMine => app.toggle_mine_filter(),
+        Action::StartSearch => {
+            app.input_mode = true;
+            app.filter_input.clear();
+        }
+        Action::Export => app.toggle_export(),
+        Action::MenuSelect(i) => {
+            app.menu_selected = i;
+            app.menu_select();
+        }
+        Action::None => {}
+    }
+}
 
-        // Filters and sorting
-        KeyCode::Char('f') => Action::CycleFilter,
-        KeyCode::Tab => Action::CycleSort,
-        KeyCode::Char('c') => Action::ToggleCritical,
-        KeyCode::Char('d') => Action::ToggleDocker,
-        KeyCode::Char('/') => Action::StartSearch,
-        KeyCode::Char('m') => Action::ToggleMenu,
+/// Handle a key in the kill confirmation dialog
+///
+/// Critical targets need "yes" typed and Enter. Otherwise only y/Y kills and
+/// any other key takes the N default, so a stray Enter can't kill a marked
+/// batch. Tab switches the signal in both modes.
+pub fn kill_confirm_key(app: &mut App, code: KeyCode) {
+    let Some(confirm) = app.kill_confirm.as_mut() else {
+        return;
+    };
+    match code {
+        KeyCode::Tab => confirm.force = !confirm.force,
+        KeyCode::Esc => app.cancel_kill(),
+        _ if !confirm.needs_yes() => match code {
+            KeyCode::Char('y' | 'Y') => app.confirm_kill(),
+            _ => app.cancel_kill(),
+        },
+        KeyCode::Enter => app.confirm_kill(),
+        KeyCode::Char(c) => confirm.input.push(c),
+        KeyCode::Backspace => {
+            confirm.input.pop();
+        }
+        _ => {}
+    }
+}
+
+/// Handle a key in the container action confirmation
+///
+/// Same answers as [`kill_confirm_key`]: typed "yes" for critical images,
+/// else only y/Y confirms and every other key cancels.
+pub fn container_confirm_key(app: &mut App, code: KeyCode) {
+    let Some(confirm) = app.container_confirm.as_mut() else {
+        return;
+    };
+    match code {
+        KeyCode::Esc => app.cancel_container_action(),
+        _ if !confirm.needs_yes() => match code {
+            KeyCode::Char('y' | 'Y') => app.confirm_container_action(),
+            _ => app.cancel_container_action(),
+        },
+        KeyCode::Enter => app.confirm_container_action(),
+        KeyCode::Char(c) => confirm.input.push(c),
+        KeyCode::Backspace => {
+            confirm.input.pop();
+        }
+        _ => {}
+    }
+}
+
+/// Handle a mouse event using the areas from the last draw
+///
+/// Clicks select rows, sort by column headers, pick menu items and run
+/// footer hints; the wheel moves the selection. Dialogs stay keyboard-only.
+pub fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    if app.input_mode
+        || app.kill_confirm.is_some()
+        || app.process_signal.is_some()
+        || app.show_container_dialog
+        || app.container_confirm.is_some()
+        || app.show_export
+    {
+        return;
+    }
+    let pos = Position::new(mouse.column, mouse.row);
+    let areas = app.hit_areas;
+
+    if app.show_menu {
+        match mouse.kind {
+            MouseEventKind::ScrollDown => app.menu_down(),
+            MouseEventKind::ScrollUp => app.menu_up(),
+            MouseEventKind::Down(MouseButton::Left) => {
+                match areas.menu.filter(|menu| menu.contains(pos)) {
+                    Some(menu) => {
+                        if let Some(i) = ui::menu_item_at(menu, pos.y) {
+                            perform(app, Action::MenuSelect(i));
+                        }
+                    }
+                    // Clicking outside closes the menu
+                    None => app.show_menu = false,
+                }
+            }
+            _ => {}
+        }
+        return;
+    }
+    if app.show_help {
+        if let MouseEventKind::Down(_) = mouse.kind {
+            app.show_help = false;
+        }
+        return;
+    }
 
-        // Display toggles
-        KeyCode::Enter => Action::ToggleDetails,
-        KeyCode::Char('?') => Action::ToggleHelp,
+    match mouse.kind {
+        MouseEventKind::ScrollDown => perform(app, Action::MoveDown),
+        MouseEventKind::ScrollUp => perform(app, Action::MoveUp),
+        MouseEventKind::Down(MouseButton::Left) if areas.tabs.contains(pos) => {
+            if let Some(tab) = ui::tab_at(app, areas.tabs, pos.x) {
+                app.set_tab(tab);
+            }
+        }
+        MouseEventKind::Down(MouseButton::Left) if areas.table.contains(pos) => {
+            click_table(app, areas.table, areas.table_offset, pos);
+        }
+        MouseEventKind::Down(MouseButton::Left) if areas.footer.contains(pos) => {
+            if let Some(action) = ui::footer_action_at(app, areas.footer, pos.x) {
+                perform(app, action);
+            }
+        }
+        _ => {}
+    }
+}
 
-        _ => Action::None,
+/// Header row sorts by the clicked column; data rows select
+fn click_table(app: &mut App, table: Rect, offset: usize, pos: Position) {
+    if pos.y == table.y + 1 {
+        if app.tab == Tab::Listening {
+            if let Some(column) = ui::column_at(app, table, pos.x) {
+                app.sort_by_column(column);
+            }
+        } else if let Some(column) = ui::tab_column_at(app.tab, table, pos.x) {
+            app.set_tab_sort_column(column);
+        }
+        return;
+    }
+    let first_row = table.y + ui::TABLE_HEADER_ROWS;
+    if pos.y < first_row || pos.y + 1 >= table.y + table.height {
+        return;
+    }
+    let index = offset + usize::from(pos.y - first_row);
+    if index < app.tab_len() {
+        match app.tab {
+            Tab::Listening => app.selected = index,
+            _ => app.view_mut().selected = index,
+        }
     }
 }
 
@@ -82,17 +345,293 @@ pub fn key_to_action(key: KeyEvent, in_menu: bool) -> Action {
 mod tests {
     use super::*;
 
+    use crate::tui::keymap::Keymap;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
     #[test]
     fn test_quit_actions() {
+        let keymap = Keymap::default();
         let key = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
-        assert_eq!(key_to_action(key, false), Action::Quit);
+        assert_eq!(keymap.action(&key), Action::Quit);
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(keymap.action(&ctrl_c), Action::Quit);
+    }
+
+    fn port(port: u16) -> crate::port::PortInfo {
+        crate::port::PortInfo::test_port(port, u32::from(port))
+    }
+
+    fn compact_kill_dialog() -> App {
+        let mut app = App::with_profile(crate::tui::app::Profile::Compact);
+        // Not in all_ports, so a confirmed kill stops at the ownership check
+        app.ports = vec![port(3000)];
+        app.kill_selected();
+        assert!(!app.kill_confirm.as_ref().expect("dialog open").needs_yes());
+        app
+    }
+
+    #[test]
+    fn test_yes_no_kill_dialog_defaults_to_cancel() {
+        for code in [KeyCode::Enter, KeyCode::Char('x'), KeyCode::Char('N')] {
+            let mut app = compact_kill_dialog();
+            kill_confirm_key(&mut app, code);
+            assert!(app.kill_confirm.is_none());
+            assert_eq!(app.status_message.as_deref(), Some("Cancelled"));
+        }
+
+        let mut app = compact_kill_dialog();
+        kill_confirm_key(&mut app, KeyCode::Tab);
+        assert!(app.kill_confirm.as_ref().is_some_and(|c| c.force));
+        kill_confirm_key(&mut app, KeyCode::Char('y'));
+        assert!(app.kill_confirm.is_none());
+        assert!(app
+            .status_message
+            .as_deref()
+            .is_some_and(|s| s.contains("no longer held")));
+    }
+
+    #[test]
+    fn test_batch_kill_dialog_cancels_on_enter() {
+        let mut app = App::new();
+        app.all_ports = vec![port(3000), port(3001)];
+        app.apply_filters();
+        app.toggle_mark_all();
+        app.kill_selected();
+        let confirm = app.kill_confirm.as_ref().expect("batch dialog open");
+        assert_eq!(confirm.targets.len(), 2);
+        assert!(!confirm.needs_yes());
+
+        kill_confirm_key(&mut app, KeyCode::Enter);
+        assert!(app.kill_confirm.is_none());
+        assert_eq!(app.status_message.as_deref(), Some("Cancelled"));
+        assert_eq!(app.marked.len(), 2);
+    }
+
+    #[test]
+    fn test_container_confirmation_cancels_on_enter() {
+        for code in [KeyCode::Enter, KeyCode::Char('x')] {
+            let mut app = App::new();
+            let mut proxy = port(8080);
+            proxy.process_name = "docker-proxy".to_string();
+            proxy.container = Some(crate::port::ContainerRef {
+                id: "abc123".to_string(),
+                name: "web".to_string(),
+                image: "nginx".to_string(),
+                compose: None,
+            });
+            app.ports = vec![proxy];
+            app.kill_selected();
+            app.container_dialog_key(Some('x'));
+            assert!(!app.container_confirm.as_ref().unwrap().needs_yes());
+
+            container_confirm_key(&mut app, code);
+            assert!(app.container_confirm.is_none());
+            assert_eq!(app.status_message.as_deref(), Some("Cancelled"));
+        }
+    }
+
+    fn click(column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn test_mouse_selects_rows_and_sorts_by_header() {
+        use crate::columns::Column;
+
+        let mut app = App::new();
+        app.all_ports = (3000..3005).map(port).collect();
+        app.apply_filters();
+        app.hit_areas.table = Rect::new(0, 3, 80, 20);
+
+        // Rows start below the border, header and margin
+        handle_mouse(&mut app, click(10, 3 + ui::TABLE_HEADER_ROWS + 2));
+        assert_eq!(app.selected, 2);
+        handle_mouse(&mut app, click(10, 3 + ui::TABLE_HEADER_ROWS + 10));
+        assert_eq!(app.selected, 2);
+
+        // PID is the fourth column: border, 2 + 7 + 6 wide columns, spacing
+        handle_mouse(&mut app, click(1 + 2 + 1 + 7 + 1 + 6 + 1 + 2, 4));
+        assert_eq!(app.sort.column, Column::Pid);
+
+        let scroll = MouseEvent {
+            kind: MouseEventKind::ScrollUp,
+            ..click(0, 0)
+        };
+        handle_mouse(&mut app, scroll);
+        assert_eq!(app.selected, 1);
+    }
+
+    #[test]
+    fn test_footer_hints_match_rendered_keys() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut app = App::new();
+        let mut terminal = Terminal::new(TestBackend::new(140, 30)).unwrap();
+        terminal.draw(|f| ui::draw(f, &mut app)).unwrap();
+
+        // Find the rendered " K " hint and click it
+        let footer = app.hit_areas.footer;
+        let buffer = terminal.backend().buffer();
+        let row = footer.y + 1;
+        let x = (footer.x..footer.x + footer.width)
+            .find(|&x| buffer[(x, row)].symbol() == "K")
+            .unwrap();
+        assert_eq!(ui::footer_action_at(&app, footer, x), Some(Action::Kill));
+
+        let q = (footer.x..footer.x + footer.width)
+            .rev()
+            .find(|&x| buffer[(x, row)].symbol() == "q")
+            .unwrap();
+        assert_eq!(
+            ui::footer_action_at(&app, footer, q - 2),
+            Some(Action::Quit)
+        );
+
+        // Menu items are clickable where they are drawn
+        app.toggle_menu();
+        terminal.draw(|f| ui::draw(f, &mut app)).unwrap();
+        let menu = app.hit_areas.menu.unwrap();
+        let buffer = terminal.backend().buffer();
+        let critical_row = (menu.y..menu.y + menu.height)
+            .find(|&y| {
+                (menu.x..menu.x + menu.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+                    .contains("Critical")
+            })
+            .unwrap();
+        handle_mouse(&mut app, click(menu.x + 10, critical_row));
+        assert!(app.critical_only);
+    }
+
+    #[test]
+    fn test_compact_profile_layout() {
+        use crate::tui::app::Profile;
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut app = App::with_profile(Profile::Compact);
+        app.all_ports = (3000..3005).map(port).collect();
+        app.apply_filters();
+        assert!(!app.show_details);
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+        terminal.draw(|f| ui::draw(f, &mut app)).unwrap();
+
+        // One-line header, tab bar and footer around a full-height table
+        let footer = app.hit_areas.footer;
+        assert_eq!((app.hit_areas.tabs.y, footer.height), (1, 1));
+        assert_eq!(app.hit_areas.table.y, 2);
+        let buffer = terminal.backend().buffer();
+        let x = (footer.x..footer.x + footer.width)
+            .find(|&x| buffer[(x, footer.y)].symbol() == "K")
+            .unwrap();
+        assert_eq!(ui::footer_action_at(&app, footer, x), Some(Action::Kill));
+
+        handle_mouse(&mut app, click(10, 2 + ui::TABLE_HEADER_ROWS + 3));
+        assert_eq!(app.selected, 3);
+
+        // Details open below the table, keeping its full width
+        perform(&mut app, Action::ToggleDetails);
+        terminal.draw(|f| ui::draw(f, &mut app)).unwrap();
+        assert_eq!(app.hit_areas.table.width, 100);
+        assert!(app.hit_areas.table.height < 21);
+    }
+
+    #[test]
+    fn test_tabs_keep_their_own_view() {
+        use crate::port::Connection;
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut app = App::new();
+        app.all_ports = (3000..3003).map(port).collect();
+        app.apply_filters();
+        app.connections = (0..3)
+            .map(|i| Connection {
+                local_address: format!("127.0.0.1:{}", 3000 + i),
+                local_port: 3000 + i,
+                remote_address: format!("10.0.0.{}:5000", i),
+                pid: Some(100),
+                process_name: "node".to_string(),
+                state: "ESTAB".to_string(),
+            })
+            .collect();
+        app.selected = 2;
+
+        // Navigation and the filter apply to the Connections tab only
+        perform(&mut app, Action::NextTab);
+        assert_eq!(app.tab, Tab::Connections);
+        app.filter_input = "10.0.0".to_string();
+        app.apply_filter();
+        perform(&mut app, Action::MoveDown);
+        assert_eq!(app.view().selected, 1);
+        assert_eq!(app.view().filter, "10.0.0");
+        assert_eq!(app.tab_len(), 3);
+
+        perform(&mut app, Action::PrevTab);
+        assert_eq!(app.tab, Tab::Listening);
+        assert_eq!(app.selected, 2);
+        assert_eq!(app.ports.len(), 3);
+
+        // Clicking a tab title switches to it and keeps its selection
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|f| ui::draw(f, &mut app)).unwrap();
+        let tabs = app.hit_areas.tabs;
+        let buffer = terminal.backend().buffer();
+        let x = (tabs.x..tabs.x + tabs.width)
+            .find(|&x| buffer[(x, tabs.y)].symbol() == "C")
+            .unwrap();
+        handle_mouse(&mut app, click(x, tabs.y));
+        assert_eq!(app.tab, Tab::Connections);
+        assert_eq!(app.view().selected, 1);
+
+        // Kill is refused outside the Listening tab
+        perform(&mut app, Action::Kill);
+        assert!(app.kill_confirm.is_none());
+    }
+
+    #[test]
+    fn test_details_show_sparklines() {
+        use ratatui::{backend::TestBackend, Terminal};
+        use std::collections::HashMap;
+
+        let mut app = App::new();
+        app.all_ports = vec![port(3000)];
+        app.apply_filters();
+        for conns in [1, 3, 2] {
+            app.metrics
+                .record(&app.all_ports, &HashMap::from([(3000, conns)]));
+        }
+
+        let mut terminal = Terminal::new(TestBackend::new(140, 40)).unwrap();
+        terminal.draw(|f| ui::draw(f, &mut app)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert!(screen.contains("Connections 2 (peak 3)"));
     }
 
     #[test]
     fn test_navigation() {
         let key_j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
         let key_k = KeyEvent::new(KeyCode::Char('k'), KeyModifiers::NONE);
-        assert_eq!(key_to_action(key_j, false), Action::MoveDown);
-        assert_eq!(key_to_action(key_k, false), Action::MoveUp);
+        let keymap = Keymap::default();
+        assert_eq!(keymap.action(&key_j), Action::MoveDown);
+        assert_eq!(keymap.action(&key_k), Action::MoveUp);
+    }
+
+    #[test]
+    fn test_action_names_round_trip() {
+        for (action, _) in crate::tui::keymap::DEFAULT_KEYS {
+            assert_eq!(Action::from_name(action.name()), Some(*action));
+        }
     }
 }
@@ -0,0 +1,277 @@
+//! Dashboard keybindings
+//!
+//! Every dashboard action has default keys, and the `[keys]` config section
+//! can rebind them:
+//!
+//! ```toml
+//! [keys]
+//! kill = "K delete"
+//! refresh = "r F5"
+//! quit = "q ctrl+c"
+//! ```
+//!
+//! Several keys are separated by spaces, and listing an action replaces its
+//! default keys. Binding one key to two actions is an error, reported when
+//! the dashboard starts.
+
+use super::events::Action;
+use crate::error::PortrError;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// A key plus Ctrl/Alt
+///
+/// Shift is part of the character (`K`, `G`, `?`), so it is not stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// Parse a chord such as `K`, `ctrl+c`, `space`, `pgdn` or `F5`
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = s;
+        while let Some((prefix, rest)) = key.split_once('+').filter(|(_, rest)| !rest.is_empty()) {
+            modifiers |= match prefix.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                _ => return Err(format!("unknown modifier '{}' in '{}'", prefix, s)),
+            };
+            key = rest;
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "tab" => KeyCode::Tab,
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pgup" | "pageup" => KeyCode::PageUp,
+                "pgdn" | "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key '{}'", s)),
+                },
+            },
+        };
+        Ok(Self { code, modifiers })
+    }
+
+    /// Whether a key press is this chord
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        key.code == self.code && modifiers == self.modifiers
+    }
+}
+
+impl std::fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "␣"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::Enter => write!(f, "⏎"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Backspace => write!(f, "⌫"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            _ => write!(f, "?"),
+        }
+    }
+}
+
+/// Default keys per action, in the order the help popup lists them
+pub const DEFAULT_KEYS: &[(Action, &str)] = &[
+    (Action::MoveDown, "j down"),
+    (Action::MoveUp, "k up"),
+    (Action::PageDown, "pgdn"),
+    (Action::PageUp, "pgup"),
+    (Action::MoveToFirst, "g home"),
+    (Action::MoveToLast, "G end"),
+    (Action::NextTab, "] l right"),
+    (Action::PrevTab, "[ h left"),
+    (Action::Kill, "K"),
+    (Action::ToggleMark, "space"),
+    (Action::MarkAll, "a"),
+    (Action::Refresh, "r F5"),
+    (Action::Export, "e"),
+    (Action::ToggleMenu, "m"),
+    (Action::StartSearch, "/"),
+    (Action::CycleFilter, "f"),
+    (Action::ToggleDocker, "d"),
+    (Action::ToggleCritical, "c"),
+    (Action::ToggleTunnels, "t"),
+    (Action::ToggleMine, "u"),
+    (Action::CycleSort, "tab"),
+    (Action::ToggleDetails, "enter i"),
+    (Action::ToggleTree, "p"),
+    (Action::ToggleHelp, "? F1"),
+    (Action::Back, "esc"),
+    (Action::Quit, "q ctrl+c"),
+];
+
+/// Active key → action bindings
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeyChord>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&[]).expect("default keys are valid")
+    }
+}
+
+impl Keymap {
+    /// Build the keymap from `[keys]` entries (action name, keys)
+    ///
+    /// An entry replaces all default keys of its action. Unknown actions,
+    /// unparsable keys and keys bound to two actions are errors.
+    pub fn from_config(overrides: &[(String, String)]) -> Result<Self, PortrError> {
+        let invalid = |msg: String| PortrError::ConfigError(format!("[keys] {}", msg));
+
+        let mut keys: Vec<(Action, &str)> = DEFAULT_KEYS.to_vec();
+        for (name, value) in overrides {
+            let action = Action::from_name(name)
+                .ok_or_else(|| invalid(format!("unknown action '{}'", name)))?;
+            if let Some(entry) = keys.iter_mut().find(|(a, _)| *a == action) {
+                entry.1 = value.as_str();
+            }
+        }
+
+        let mut bindings: Vec<(Action, Vec<KeyChord>)> = Vec::new();
+        for (action, value) in keys {
+            let chords = value
+                .split_whitespace()
+                .map(KeyChord::parse)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| invalid(format!("{}: {}", action.name(), e)))?;
+
+            for chord in &chords {
+                if let Some((other, _)) = bindings.iter().find(|(_, c)| c.contains(chord)) {
+                    return Err(invalid(format!(
+                        "'{}' is bound to both {} and {}",
+                        chord,
+                        other.name(),
+                        action.name()
+                    )));
+                }
+            }
+            bindings.push((action, chords));
+        }
+
+        Ok(Self { bindings })
+    }
+
+    /// Action bound to a key press
+    pub fn action(&self, key: &KeyEvent) -> Action {
+        self.bindings
+            .iter()
+            .find(|(_, chords)| chords.iter().any(|c| c.matches(key)))
+            .map_or(Action::None, |(action, _)| *action)
+    }
+
+    /// Keys bound to an action
+    pub fn keys(&self, action: Action) -> &[KeyChord] {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map_or(&[], |(_, chords)| chords.as_slice())
+    }
+
+    /// Keys of an action for display, e.g. "j/↓"
+    pub fn label(&self, action: Action) -> String {
+        self.keys(action)
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_chords() {
+        let chord = KeyChord::parse("ctrl+c").unwrap();
+        assert!(chord.matches(&key(KeyCode::Char('c'), KeyModifiers::CONTROL)));
+        assert!(!chord.matches(&key(KeyCode::Char('c'), KeyModifiers::NONE)));
+        assert_eq!(chord.to_string(), "Ctrl+c");
+
+        // Shift comes with the character itself
+        let upper = KeyChord::parse("K").unwrap();
+        assert!(upper.matches(&key(KeyCode::Char('K'), KeyModifiers::SHIFT)));
+
+        assert_eq!(KeyChord::parse("PgDn").unwrap().code, KeyCode::PageDown);
+        assert_eq!(KeyChord::parse("F5").unwrap().code, KeyCode::F(5));
+        assert_eq!(KeyChord::parse("+").unwrap().code, KeyCode::Char('+'));
+        assert!(KeyChord::parse("hyper+x").is_err());
+        assert!(KeyChord::parse("f13").is_err());
+    }
+
+    #[test]
+    fn test_overrides_replace_defaults() {
+        let keymap = Keymap::from_config(&[
+            ("kill".to_string(), "x".to_string()),
+            ("refresh".to_string(), "ctrl+r F5".to_string()),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            keymap.action(&key(KeyCode::Char('x'), KeyModifiers::NONE)),
+            Action::Kill
+        );
+        assert_eq!(
+            keymap.action(&key(KeyCode::Char('K'), KeyModifiers::SHIFT)),
+            Action::None
+        );
+        assert_eq!(keymap.label(Action::Refresh), "Ctrl+r/F5");
+    }
+
+    #[test]
+    fn test_conflicts_and_unknown_actions_are_errors() {
+        let err = Keymap::from_config(&[("kill".to_string(), "d".to_string())]).unwrap_err();
+        assert!(err.to_string().contains("'d' is bound to both"));
+
+        // Moving the other action out of the way resolves it
+        assert!(Keymap::from_config(&[
+            ("kill".to_string(), "d".to_string()),
+            ("toggle_docker".to_string(), "D".to_string()),
+        ])
+        .is_ok());
+
+        assert!(Keymap::from_config(&[("explode".to_string(), "x".to_string())]).is_err());
+        assert!(Keymap::from_config(&[("kill".to_string(), "ctrl+".to_string())]).is_err());
+    }
+}
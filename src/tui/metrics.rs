@@ -0,0 +1,89 @@
+//! Per-port resource history for the dashboard sparklines
+//!
+//! Every timed refresh appends one sample per listening port. Samples are
+//! keyed by (port, pid), so a restarted server starts a fresh line instead of
+//! continuing the old process's history, and each buffer holds a bounded
+//! window of the most recent samples.
+
+use crate::port::PortInfo;
+use std::collections::{HashMap, VecDeque};
+
+/// Samples kept per port: five minutes of timed 2s refreshes
+///
+/// Manual refreshes and rescans after a kill don't add samples.
+pub const HISTORY_LEN: usize = 150;
+
+/// One refresh worth of measurements for a port
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricSample {
+    pub memory_mb: f64,
+    pub cpu_percent: f32,
+    /// Established TCP connections to the port
+    pub connections: usize,
+}
+
+/// Ring buffers of recent samples, by (port, pid)
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    samples: HashMap<(u16, u32), VecDeque<MetricSample>>,
+}
+
+impl Metrics {
+    /// Append a sample for every port
+    ///
+    /// Ports that are gone lose their history, so the map never outgrows
+    /// the current listeners.
+    pub fn record(&mut self, ports: &[PortInfo], connections: &HashMap<u16, usize>) {
+        self.samples
+            .retain(|(port, pid), _| ports.iter().any(|p| p.port == *port && p.pid == *pid));
+
+        for p in ports {
+            let buffer = self.samples.entry((p.port, p.pid)).or_default();
+            if buffer.len() == HISTORY_LEN {
+                buffer.pop_front();
+            }
+            buffer.push_back(MetricSample {
+                memory_mb: p.memory_mb,
+                cpu_percent: p.cpu_percent,
+                connections: connections.get(&p.port).copied().unwrap_or(0),
+            });
+        }
+    }
+
+    /// Samples for a port, oldest first
+    pub fn history(&self, port_info: &PortInfo) -> Option<&VecDeque<MetricSample>> {
+        self.samples.get(&(port_info.port, port_info.pid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(port: u16, pid: u32, memory_mb: f64) -> PortInfo {
+        PortInfo {
+            local_address: format!("0.0.0.0:{}", port),
+            memory_mb,
+            ..PortInfo::test_port(port, pid)
+        }
+    }
+
+    #[test]
+    fn test_history_is_bounded_and_follows_the_process() {
+        let mut metrics = Metrics::default();
+        let connections = HashMap::from([(3000, 4)]);
+        for i in 0..HISTORY_LEN + 10 {
+            metrics.record(&[port(3000, 10, i as f64)], &connections);
+        }
+
+        let history = metrics.history(&port(3000, 10, 0.0)).unwrap();
+        assert_eq!(history.len(), HISTORY_LEN);
+        assert_eq!(history.front().unwrap().memory_mb, 10.0);
+        assert_eq!(history.back().unwrap().connections, 4);
+
+        // A new process on the port starts over; the old history is dropped
+        metrics.record(&[port(3000, 11, 1.0)], &HashMap::new());
+        assert!(metrics.history(&port(3000, 10, 0.0)).is_none());
+        assert_eq!(metrics.history(&port(3000, 11, 0.0)).unwrap().len(), 1);
+    }
+}
@@ -2,12 +2,18 @@
 //!
 //! Beautiful real-time port monitoring with keyboard navigation.
 //! The "Wow Factor" - designed for viral screenshots!
+//!
+//! `portr dashboard` and `portr -i` both run this module, with the
+//! [`Profile::Dashboard`] and [`Profile::Compact`] layouts.
 
 pub mod app;
 pub mod events;
+pub mod keymap;
+pub mod metrics;
+pub mod tabs;
 pub mod ui;
 
-use crate::PortrError;
+use crate::{PortrError, TableOptions};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
     execute,
@@ -17,10 +23,19 @@ use ratatui::prelude::*;
 use std::io;
 use std::time::{Duration, Instant};
 
-pub use app::App;
+pub use app::{App, Profile};
+use events::Action;
 
 /// Run the TUI dashboard
-pub fn run_dashboard() -> Result<(), PortrError> {
+pub fn run_dashboard(table: &TableOptions) -> Result<(), PortrError> {
+    run(Profile::Dashboard, table)
+}
+
+/// Run the TUI with the given layout profile, columns and sort order
+pub fn run(profile: Profile, table: &TableOptions) -> Result<(), PortrError> {
+    // Refuse a broken [keys] section before taking over the terminal
+    keymap::Keymap::from_config(&crate::config::load_config().keys)?;
+
     // Setup terminal
     enable_raw_mode().map_err(|e| PortrError::SystemError(e.to_string()))?;
     let mut stdout = io::stdout();
@@ -31,7 +46,8 @@ pub fn run_dashboard() -> Result<(), PortrError> {
         Terminal::new(backend).map_err(|e| PortrError::SystemError(e.to_string()))?;
 
     // Create app and run
-    let mut app = App::new();
+    let mut app = App::with_profile(profile);
+    app.set_table_options(table);
     app.refresh_ports();
     let result = run_app(&mut terminal, &mut app);
 
@@ -69,10 +85,15 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<(),
             .unwrap_or_else(|| Duration::from_secs(0));
 
         if crossterm::event::poll(timeout).map_err(|e| PortrError::SystemError(e.to_string()))? {
-            if let Event::Key(key) =
-                event::read().map_err(|e| PortrError::SystemError(e.to_string()))?
-            {
-                if key.kind == KeyEventKind::Press {
+            match event::read().map_err(|e| PortrError::SystemError(e.to_string()))? {
+                Event::Mouse(mouse) => events::handle_mouse(app, mouse),
+                // Redraw right away at the new size instead of on the next tick
+                Event::Resize(_, _) => {
+                    terminal
+                        .autoresize()
+                        .map_err(|e| PortrError::SystemError(e.to_string()))?;
+                }
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
                     // Handle input mode first
                     if app.input_mode {
                         match key.code {
@@ -92,6 +113,29 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<(),
                             }
                             _ => {}
                         }
+                    } else if app.kill_confirm.is_some() {
+                        events::kill_confirm_key(app, key.code);
+                    } else if let Some(ref mut signal) = app.process_signal {
+                        // Tree node signal dialog, same keys as the kill dialog
+                        match key.code {
+                            KeyCode::Enter => app.confirm_signal(),
+                            KeyCode::Esc => app.cancel_signal(),
+                            KeyCode::Tab => signal.force = !signal.force,
+                            KeyCode::Char(c) => signal.input.push(c),
+                            KeyCode::Backspace => {
+                                signal.input.pop();
+                            }
+                            _ => {}
+                        }
+                    } else if app.container_confirm.is_some() {
+                        events::container_confirm_key(app, key.code);
+                    } else if app.show_container_dialog {
+                        // Container action dialog: s/r/p/x pick, anything else cancels
+                        let pressed = match key.code {
+                            KeyCode::Char(c) => Some(c),
+                            _ => None,
+                        };
+                        app.container_dialog_key(pressed);
                     } else if app.show_export {
                         // Export popup mode
                         match key.code {
@@ -116,63 +160,20 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<(),
                             _ => {}
                         }
                     } else if app.show_menu {
-                        // Menu mode navigation - q always exits app from menu
-                        match key.code {
-                            KeyCode::Char('q') => {
-                                return Ok(()); // Quit app entirely
-                            }
-                            KeyCode::Esc | KeyCode::Char('m') => {
+                        // Menu: item keys jump; quit, menu and movement keys
+                        // follow the keymap, so q always exits the app
+                        match (app.keymap.action(&key), key.code) {
+                            (Action::Quit, _) => return Ok(()),
+                            (Action::ToggleMenu, _) | (_, KeyCode::Esc) => {
                                 app.show_menu = false; // Just close menu
                             }
-                            KeyCode::Char('j') | KeyCode::Down => {
-                                app.menu_down();
-                            }
-                            KeyCode::Char('k') | KeyCode::Up => {
-                                app.menu_up();
-                            }
-                            KeyCode::Enter => {
-                                app.menu_select();
-                                if !app.running {
-                                    return Ok(());
-                                }
-                            }
-                            KeyCode::Char('1') => {
-                                app.menu_selected = 0;
-                                app.menu_select();
-                            }
-                            KeyCode::Char('2') => {
-                                app.menu_selected = 1;
-                                app.menu_select();
-                            }
-                            KeyCode::Char('3') => {
-                                app.menu_selected = 2;
-                                app.menu_select();
-                            }
-                            KeyCode::Char('4') => {
-                                app.menu_selected = 3;
-                                app.menu_select();
-                            }
-                            KeyCode::Char('5') => {
-                                app.menu_selected = 4;
-                                app.menu_select();
-                            }
-                            KeyCode::Char('6') => {
-                                app.menu_selected = 5;
-                                app.menu_select();
-                            }
-                            KeyCode::Char('7') => {
-                                app.menu_selected = 6;
-                                app.menu_select();
-                            }
-                            KeyCode::Char('8') => {
-                                app.menu_selected = 7;
-                                app.menu_select();
-                            }
-                            KeyCode::Char('0') => {
-                                app.menu_selected = 8;
-                                app.menu_select();
-                                if !app.running {
-                                    return Ok(());
+                            (Action::MoveDown, _) => app.menu_down(),
+                            (Action::MoveUp, _) => app.menu_up(),
+                            (_, KeyCode::Enter) => app.menu_select(),
+                            (_, KeyCode::Char(c)) => {
+                                if let Some(i) = app::menu_item_for_key(c) {
+                                    app.menu_selected = i;
+                                    app.menu_select();
                                 }
                             }
                             _ => {}
@@ -181,84 +182,11 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<(),
                         // Any key closes help
                         app.show_help = false;
                     } else {
-                        match key.code {
-                            KeyCode::Char('q') => {
-                                return Ok(());
-                            }
-                            KeyCode::Esc => {
-                                // Esc clears filters first, then quits
-                                if !app.filter_text.is_empty()
-                                    || app.docker_only
-                                    || app.critical_only
-                                {
-                                    app.clear_filter();
-                                    app.docker_only = false;
-                                    app.critical_only = false;
-                                    app.apply_filters();
-                                    app.set_status(&format!(
-                                        "Filters cleared ({} ports)",
-                                        app.ports.len()
-                                    ));
-                                } else {
-                                    return Ok(());
-                                }
-                            }
-                            KeyCode::Char('m') => {
-                                app.toggle_menu();
-                            }
-                            KeyCode::Char('j') | KeyCode::Down => {
-                                app.next();
-                            }
-                            KeyCode::Char('k') | KeyCode::Up => {
-                                app.previous();
-                            }
-                            KeyCode::PageDown => {
-                                app.page_down();
-                            }
-                            KeyCode::PageUp => {
-                                app.page_up();
-                            }
-                            KeyCode::Char('g') => {
-                                app.first();
-                            }
-                            KeyCode::Char('G') => {
-                                app.last();
-                            }
-                            KeyCode::Char('/') => {
-                                app.input_mode = true;
-                                app.filter_input.clear();
-                            }
-                            KeyCode::Char('f') => {
-                                app.cycle_filter();
-                            }
-                            KeyCode::Char('K') => {
-                                app.kill_selected();
-                            }
-                            KeyCode::Char('r') | KeyCode::F(5) => {
-                                app.refresh_ports();
-                            }
-                            KeyCode::Char('d') => {
-                                app.toggle_docker_filter();
-                            }
-                            KeyCode::Char('c') => {
-                                app.toggle_critical_filter();
-                            }
-                            KeyCode::Char('e') => {
-                                app.toggle_export();
-                            }
-                            KeyCode::Char('?') => {
-                                app.show_help = !app.show_help;
-                            }
-                            KeyCode::Enter => {
-                                app.toggle_details();
-                            }
-                            KeyCode::Tab => {
-                                app.cycle_sort();
-                            }
-                            _ => {}
-                        }
+                        let action = app.keymap.action(&key);
+                        events::perform(app, action);
                     }
                 }
+                _ => {}
             }
         }
 
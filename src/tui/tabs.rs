@@ -0,0 +1,246 @@
+//! Dashboard tabs
+//!
+//! The Listening tab is the port table driven by the main `App` fields. The
+//! other tabs are read-only lists, each with its own [`TabView`]: a text
+//! filter, a sort column and a selection that survive switching tabs.
+
+use crate::history::HistoryEvent;
+use crate::port::Connection;
+use std::cmp::Ordering;
+
+/// Dashboard tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tab {
+    /// Listening sockets (the port table)
+    Listening,
+    /// Established TCP connections
+    Connections,
+    /// Docker containers and their port mappings
+    Containers,
+    /// Binds and unbinds seen while the dashboard runs
+    History,
+}
+
+impl Tab {
+    /// All tabs, in display order
+    pub const ALL: [Tab; 4] = [
+        Tab::Listening,
+        Tab::Connections,
+        Tab::Containers,
+        Tab::History,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Tab::Listening => "Listening",
+            Tab::Connections => "Connections",
+            Tab::Containers => "Containers",
+            Tab::History => "History",
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        Tab::ALL.iter().position(|t| t == self).unwrap_or(0)
+    }
+
+    pub fn next(&self) -> Tab {
+        Tab::ALL[(self.index() + 1) % Tab::ALL.len()]
+    }
+
+    pub fn previous(&self) -> Tab {
+        Tab::ALL[(self.index() + Tab::ALL.len() - 1) % Tab::ALL.len()]
+    }
+
+    /// Column headers of the tab's table (not used for Listening)
+    pub fn columns(&self) -> &'static [&'static str] {
+        match self {
+            Tab::Listening => &[],
+            Tab::Connections => &["LOCAL", "REMOTE", "PID", "PROCESS", "STATE"],
+            Tab::Containers => &["NAME", "IMAGE", "PORTS", "COMPOSE", "STATUS"],
+            Tab::History => &["TIME", "EVENT", "PORT", "PROTO", "PID", "PROCESS"],
+        }
+    }
+
+    /// Columns the tab can be sorted by, in the order Tab cycles them
+    pub fn sort_columns(&self) -> &'static [usize] {
+        match self {
+            Tab::Listening => &[],
+            Tab::Connections => &[0, 1, 2, 3],
+            Tab::Containers => &[0, 1, 3],
+            Tab::History => &[0, 2, 1],
+        }
+    }
+}
+
+/// Filter, sort and selection of one tab
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TabView {
+    pub selected: usize,
+    /// Case-insensitive text every row is matched against
+    pub filter: String,
+    /// Index into [`Tab::sort_columns`]
+    pub sort: usize,
+}
+
+/// Container row of the Containers tab
+///
+/// Built from Docker's container list, so it also exists in builds without
+/// the `docker` feature (where the tab stays empty).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerRow {
+    pub name: String,
+    pub image: String,
+    /// Published ports, `docker ps` style
+    pub ports: Vec<String>,
+    /// "project/service" for compose containers
+    pub compose: Option<String>,
+    pub status: String,
+    pub critical: bool,
+}
+
+/// A row of one of the list tabs
+pub trait TabRow {
+    /// Text of each column, as displayed
+    fn cells(&self) -> Vec<String>;
+
+    /// Compare two rows by a column; numeric columns compare as numbers
+    fn compare(&self, other: &Self, column: usize) -> Ordering {
+        self.cells()[column].cmp(&other.cells()[column])
+    }
+}
+
+impl TabRow for Connection {
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.local_address.clone(),
+            self.remote_address.clone(),
+            self.pid.map_or_else(|| "-".to_string(), |p| p.to_string()),
+            self.process_name.clone(),
+            self.state.clone(),
+        ]
+    }
+
+    fn compare(&self, other: &Self, column: usize) -> Ordering {
+        match column {
+            0 => (self.local_port, &self.remote_address)
+                .cmp(&(other.local_port, &other.remote_address)),
+            2 => self.pid.cmp(&other.pid),
+            _ => self.cells()[column].cmp(&other.cells()[column]),
+        }
+    }
+}
+
+impl TabRow for ContainerRow {
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            self.image.clone(),
+            self.ports.join(", "),
+            self.compose.clone().unwrap_or_else(|| "-".to_string()),
+            self.status.clone(),
+        ]
+    }
+}
+
+impl TabRow for HistoryEvent {
+    fn cells(&self) -> Vec<String> {
+        // The clock part is enough for a session log
+        let time = self.time_display();
+        let process = match (&self.previous_process, &self.previous_state) {
+            (Some(previous), _) => format!("{} (was {})", self.process, previous),
+            (None, Some(previous)) => format!("{} ({} → {})", self.process, previous, self.state),
+            (None, None) => self.process.clone(),
+        };
+        vec![
+            time.get(11..).unwrap_or(&time).to_string(),
+            self.event.label().to_string(),
+            self.port.to_string(),
+            self.protocol.clone(),
+            self.pid.to_string(),
+            process,
+        ]
+    }
+
+    fn compare(&self, other: &Self, column: usize) -> Ordering {
+        match column {
+            // Newest first
+            0 => other.timestamp.cmp(&self.timestamp),
+            2 => self.port.cmp(&other.port),
+            _ => self.cells()[column].cmp(&other.cells()[column]),
+        }
+    }
+}
+
+/// Rows matching the view's filter, in its sort order
+pub fn visible_rows<'a, T: TabRow + 'a>(
+    rows: impl IntoIterator<Item = &'a T>,
+    tab: Tab,
+    view: &TabView,
+) -> Vec<&'a T> {
+    let filter = view.filter.to_lowercase();
+    let mut visible: Vec<&T> = rows
+        .into_iter()
+        .filter(|row| {
+            filter.is_empty()
+                || row
+                    .cells()
+                    .iter()
+                    .any(|cell| cell.to_lowercase().contains(&filter))
+        })
+        .collect();
+    if let Some(&column) = tab.sort_columns().get(view.sort) {
+        // Stable, so equal rows keep their scan order
+        visible.sort_by(|a, b| a.compare(b, column));
+    }
+    visible
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connection(local_port: u16, remote: &str, process: &str) -> Connection {
+        Connection {
+            local_address: format!("127.0.0.1:{}", local_port),
+            local_port,
+            remote_address: remote.to_string(),
+            pid: Some(u32::from(local_port)),
+            process_name: process.to_string(),
+            state: "ESTAB".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_tab_views_filter_and_sort() {
+        let rows = vec![
+            connection(8080, "10.0.0.2:5000", "nginx"),
+            connection(443, "10.0.0.9:6000", "caddy"),
+            connection(5432, "127.0.0.1:41000", "postgres"),
+        ];
+
+        // Default sort is the local port, numerically
+        let mut view = TabView::default();
+        let ports: Vec<u16> = visible_rows(&rows, Tab::Connections, &view)
+            .iter()
+            .map(|c| c.local_port)
+            .collect();
+        assert_eq!(ports, vec![443, 5432, 8080]);
+
+        // Process column, then a filter that matches the remote address
+        view.sort = 3;
+        let names: Vec<&str> = visible_rows(&rows, Tab::Connections, &view)
+            .iter()
+            .map(|c| c.process_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["caddy", "nginx", "postgres"]);
+
+        view.filter = "10.0.0".to_string();
+        assert_eq!(visible_rows(&rows, Tab::Connections, &view).len(), 2);
+    }
+
+    #[test]
+    fn test_tab_cycle() {
+        assert_eq!(Tab::History.next(), Tab::Listening);
+        assert_eq!(Tab::Listening.previous(), Tab::History);
+    }
+}
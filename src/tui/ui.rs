@@ -2,18 +2,26 @@
 //!
 //! Beautiful, viral-screenshot-worthy interface!
 
-use super::app::{App, MENU_ITEMS};
+use super::app::{App, HitAreas, KillConfirmation, Profile, DASHBOARD_COLUMNS, MENU_ITEMS};
+use super::events::Action;
+use super::metrics::MetricSample;
+use super::tabs::{self, Tab, TabRow};
+use crate::columns::Column;
+use crate::history::HistoryKind;
+use crate::port::PortInfo;
 use crate::services;
+use crossterm::event::KeyCode;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style, Stylize},
     text::{Line, Span},
     widgets::{
-        Block, Borders, Cell, Clear, HighlightSpacing, Paragraph, Row, Scrollbar,
-        ScrollbarOrientation, ScrollbarState, Table, TableState, Wrap,
+        Block, Borders, Cell, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph, Row,
+        Scrollbar, ScrollbarOrientation, ScrollbarState, Sparkline, Table, TableState, Tabs, Wrap,
     },
     Frame,
 };
+use std::collections::VecDeque;
 
 // Modern color palette (inspired by Tokyo Night)
 const ACCENT: Color = Color::Rgb(122, 162, 247); // Soft blue
@@ -24,35 +32,257 @@ const DANGER: Color = Color::Rgb(247, 118, 142); // Red/pink
 const MUTED: Color = Color::Rgb(86, 95, 137); // Muted gray-blue
 const BG_DARK: Color = Color::Rgb(26, 27, 38); // Dark background
 const TEXT_DIM: Color = Color::Rgb(169, 177, 214); // Dimmed text
+const TUNNEL: Color = Color::Rgb(125, 207, 255); // Cyan
 
 /// Main draw function
-pub fn draw(f: &mut Frame, app: &App) {
-    // Main layout: header, body, footer
+///
+/// Records where the tabs, table, footer and menu ended up for mouse
+/// handling.
+pub fn draw(f: &mut Frame, app: &mut App) {
+    // Main layout: header, body, footer; the compact profile drops the
+    // banner and borders to leave the rows for ports
+    let (header_height, body_min, footer_height) = match app.profile {
+        Profile::Dashboard => (3, 10, 3),
+        Profile::Compact => (1, 5, 1),
+    };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), // Header/banner
-            Constraint::Min(10),   // Main content
-            Constraint::Length(3), // Footer/help
+            Constraint::Length(header_height), // Header/banner
+            Constraint::Length(1),             // Tabs
+            Constraint::Min(body_min),         // Main content
+            Constraint::Length(footer_height), // Footer/help
         ])
         .split(f.area());
 
     draw_header(f, app, chunks[0]);
-    draw_body(f, app, chunks[1]);
-    draw_footer(f, app, chunks[2]);
+    draw_tabs(f, app, chunks[1]);
+    let (table, table_offset) = match app.tab {
+        Tab::Listening => draw_body(f, app, chunks[2]),
+        _ => (chunks[2], draw_list_tab(f, app, chunks[2])),
+    };
+    draw_footer(f, app, chunks[3]);
 
     // Overlays (only one at a time)
-    if app.show_export {
+    let mut menu = None;
+    if app.kill_confirm.is_some() {
+        draw_kill_confirm(f, app);
+    } else if app.process_signal.is_some() {
+        draw_process_signal(f, app);
+    } else if app.container_confirm.is_some() {
+        draw_container_confirm(f, app);
+    } else if app.show_container_dialog {
+        draw_container_dialog(f, app);
+    } else if app.show_export {
         draw_export_popup(f, app);
     } else if app.show_menu {
-        draw_menu_popup(f, app);
+        menu = Some(draw_menu_popup(f, app));
     } else if app.show_help {
-        draw_help_popup(f);
+        draw_help_popup(f, app);
+    }
+
+    app.hit_areas = HitAreas {
+        table,
+        table_offset,
+        footer: chunks[3],
+        menu,
+        tabs: chunks[1],
+    };
+}
+
+/// Tab titles with their row counts
+fn tab_titles(app: &App) -> Vec<String> {
+    Tab::ALL
+        .iter()
+        .map(|tab| {
+            let count = match tab {
+                Tab::Listening => app.ports.len(),
+                Tab::Connections => app.connections.len(),
+                Tab::Containers => app.containers.len(),
+                Tab::History => app.events.len(),
+            };
+            format!("{} {}", tab.title(), count)
+        })
+        .collect()
+}
+
+/// Draw the tab bar
+fn draw_tabs(f: &mut Frame, app: &App, area: Rect) {
+    let tabs = Tabs::new(tab_titles(app))
+        .select(app.tab.index())
+        .style(Style::default().fg(TEXT_DIM))
+        .highlight_style(Style::default().fg(Color::Black).bg(ACCENT).bold())
+        .divider(Span::styled("│", Style::default().fg(MUTED)));
+    f.render_widget(tabs, area);
+}
+
+/// Tab whose title is at column `x` of the tab bar
+pub fn tab_at(app: &App, tabs: Rect, x: u16) -> Option<Tab> {
+    // Mirrors the Tabs widget: padding, title, padding, then a divider
+    let mut left = tabs.x;
+    for (tab, title) in Tab::ALL.iter().zip(tab_titles(app)) {
+        let width = Span::raw(title).width() as u16 + 2;
+        if x >= left && x < left + width {
+            return Some(*tab);
+        }
+        left += width + 1;
+    }
+    None
+}
+
+/// Column widths of a list tab's table
+fn tab_widths(tab: Tab) -> Vec<Constraint> {
+    match tab {
+        Tab::Listening => table_widths(DASHBOARD_COLUMNS),
+        Tab::Connections => vec![
+            Constraint::Length(24), // local
+            Constraint::Length(24), // remote
+            Constraint::Length(8),  // pid
+            Constraint::Length(20), // process
+            Constraint::Min(8),     // state
+        ],
+        Tab::Containers => vec![
+            Constraint::Length(22), // name
+            Constraint::Length(24), // image
+            Constraint::Length(30), // ports
+            Constraint::Length(20), // compose
+            Constraint::Min(10),    // status
+        ],
+        Tab::History => vec![
+            Constraint::Length(9),  // time
+            Constraint::Length(14), // event
+            Constraint::Length(6),  // port
+            Constraint::Length(6),  // proto
+            Constraint::Length(8),  // pid
+            Constraint::Min(20),    // process
+        ],
+    }
+}
+
+/// Column of a list tab's table at `x`
+pub fn tab_column_at(tab: Tab, table: Rect, x: u16) -> Option<usize> {
+    let inner = table.inner(ratatui::layout::Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    let columns = Layout::horizontal(tab_widths(tab)).spacing(1).split(inner);
+    columns.iter().position(|c| x >= c.x && x < c.x + c.width)
+}
+
+/// Draw the Connections, Containers or History tab, returning the first
+/// visible row
+fn draw_list_tab(f: &mut Frame, app: &App, area: Rect) -> usize {
+    let view = app.view();
+    let (rows, empty): (Vec<(Vec<String>, Style)>, &str) = match app.tab {
+        Tab::Listening => (Vec::new(), ""),
+        Tab::Connections => (
+            tabs::visible_rows(&app.connections, app.tab, view)
+                .into_iter()
+                .map(|c| (c.cells(), Style::default().fg(TEXT_DIM)))
+                .collect(),
+            "No established connections",
+        ),
+        Tab::Containers => (
+            tabs::visible_rows(&app.containers, app.tab, view)
+                .into_iter()
+                .map(|c| {
+                    let style = if c.critical {
+                        Style::default().fg(WARNING)
+                    } else {
+                        Style::default().fg(Color::LightBlue)
+                    };
+                    (c.cells(), style)
+                })
+                .collect(),
+            if cfg!(feature = "docker") {
+                "No running containers (or Docker is unreachable)"
+            } else {
+                "Built without Docker support (--features docker)"
+            },
+        ),
+        Tab::History => (
+            tabs::visible_rows(&app.events, app.tab, view)
+                .into_iter()
+                .map(|e| {
+                    let color = match e.event {
+                        HistoryKind::Bind => SUCCESS,
+                        HistoryKind::Unbind => DANGER,
+                        HistoryKind::OwnerChange => WARNING,
+                        HistoryKind::StateChange => ACCENT2,
+                    };
+                    (e.cells(), Style::default().fg(color))
+                })
+                .collect(),
+            "No binds or unbinds since the dashboard started",
+        ),
+    };
+
+    let sorted = app.tab.sort_columns().get(view.sort).copied();
+    let header = Row::new(app.tab.columns().iter().enumerate().map(|(i, h)| {
+        let style = Style::default().fg(ACCENT).bold();
+        if Some(i) == sorted {
+            Cell::from(*h).style(style.add_modifier(Modifier::UNDERLINED))
+        } else {
+            Cell::from(*h).style(style)
+        }
+    }))
+    .height(1)
+    .bottom_margin(1);
+
+    let count = rows.len();
+    let mut title = format!(" {} ({}) ", app.tab.title(), count);
+    if !view.filter.is_empty() {
+        title.push_str(&format!("🔍 {} ", view.filter));
+    }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(MUTED))
+        .title(Span::styled(title, Style::default().fg(ACCENT).bold()));
+
+    if rows.is_empty() {
+        let message = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("  {}", empty),
+                Style::default().fg(MUTED),
+            )),
+        ])
+        .block(block);
+        f.render_widget(message, area);
+        return 0;
     }
+
+    let table = Table::new(
+        rows.into_iter()
+            .map(|(cells, style)| Row::new(cells).style(style)),
+        tab_widths(app.tab),
+    )
+    .header(header)
+    .block(block)
+    .row_highlight_style(
+        Style::default()
+            .bg(Color::Rgb(45, 50, 80))
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut state = TableState::default().with_selected(Some(view.selected.min(count - 1)));
+    f.render_stateful_widget(table, area, &mut state);
+    state.offset()
 }
 
 /// Draw the header with animated banner and status
 fn draw_header(f: &mut Frame, app: &App, area: Rect) {
+    if app.profile == Profile::Compact {
+        let logo = vec![
+            Span::styled(" 🐸 ", Style::default()),
+            Span::styled("portr", Style::default().fg(ACCENT).bold()),
+            Span::styled("  │  ", Style::default().fg(MUTED)),
+        ];
+        let content: Vec<Span> = [logo, header_stats(app)].concat();
+        f.render_widget(Paragraph::new(Line::from(content)), area);
+        return;
+    }
+
     // Animated pulse effect based on tick
     let pulse = (app.tick % 20) < 10;
     let accent_color = if pulse { ACCENT } else { ACCENT2 };
@@ -77,7 +307,21 @@ fn draw_header(f: &mut Frame, app: &App, area: Rect) {
         Span::raw("  "),
     ];
 
-    // Stats section
+    let content: Vec<Span> = [logo, header_stats(app)].concat();
+
+    let header = Paragraph::new(Line::from(content))
+        .block(
+            Block::default()
+                .borders(Borders::BOTTOM)
+                .border_style(Style::default().fg(MUTED)),
+        )
+        .style(Style::default());
+
+    f.render_widget(header, area);
+}
+
+/// Header stats: port count, filter, sort, active filter badges and status
+fn header_stats(app: &App) -> Vec<Span<'_>> {
     let mut stats = vec![
         Span::styled(
             format!("⬢ {} ", app.ports.len()),
@@ -91,7 +335,7 @@ fn draw_header(f: &mut Frame, app: &App, area: Rect) {
         ),
         Span::styled("  │  ", Style::default().fg(MUTED)),
         Span::styled(
-            format!("⇅ {}", app.sort_mode_str()),
+            format!("⇅ {}", app.sort_label()),
             Style::default().fg(ACCENT2),
         ),
     ];
@@ -114,6 +358,27 @@ fn draw_header(f: &mut Frame, app: &App, area: Rect) {
             Style::default().fg(Color::Black).bg(DANGER).bold(),
         ));
     }
+    if app.tunnels_only {
+        stats.push(Span::styled("  ", Style::default()));
+        stats.push(Span::styled(
+            " ⇄ Tunnels ",
+            Style::default().fg(Color::Black).bg(TUNNEL).bold(),
+        ));
+    }
+    if app.mine_only {
+        stats.push(Span::styled("  ", Style::default()));
+        stats.push(Span::styled(
+            " 👤 Mine ",
+            Style::default().fg(Color::Black).bg(SUCCESS).bold(),
+        ));
+    }
+    if !app.marked.is_empty() {
+        stats.push(Span::styled("  ", Style::default()));
+        stats.push(Span::styled(
+            format!(" ● {} marked ", app.marked.len()),
+            Style::default().fg(Color::Black).bg(ACCENT2).bold(),
+        ));
+    }
     if !app.filter_text.is_empty() {
         stats.push(Span::styled("  ", Style::default()));
         stats.push(Span::styled(
@@ -122,63 +387,203 @@ fn draw_header(f: &mut Frame, app: &App, area: Rect) {
         ));
     }
 
-    // Add status message if present (with fade effect based on time)
-    let status = if let Some(ref msg) = app.status_message {
-        vec![
-            Span::styled("  │  ", Style::default().fg(MUTED)),
-            Span::styled(msg.as_str(), Style::default().fg(Color::White).italic()),
-        ]
-    } else {
-        vec![]
-    };
-
-    // Combine all sections
-    let content: Vec<Span> = [logo, stats, status].concat();
-
-    let header = Paragraph::new(Line::from(content))
-        .block(
-            Block::default()
-                .borders(Borders::BOTTOM)
-                .border_style(Style::default().fg(MUTED)),
-        )
-        .style(Style::default());
+    // Add status message if present
+    if let Some(ref msg) = app.status_message {
+        stats.push(Span::styled("  │  ", Style::default().fg(MUTED)));
+        stats.push(Span::styled(
+            msg.as_str(),
+            Style::default().fg(Color::White).italic(),
+        ));
+    }
 
-    f.render_widget(header, area);
+    stats
 }
 
 /// Draw the main body with table and details
-fn draw_body(f: &mut Frame, app: &App, area: Rect) {
-    if app.show_details && !app.ports.is_empty() {
-        // Split: table (left) + details (right)
+///
+/// Returns the table's area and first visible row.
+fn draw_body(f: &mut Frame, app: &App, area: Rect) -> (Rect, usize) {
+    if (app.show_details || app.show_tree) && !app.ports.is_empty() {
+        // Split: table (left) + side panels (right), or panels below the
+        // table in the compact profile
+        let (direction, side_direction) = match app.profile {
+            Profile::Dashboard => (Direction::Horizontal, Direction::Vertical),
+            Profile::Compact => (Direction::Vertical, Direction::Horizontal),
+        };
+        let table_share = if app.profile == Profile::Dashboard {
+            65
+        } else {
+            55
+        };
         let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+            .direction(direction)
+            .constraints([
+                Constraint::Percentage(table_share),
+                Constraint::Percentage(100 - table_share),
+            ])
             .split(area);
 
-        draw_table(f, app, chunks[0]);
-        draw_details(f, app, chunks[1]);
+        let offset = draw_table(f, app, chunks[0]);
+        match (app.show_details, app.show_tree) {
+            (true, true) => {
+                let side = Layout::default()
+                    .direction(side_direction)
+                    .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+                    .split(chunks[1]);
+                draw_details(f, app, side[0]);
+                draw_tree(f, app, side[1]);
+            }
+            (true, false) => draw_details(f, app, chunks[1]),
+            _ => draw_tree(f, app, chunks[1]),
+        }
+        (chunks[0], offset)
     } else {
         // Full width table
-        draw_table(f, app, area);
+        (area, draw_table(f, app, area))
     }
 }
 
-/// Draw the port table
-fn draw_table(f: &mut Frame, app: &App, area: Rect) {
-    let header_cells = [
-        "", "PORT", "PROTO", "PID", "PROCESS", "MEMORY", "UPTIME", "STATE",
-    ]
-    .iter()
-    .map(|h| Cell::from(*h).style(Style::default().fg(ACCENT).bold()));
-    let header = Row::new(header_cells).height(1).bottom_margin(1);
+/// Draw the process tree pane for the selected port
+///
+/// Ancestors lead down to the port's process, its children follow. Nodes
+/// holding sockets list their ports; the focused pane highlights a node.
+fn draw_tree(f: &mut Frame, app: &App, area: Rect) {
+    let rows = app.visible_tree();
+    let items: Vec<ListItem> = rows
+        .iter()
+        .map(|row| {
+            let marker = if !app.tree_has_children(row.pid) {
+                "  "
+            } else if app.tree_collapsed.contains(&row.pid) {
+                "▸ "
+            } else {
+                "▾ "
+            };
+            let connector = if row.depth == 0 { "" } else { "└─" };
+            let name_style = if row.is_target {
+                Style::default().fg(SUCCESS).bold()
+            } else {
+                Style::default().fg(Color::White)
+            };
+
+            let mut spans = vec![
+                Span::raw(" ".repeat(row.depth.saturating_sub(1) * 2)),
+                Span::styled(connector, Style::default().fg(MUTED)),
+                Span::styled(marker, Style::default().fg(ACCENT2)),
+                Span::styled(row.name.as_str(), name_style),
+                Span::styled(format!(" {}", row.pid), Style::default().fg(MUTED)),
+                Span::styled(
+                    format!("  {} {:.1}%", format_mb(row.memory_mb), row.cpu_percent),
+                    Style::default().fg(TEXT_DIM),
+                ),
+            ];
+            if !row.ports.is_empty() {
+                let ports: Vec<String> = row.ports.iter().map(|p| format!(":{}", p)).collect();
+                spans.push(Span::styled(
+                    format!("  ⚡{}", ports.join(",")),
+                    Style::default().fg(WARNING),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let (border, hint) = if app.tree_focus {
+        (ACCENT, " ⏎ fold · K signal · Esc back ")
+    } else {
+        (MUTED, "")
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border))
+                .title(Span::styled(
+                    " ⑂ Process Tree ",
+                    Style::default().fg(ACCENT).bold(),
+                ))
+                .title_bottom(Span::styled(hint, Style::default().fg(MUTED))),
+        )
+        .highlight_style(Style::default().bg(Color::Rgb(45, 50, 80)).bold());
+
+    let mut state = ListState::default().with_selected(app.tree_focus.then_some(app.tree_selected));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// Port table column widths, after the selector; the last column takes
+/// the remaining space
+fn table_widths(columns: &[Column]) -> Vec<Constraint> {
+    let mut widths = vec![Constraint::Length(2)]; // selector
+    for (i, column) in columns.iter().enumerate() {
+        let width = match column {
+            Column::Port => 7,
+            Column::Proto => 6,
+            Column::Pid => 8,
+            Column::Proc | Column::Container => 20,
+            Column::User | Column::Service => 12,
+            Column::Addr => 16,
+            Column::State | Column::Cpu => 8,
+            Column::Mem | Column::Uptime => 10,
+            Column::Cmd => 30,
+        };
+        widths.push(if i + 1 == columns.len() {
+            Constraint::Min(width)
+        } else {
+            Constraint::Length(width)
+        });
+    }
+    widths
+}
+
+/// Rows taken by the table's top border, header and header margin
+pub const TABLE_HEADER_ROWS: u16 = 3;
+
+/// Port table column at `x`, for sorting by a header click
+pub fn column_at(app: &App, table: Rect, x: u16) -> Option<Column> {
+    // Same split the Table widget does inside its border
+    let inner = table.inner(ratatui::layout::Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    let columns = app.table_columns();
+    let areas = Layout::horizontal(table_widths(columns))
+        .spacing(1)
+        .split(inner);
+    let index = areas.iter().position(|c| x >= c.x && x < c.x + c.width)?;
+    // Index 0 is the selector
+    index.checked_sub(1).map(|i| columns[i])
+}
+
+/// Draw the port table, returning the first visible row
+fn draw_table(f: &mut Frame, app: &App, area: Rect) -> usize {
+    let columns = app.table_columns();
+    let header_cells = columns.iter().map(|column| {
+        // Underline the column the table is sorted by, with its direction
+        let style = Style::default().fg(ACCENT).bold();
+        if *column == app.sort.column {
+            let arrow = if app.sort.descending { "↓" } else { "↑" };
+            Cell::from(format!("{}{}", column.header(), arrow))
+                .style(style.add_modifier(Modifier::UNDERLINED))
+        } else {
+            Cell::from(column.header()).style(style)
+        }
+    });
+    let header = Row::new(std::iter::once(Cell::from("")).chain(header_cells))
+        .height(1)
+        .bottom_margin(1);
 
     let rows = app.ports.iter().enumerate().map(|(i, port)| {
         let is_selected = i == app.selected;
-        let is_critical = services::requires_confirmation(port.port);
-        let is_docker = port.process_name.to_lowercase().contains("docker");
-
-        // Selection indicator with animation
-        let selector = if is_selected { "▶" } else { " " };
+        let is_critical = port.requires_confirmation();
+        let is_docker = port.is_docker();
+
+        // Selection indicator, plus a dot on ports marked for batch actions
+        let selector = match (is_selected, app.is_marked(port)) {
+            (true, true) => "▶●",
+            (true, false) => "▶",
+            (false, true) => " ●",
+            (false, false) => " ",
+        };
         let selector_style = if is_selected {
             Style::default().fg(SUCCESS).bold()
         } else {
@@ -195,17 +600,13 @@ fn draw_table(f: &mut Frame, app: &App, area: Rect) {
             Style::default().fg(SUCCESS)
         };
 
-        // Format uptime
-        let uptime = format_uptime(port.uptime_secs);
-
-        // State with color
-        let state = &port.state;
-
         // Row style based on type
         let row_style = if is_critical {
             Style::default().fg(WARNING)
         } else if is_docker {
             Style::default().fg(Color::LightBlue)
+        } else if port.is_tunnel() {
+            Style::default().fg(TUNNEL)
         } else {
             Style::default().fg(TEXT_DIM)
         };
@@ -217,35 +618,32 @@ fn draw_table(f: &mut Frame, app: &App, area: Rect) {
             Style::default().fg(ACCENT).bold()
         };
 
-        let cells = vec![
-            Cell::from(selector).style(selector_style),
-            Cell::from(port.port.to_string()).style(port_style),
-            Cell::from(port.protocol.as_str()).style(Style::default().fg(ACCENT2)),
-            Cell::from(port.pid.to_string()).style(Style::default().fg(MUTED)),
-            Cell::from(truncate(&port.process_name, 20)).style(Style::default().fg(Color::White)),
-            Cell::from(memory).style(mem_style),
-            Cell::from(uptime).style(Style::default().fg(TEXT_DIM)),
-            Cell::from(state.as_str()).style(state_style(state)),
-        ];
+        let mut cells = vec![Cell::from(selector).style(selector_style)];
+        cells.extend(columns.iter().map(|column| {
+            match column {
+                Column::Port => Cell::from(port.port.to_string()).style(port_style),
+                Column::Proto => {
+                    Cell::from(port.protocol.as_str()).style(Style::default().fg(ACCENT2))
+                }
+                Column::Pid => Cell::from(port.pid.to_string()).style(Style::default().fg(MUTED)),
+                Column::Proc => Cell::from(truncate(&port.display_name(), 20))
+                    .style(Style::default().fg(Color::White)),
+                Column::Mem => Cell::from(memory.clone()).style(mem_style),
+                Column::Uptime => {
+                    Cell::from(format_uptime(port.uptime_secs)).style(Style::default().fg(TEXT_DIM))
+                }
+                Column::State => Cell::from(port.state.as_str()).style(state_style(&port.state)),
+                _ => Cell::from(column.display(port)).style(Style::default().fg(TEXT_DIM)),
+            }
+        }));
 
         Row::new(cells).style(row_style)
     });
 
-    let widths = [
-        Constraint::Length(2),  // selector
-        Constraint::Length(7),  // port
-        Constraint::Length(6),  // proto
-        Constraint::Length(8),  // pid
-        Constraint::Length(20), // process
-        Constraint::Length(10), // memory
-        Constraint::Length(10), // uptime
-        Constraint::Min(8),     // state
-    ];
-
     // Dynamic title with count
     let title = format!(" ⬡ Listening Ports ({}) ", app.ports.len());
 
-    let table = Table::new(rows, widths)
+    let table = Table::new(rows, table_widths(columns))
         .header(header)
         .block(
             Block::default()
@@ -262,6 +660,7 @@ fn draw_table(f: &mut Frame, app: &App, area: Rect) {
 
     let mut state = TableState::default().with_selected(Some(app.selected));
     f.render_stateful_widget(table, area, &mut state);
+    let offset = state.offset();
 
     // Scrollbar with modern style
     if app.ports.len() > (area.height as usize - 4) {
@@ -280,6 +679,8 @@ fn draw_table(f: &mut Frame, app: &App, area: Rect) {
             &mut scrollbar_state,
         );
     }
+
+    offset
 }
 
 /// Draw the details panel
@@ -311,13 +712,10 @@ fn draw_details(f: &mut Frame, app: &App, area: Rect) {
         }
     };
 
-    let is_critical = services::requires_confirmation(port.port);
-    let is_docker = port.process_name.to_lowercase().contains("docker");
+    let is_critical = port.requires_confirmation();
+    let is_docker = port.is_docker();
     let service = services::lookup(port.port);
 
-    // Memory usage for mini-sparkline visual
-    let mem_bar = create_mem_bar(port.memory_mb);
-
     let mut lines = vec![
         Line::from(""),
         Line::from(vec![
@@ -349,6 +747,10 @@ fn draw_details(f: &mut Frame, app: &App, area: Rect) {
             Span::styled("  ◈ Protocol: ", Style::default().fg(MUTED)),
             Span::styled(&port.protocol, Style::default().fg(ACCENT2)),
         ]),
+        Line::from(vec![
+            Span::styled("  ☺ User: ", Style::default().fg(MUTED)),
+            Span::styled(owner_label(port), Style::default().fg(TEXT_DIM)),
+        ]),
         Line::from(vec![
             Span::styled("  ⊕ Local: ", Style::default().fg(MUTED)),
             Span::styled(&port.local_address, Style::default().fg(TEXT_DIM)),
@@ -361,26 +763,12 @@ fn draw_details(f: &mut Frame, app: &App, area: Rect) {
         Line::from(""),
     ];
 
-    // Memory with visual bar
+    // Memory
     lines.push(Line::from(vec![
         Span::styled("  ▤ Memory: ", Style::default().fg(MUTED)),
         Span::styled(
             format_mb(port.memory_mb),
-            Style::default().fg(if port.memory_mb > 100.0 {
-                WARNING
-            } else {
-                SUCCESS
-            }),
-        ),
-        Span::styled(
-            format!(" {}", mem_bar),
-            Style::default().fg(if port.memory_mb > 500.0 {
-                DANGER
-            } else if port.memory_mb > 100.0 {
-                WARNING
-            } else {
-                SUCCESS
-            }),
+            Style::default().fg(mem_color(port.memory_mb)),
         ),
     ]));
 
@@ -419,6 +807,72 @@ fn draw_details(f: &mut Frame, app: &App, area: Rect) {
         }
     }
 
+    // kubectl port-forward target
+    if let Some(ref forward) = port.kube_forward {
+        lines.push(Line::from(vec![
+            Span::styled("  ⎈ Forward:   ", Style::default().fg(MUTED)),
+            Span::styled(forward.to_string(), Style::default().fg(ACCENT).bold()),
+        ]));
+        if let Some(ref context) = forward.context {
+            lines.push(Line::from(vec![
+                Span::styled("    context ", Style::default().fg(MUTED)),
+                Span::styled(context.as_str(), Style::default().fg(TEXT_DIM).italic()),
+            ]));
+        }
+        lines.push(Line::from(Span::styled(
+            "    ● Low risk - kill closes the local forward only",
+            Style::default().fg(SUCCESS),
+        )));
+    }
+
+    // SSH tunnel behind this listener
+    if let Some(ref tunnel) = port.tunnel {
+        lines.push(Line::from(vec![
+            Span::styled("  ⇄ Tunnel:    ", Style::default().fg(MUTED)),
+            Span::styled(
+                format!("ssh {}", tunnel.kind.flag()),
+                Style::default().fg(TUNNEL).bold(),
+            ),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled(tunnel.to_string(), Style::default().fg(TEXT_DIM).italic()),
+        ]));
+        if tunnel.kill_note().is_some() {
+            lines.push(Line::from(Span::styled(
+                "    ● Low risk - kill closes the tunnel only",
+                Style::default().fg(SUCCESS),
+            )));
+        }
+    }
+
+    // Docker container publishing this port
+    if let Some(ref container) = port.container {
+        lines.push(Line::from(vec![
+            Span::styled("  🐳 Container: ", Style::default().fg(MUTED)),
+            Span::styled(
+                container.name.as_str(),
+                Style::default().fg(Color::LightBlue).bold(),
+            ),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled(
+                format!("{} ({})", container.image, container.id),
+                Style::default().fg(TEXT_DIM).italic(),
+            ),
+        ]));
+        if let Some(ref compose) = container.compose {
+            lines.push(Line::from(vec![
+                Span::styled("  📦 Compose:   ", Style::default().fg(MUTED)),
+                Span::styled(
+                    format!("{} / {}", compose.project, compose.service),
+                    Style::default().fg(Color::LightBlue),
+                ),
+            ]));
+        }
+    }
+
     // Critical warning box
     if is_critical {
         lines.push(Line::from(""));
@@ -466,29 +920,206 @@ fn draw_details(f: &mut Frame, app: &App, area: Rect) {
     };
     let title = format!(" {} Details ", title_icon);
 
-    let details = Paragraph::new(lines)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(border_style)
-                .title(Span::styled(title, Style::default().fg(ACCENT).bold())),
-        )
-        .wrap(Wrap { trim: true });
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(border_style)
+        .title(Span::styled(title, Style::default().fg(ACCENT).bold()));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    // Sparklines along the bottom once there is history and room for it
+    let history = app.metrics.history(port).filter(|h| h.len() > 1);
+    let text_area = match history {
+        Some(history) if inner.height >= SPARKLINE_ROWS + 8 => {
+            let chunks = Layout::vertical([Constraint::Min(0), Constraint::Length(SPARKLINE_ROWS)])
+                .split(inner);
+            draw_sparklines(f, history, chunks[1]);
+            chunks[0]
+        }
+        _ => inner,
+    };
+
+    let details = Paragraph::new(lines).wrap(Wrap { trim: true });
+    f.render_widget(details, text_area);
+}
+
+/// Rows taken by the three sparklines in the details panel
+const SPARKLINE_ROWS: u16 = 9;
+
+/// Memory, CPU and connection history of the selected port
+///
+/// Each series gets a label with its latest and peak value above a two-row
+/// sparkline of the most recent samples that fit the width.
+fn draw_sparklines(f: &mut Frame, history: &VecDeque<MetricSample>, area: Rect) {
+    let latest = history.back().copied().unwrap_or(MetricSample {
+        memory_mb: 0.0,
+        cpu_percent: 0.0,
+        connections: 0,
+    });
+    let peak_mb = history.iter().map(|s| s.memory_mb).fold(0.0, f64::max);
+    let peak_cpu = history.iter().map(|s| s.cpu_percent).fold(0.0, f32::max);
+    let peak_conns = history.iter().map(|s| s.connections).max().unwrap_or(0);
+
+    // Sparklines take u64: memory in KB, CPU in tenths of a percent
+    let series: [(String, Vec<u64>, Color); 3] = [
+        (
+            format!(
+                "▤ Memory {} (peak {})",
+                format_mb(latest.memory_mb),
+                format_mb(peak_mb)
+            ),
+            history
+                .iter()
+                .map(|s| (s.memory_mb * 1024.0) as u64)
+                .collect(),
+            mem_color(latest.memory_mb),
+        ),
+        (
+            format!("◐ CPU {:.1}% (peak {:.1}%)", latest.cpu_percent, peak_cpu),
+            history
+                .iter()
+                .map(|s| (s.cpu_percent * 10.0) as u64)
+                .collect(),
+            ACCENT,
+        ),
+        (
+            format!("⇄ Connections {} (peak {})", latest.connections, peak_conns),
+            history.iter().map(|s| s.connections as u64).collect(),
+            ACCENT2,
+        ),
+    ];
+
+    let rows = Layout::vertical([Constraint::Length(3); 3]).split(area);
+    for ((label, data, color), row) in series.into_iter().zip(rows.iter()) {
+        let [label_area, line_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Length(2)]).areas(*row);
+        f.render_widget(
+            Paragraph::new(Span::styled(
+                format!(" {}", label),
+                Style::default().fg(TEXT_DIM),
+            )),
+            label_area,
+        );
+        // Newest samples on the right
+        let width = usize::from(line_area.width.saturating_sub(1));
+        let start = data.len().saturating_sub(width);
+        f.render_widget(
+            Sparkline::default()
+                .data(&data[start..])
+                .style(Style::default().fg(color)),
+            line_area.inner(ratatui::layout::Margin {
+                vertical: 0,
+                horizontal: 1,
+            }),
+        );
+    }
+}
 
-    f.render_widget(details, area);
+/// Color for a memory amount: green, then warning past 100 MB, danger past 500 MB
+fn mem_color(memory_mb: f64) -> Color {
+    if memory_mb > 500.0 {
+        DANGER
+    } else if memory_mb > 100.0 {
+        WARNING
+    } else {
+        SUCCESS
+    }
+}
+
+/// Footer key hints: key, label, key style and the action a click runs
+///
+/// Only the first key of each action fits; the help popup lists them all.
+fn footer_hints(app: &App) -> Vec<(String, &'static str, Style, Action)> {
+    let keys = |action| {
+        app.keymap
+            .keys(action)
+            .first()
+            .map_or_else(|| "-".to_string(), |c| c.to_string())
+    };
+    // Arrows when both are bound, else the first key of each, e.g. "k/j"
+    let up = app.keymap.keys(Action::MoveUp);
+    let down = app.keymap.keys(Action::MoveDown);
+    let arrows =
+        up.iter().any(|c| c.code == KeyCode::Up) && down.iter().any(|c| c.code == KeyCode::Down);
+    let nav = match (up.first(), down.first()) {
+        _ if arrows => "↑↓".to_string(),
+        (Some(up), Some(down)) => format!("{}/{}", up, down),
+        _ => "-".to_string(),
+    };
+    vec![
+        (
+            keys(Action::ToggleMenu),
+            "menu",
+            Style::default().fg(Color::Black).bg(ACCENT).bold(),
+            Action::ToggleMenu,
+        ),
+        (nav, "nav", Style::default().fg(WARNING), Action::None),
+        (
+            keys(Action::Kill),
+            "kill",
+            Style::default().fg(Color::Black).bg(DANGER).bold(),
+            Action::Kill,
+        ),
+        (
+            keys(Action::ToggleMark),
+            "mark",
+            Style::default().fg(ACCENT2),
+            Action::ToggleMark,
+        ),
+        (
+            keys(Action::StartSearch),
+            "search",
+            Style::default().fg(ACCENT),
+            Action::StartSearch,
+        ),
+        (
+            keys(Action::Export),
+            "export",
+            Style::default().fg(Color::Black).bg(SUCCESS).bold(),
+            Action::Export,
+        ),
+        (
+            keys(Action::Refresh),
+            "refresh",
+            Style::default().fg(SUCCESS),
+            Action::Refresh,
+        ),
+        (
+            keys(Action::ToggleHelp),
+            "help",
+            Style::default().fg(ACCENT2),
+            Action::ToggleHelp,
+        ),
+        (
+            keys(Action::Quit),
+            "quit",
+            Style::default().fg(DANGER),
+            Action::Quit,
+        ),
+    ]
 }
 
-/// Create a visual memory bar
-fn create_mem_bar(memory_mb: f64) -> String {
-    let filled = ((memory_mb / 100.0).min(10.0) as usize).min(10);
-    let empty = 10 - filled;
-    format!("[{}{}]", "█".repeat(filled), "░".repeat(empty))
+/// Action of the footer hint at column `x`
+pub fn footer_action_at(app: &App, footer: Rect, x: u16) -> Option<Action> {
+    // Mirrors the spans built in draw_footer
+    let mut left = footer.x + 1;
+    for (i, (key, label, _, action)) in footer_hints(app).into_iter().enumerate() {
+        if i > 0 {
+            left += 1;
+        }
+        let width = Span::raw(format!(" {}  {} ", key, label)).width() as u16;
+        if x >= left && x < left + width {
+            return Some(action);
+        }
+        left += width;
+    }
+    None
 }
 
 /// Draw the footer with help
 fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
     let help = if app.input_mode {
-        Line::from(vec![
+        let mut spans = vec![
             Span::styled(" 🔍 ", Style::default().fg(ACCENT)),
             Span::styled(&app.filter_input, Style::default().fg(Color::White).bold()),
             Span::styled(
@@ -501,49 +1132,66 @@ fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
             Span::styled("⏎ apply", Style::default().fg(SUCCESS)),
             Span::styled("  ", Style::default()),
             Span::styled("⎋ cancel", Style::default().fg(DANGER)),
-        ])
+        ];
+        if app.tab == Tab::Listening && app.filter_input.is_empty() {
+            spans.push(Span::styled(
+                "  e.g. port:3000-3999 proc:node mem>200 !docker",
+                Style::default().fg(MUTED),
+            ));
+        }
+        Line::from(spans)
     } else {
-        Line::from(vec![
-            Span::styled(" ", Style::default()),
-            Span::styled("m", Style::default().fg(Color::Black).bg(ACCENT).bold()),
-            Span::styled(" menu ", Style::default().fg(TEXT_DIM)),
-            Span::styled("│", Style::default().fg(MUTED)),
-            Span::styled(" ↑↓ ", Style::default().fg(WARNING)),
-            Span::styled("nav ", Style::default().fg(TEXT_DIM)),
-            Span::styled("│", Style::default().fg(MUTED)),
-            Span::styled(" K ", Style::default().fg(Color::Black).bg(DANGER).bold()),
-            Span::styled(" kill ", Style::default().fg(TEXT_DIM)),
-            Span::styled("│", Style::default().fg(MUTED)),
-            Span::styled(" / ", Style::default().fg(ACCENT)),
-            Span::styled("search ", Style::default().fg(TEXT_DIM)),
-            Span::styled("│", Style::default().fg(MUTED)),
-            Span::styled(" e ", Style::default().fg(Color::Black).bg(SUCCESS).bold()),
-            Span::styled(" export ", Style::default().fg(TEXT_DIM)),
-            Span::styled("│", Style::default().fg(MUTED)),
-            Span::styled(" r ", Style::default().fg(SUCCESS)),
-            Span::styled("refresh ", Style::default().fg(TEXT_DIM)),
-            Span::styled("│", Style::default().fg(MUTED)),
-            Span::styled(" ? ", Style::default().fg(ACCENT2)),
-            Span::styled("help ", Style::default().fg(TEXT_DIM)),
-            Span::styled("│", Style::default().fg(MUTED)),
-            Span::styled(" q ", Style::default().fg(DANGER)),
-            Span::styled("quit", Style::default().fg(TEXT_DIM)),
-        ])
+        let mut spans = vec![Span::raw(" ")];
+        for (i, (key, label, style, _)) in footer_hints(app).into_iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::styled("│", Style::default().fg(MUTED)));
+            }
+            spans.push(Span::styled(format!(" {} ", key), style));
+            spans.push(Span::styled(
+                format!(" {} ", label),
+                Style::default().fg(TEXT_DIM),
+            ));
+        }
+        Line::from(spans)
     };
 
-    let footer = Paragraph::new(help)
-        .block(
+    let footer = match app.profile {
+        Profile::Dashboard => Paragraph::new(help).block(
             Block::default()
                 .borders(Borders::TOP)
                 .border_style(Style::default().fg(MUTED)),
-        )
-        .style(Style::default());
+        ),
+        Profile::Compact => Paragraph::new(help),
+    };
 
     f.render_widget(footer, area);
 }
 
-/// Draw menu popup overlay
-fn draw_menu_popup(f: &mut Frame, app: &App) {
+/// Lines above the first menu item (title box and section header)
+const MENU_HEADER_LINES: u16 = 6;
+
+/// Whether a section divider is drawn above menu item `i`
+fn menu_divider_before(i: usize) -> bool {
+    matches!(i, 4 | 6 | 8)
+}
+
+/// Menu item on screen row `y`
+pub fn menu_item_at(menu: Rect, y: u16) -> Option<usize> {
+    let mut row = menu.y + 1 + MENU_HEADER_LINES;
+    for i in 0..MENU_ITEMS.len() {
+        if menu_divider_before(i) {
+            row += 1;
+        }
+        if y == row {
+            return Some(i);
+        }
+        row += 1;
+    }
+    None
+}
+
+/// Draw menu popup overlay, returning its area
+fn draw_menu_popup(f: &mut Frame, app: &App) -> Rect {
     let area = centered_rect(55, 65, f.area());
 
     // Clear background
@@ -595,21 +1243,14 @@ fn draw_menu_popup(f: &mut Frame, app: &App) {
         let is_selected = i == app.menu_selected;
 
         // Add section dividers
-        if i == 4 {
-            menu_lines.push(Line::from(Span::styled(
-                "     ├─ Filters ──────────────────────────┤",
-                Style::default().fg(MUTED),
-            )));
-        }
-        if i == 6 {
-            menu_lines.push(Line::from(Span::styled(
-                "     ├─ Actions ──────────────────────────┤",
-                Style::default().fg(MUTED),
-            )));
-        }
-        if i == 8 {
+        if menu_divider_before(i) {
+            let divider = match i {
+                4 => "     ├─ Filters ──────────────────────────┤",
+                6 => "     ├─ Actions ──────────────────────────┤",
+                _ => "     ├────────────────────────────────────┤",
+            };
             menu_lines.push(Line::from(Span::styled(
-                "     ├────────────────────────────────────┤",
+                divider,
                 Style::default().fg(MUTED),
             )));
         }
@@ -675,150 +1316,128 @@ fn draw_menu_popup(f: &mut Frame, app: &App) {
         .style(Style::default().bg(BG_DARK));
 
     f.render_widget(menu, area);
+    area
 }
 
-/// Draw help popup overlay
-fn draw_help_popup(f: &mut Frame) {
-    let area = centered_rect(65, 75, f.area());
+/// Help popup sections: title, key color and the actions listed
+const HELP_SECTIONS: &[(&str, Color, &[Action])] = &[
+    (
+        "Navigation",
+        WARNING,
+        &[
+            Action::MoveDown,
+            Action::MoveUp,
+            Action::PageDown,
+            Action::PageUp,
+            Action::MoveToFirst,
+            Action::MoveToLast,
+            Action::NextTab,
+            Action::PrevTab,
+        ],
+    ),
+    (
+        "Actions",
+        SUCCESS,
+        &[
+            Action::Kill,
+            Action::ToggleMark,
+            Action::MarkAll,
+            Action::Refresh,
+            Action::Export,
+        ],
+    ),
+    (
+        "Filters & Views",
+        ACCENT2,
+        &[
+            Action::ToggleMenu,
+            Action::StartSearch,
+            Action::CycleFilter,
+            Action::ToggleDocker,
+            Action::ToggleCritical,
+            Action::ToggleTunnels,
+            Action::ToggleMine,
+            Action::CycleSort,
+            Action::ToggleDetails,
+            Action::ToggleTree,
+        ],
+    ),
+    (
+        "General",
+        ACCENT,
+        &[Action::ToggleHelp, Action::Back, Action::Quit],
+    ),
+];
+
+/// Draw help popup overlay, listing the active keymap
+fn draw_help_popup(f: &mut Frame, app: &App) {
+    const KEY_WIDTH: usize = 10;
+    const DESC_WIDTH: usize = 38;
+    let rule = |left: &str, title: &str, right: &str| {
+        let title = if title.is_empty() {
+            String::new()
+        } else {
+            format!("─ {} ", title)
+        };
+        let fill = KEY_WIDTH + DESC_WIDTH + 2 - title.chars().count();
+        Line::from(Span::styled(
+            format!("   {}{}{}{}", left, title, "─".repeat(fill), right),
+            Style::default().fg(MUTED),
+        ))
+    };
+
+    let area = centered_rect(65, 80, f.area());
 
     // Clear background
     f.render_widget(Clear, area);
 
-    let help_text = vec![
+    let mut help_text = vec![
         Line::from(""),
         Line::from(vec![
             Span::styled("     ", Style::default()),
             Span::styled("⌨️ KEYBOARD SHORTCUTS", Style::default().fg(ACCENT).bold()),
         ]),
         Line::from(""),
-        Line::from(Span::styled(
-            "   ┌─ Navigation ─────────────────────────────┐",
-            Style::default().fg(MUTED),
-        )),
-        Line::from(vec![
-            Span::styled("   │ ", Style::default().fg(MUTED)),
-            Span::styled(" j/↓ ", Style::default().fg(Color::Black).bg(WARNING)),
-            Span::styled(" Move down          ", Style::default().fg(TEXT_DIM)),
-            Span::styled(" PgDn ", Style::default().fg(Color::Black).bg(WARNING)),
-            Span::styled(" Page down │", Style::default().fg(TEXT_DIM)),
-        ]),
-        Line::from(vec![
-            Span::styled("   │ ", Style::default().fg(MUTED)),
-            Span::styled(" k/↑ ", Style::default().fg(Color::Black).bg(WARNING)),
-            Span::styled(" Move up            ", Style::default().fg(TEXT_DIM)),
-            Span::styled(" PgUp ", Style::default().fg(Color::Black).bg(WARNING)),
-            Span::styled(" Page up   │", Style::default().fg(TEXT_DIM)),
-        ]),
-        Line::from(vec![
-            Span::styled("   │ ", Style::default().fg(MUTED)),
-            Span::styled(" g ", Style::default().fg(Color::Black).bg(WARNING)),
-            Span::styled(" Go to first        ", Style::default().fg(TEXT_DIM)),
-            Span::styled(" G ", Style::default().fg(Color::Black).bg(WARNING)),
-            Span::styled(" Go to last      │", Style::default().fg(TEXT_DIM)),
-        ]),
-        Line::from(Span::styled(
-            "   ├─ Actions ────────────────────────────────┤",
-            Style::default().fg(MUTED),
-        )),
-        Line::from(vec![
-            Span::styled("   │ ", Style::default().fg(MUTED)),
-            Span::styled(" K ", Style::default().fg(Color::Black).bg(DANGER).bold()),
-            Span::styled(
-                " Kill selected process (Shift+K)      │",
-                Style::default().fg(TEXT_DIM),
-            ),
-        ]),
-        Line::from(vec![
-            Span::styled("   │ ", Style::default().fg(MUTED)),
-            Span::styled(" r ", Style::default().fg(Color::Black).bg(SUCCESS)),
-            Span::styled(
-                " Refresh port list                    │",
-                Style::default().fg(TEXT_DIM),
-            ),
-        ]),
-        Line::from(vec![
-            Span::styled("   │ ", Style::default().fg(MUTED)),
-            Span::styled(" e ", Style::default().fg(Color::Black).bg(SUCCESS)),
-            Span::styled(
-                " Export ports (JSON/CSV/MD)           │",
-                Style::default().fg(TEXT_DIM),
-            ),
-        ]),
-        Line::from(Span::styled(
-            "   ├─ Filters & Views ────────────────────────┤",
-            Style::default().fg(MUTED),
-        )),
-        Line::from(vec![
-            Span::styled("   │ ", Style::default().fg(MUTED)),
-            Span::styled(" m ", Style::default().fg(Color::Black).bg(ACCENT)),
-            Span::styled(
-                " Open quick menu                      │",
-                Style::default().fg(TEXT_DIM),
-            ),
-        ]),
-        Line::from(vec![
-            Span::styled("   │ ", Style::default().fg(MUTED)),
-            Span::styled(" / ", Style::default().fg(Color::Black).bg(ACCENT)),
-            Span::styled(
-                " Search/filter by text                │",
-                Style::default().fg(TEXT_DIM),
-            ),
-        ]),
-        Line::from(vec![
-            Span::styled("   │ ", Style::default().fg(MUTED)),
-            Span::styled(" f ", Style::default().fg(Color::Black).bg(ACCENT2)),
-            Span::styled(
-                " Cycle filter (All/TCP/UDP)           │",
-                Style::default().fg(TEXT_DIM),
-            ),
-        ]),
-        Line::from(vec![
-            Span::styled("   │ ", Style::default().fg(MUTED)),
-            Span::styled(
-                " d ",
-                Style::default().fg(Color::Black).bg(Color::LightBlue),
-            ),
-            Span::styled(" Docker only      ", Style::default().fg(TEXT_DIM)),
-            Span::styled(" c ", Style::default().fg(Color::Black).bg(DANGER)),
-            Span::styled(" Critical only │", Style::default().fg(TEXT_DIM)),
-        ]),
-        Line::from(vec![
-            Span::styled("   │ ", Style::default().fg(MUTED)),
-            Span::styled(" Tab ", Style::default().fg(Color::Black).bg(ACCENT2)),
-            Span::styled(" Cycle sort mode ", Style::default().fg(TEXT_DIM)),
-            Span::styled(" ⏎ ", Style::default().fg(Color::Black).bg(SUCCESS)),
-            Span::styled(" Toggle details │", Style::default().fg(TEXT_DIM)),
-        ]),
-        Line::from(Span::styled(
-            "   ├─ General ────────────────────────────────┤",
-            Style::default().fg(MUTED),
-        )),
-        Line::from(vec![
-            Span::styled("   │ ", Style::default().fg(MUTED)),
-            Span::styled(" ? ", Style::default().fg(Color::Black).bg(ACCENT2)),
-            Span::styled(" Toggle this help   ", Style::default().fg(TEXT_DIM)),
-            Span::styled(" q ", Style::default().fg(Color::Black).bg(DANGER)),
-            Span::styled(" Quit portr    │", Style::default().fg(TEXT_DIM)),
-        ]),
-        Line::from(vec![
-            Span::styled("   │ ", Style::default().fg(MUTED)),
-            Span::styled(" Esc ", Style::default().fg(Color::Black).bg(WARNING)),
-            Span::styled(
-                " Clear filters / Exit                │",
-                Style::default().fg(TEXT_DIM),
-            ),
-        ]),
-        Line::from(Span::styled(
-            "   └──────────────────────────────────────────┘",
-            Style::default().fg(MUTED),
-        )),
-        Line::from(""),
-        Line::from(vec![Span::styled(
-            "          Press any key to close",
-            Style::default().fg(TEXT_DIM).italic(),
-        )]),
     ];
 
+    for (i, (title, color, actions)) in HELP_SECTIONS.iter().enumerate() {
+        help_text.push(if i == 0 {
+            rule("┌", title, "┐")
+        } else {
+            rule("├", title, "┤")
+        });
+        for action in *actions {
+            let keys = app.keymap.label(*action);
+            let keys = if keys.is_empty() {
+                "-".to_string()
+            } else {
+                keys
+            };
+            let key_style = if *action == Action::Kill {
+                Style::default().fg(Color::Black).bg(DANGER).bold()
+            } else {
+                Style::default().fg(Color::Black).bg(*color)
+            };
+            let pad = KEY_WIDTH.saturating_sub(keys.chars().count() + 2);
+            help_text.push(Line::from(vec![
+                Span::styled("   │ ", Style::default().fg(MUTED)),
+                Span::styled(format!(" {} ", keys), key_style),
+                Span::raw(" ".repeat(pad)),
+                Span::styled(
+                    format!(" {:<width$}", action.description(), width = DESC_WIDTH - 1),
+                    Style::default().fg(TEXT_DIM),
+                ),
+                Span::styled("│", Style::default().fg(MUTED)),
+            ]));
+        }
+    }
+    help_text.push(rule("└", "", "┘"));
+    help_text.push(Line::from(""));
+    help_text.push(Line::from(vec![Span::styled(
+        "          Press any key to close · rebind keys under [keys] in the config",
+        Style::default().fg(TEXT_DIM).italic(),
+    )]));
+
     let help = Paragraph::new(help_text)
         .block(
             Block::default()
@@ -856,6 +1475,24 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
+/// "user:group", plus the effective IDs when they differ (setuid, setgid)
+fn owner_label(port: &PortInfo) -> String {
+    let pair = |user: Option<&str>, group: Option<&str>| match group {
+        Some(group) => format!("{}:{}", user.unwrap_or("-"), group),
+        None => user.unwrap_or("-").to_string(),
+    };
+    let real = pair(port.user.as_deref(), port.group.as_deref());
+    let effective = pair(
+        port.effective_user.as_deref().or(port.user.as_deref()),
+        port.effective_group.as_deref().or(port.group.as_deref()),
+    );
+    if effective == real {
+        real
+    } else {
+        format!("{} (eff. {})", real, effective)
+    }
+}
+
 /// Format MB to human readable
 fn format_mb(mb: f64) -> String {
     if mb >= 1024.0 {
@@ -884,12 +1521,13 @@ fn format_uptime(secs: u64) -> String {
     }
 }
 
-/// Truncate string with ellipsis
+/// Truncate string with ellipsis, counting characters rather than bytes
 fn truncate(s: &str, max: usize) -> String {
-    if s.len() <= max {
+    if s.chars().count() <= max {
         s.to_string()
     } else {
-        format!("{}…", &s[..max - 1])
+        let head: String = s.chars().take(max - 1).collect();
+        format!("{}…", head)
     }
 }
 
@@ -904,6 +1542,484 @@ fn state_style(state: &str) -> Style {
     }
 }
 
+/// Draw the container action dialog (opened by K on a Docker port)
+fn draw_container_dialog(f: &mut Frame, app: &App) {
+    use super::app::CONTAINER_ACTIONS;
+
+    let container = match app.get_selected().and_then(|p| p.container.as_ref()) {
+        Some(c) => c,
+        None => return,
+    };
+
+    let area = centered_rect(50, 40, f.area());
+    f.render_widget(Clear, area);
+
+    let critical = services::is_critical_image(&container.image);
+
+    let mut content = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  🐳 ", Style::default()),
+            Span::styled(
+                container.name.as_str(),
+                Style::default().fg(Color::LightBlue).bold(),
+            ),
+            Span::styled(
+                format!("  {}", container.image),
+                Style::default().fg(TEXT_DIM).italic(),
+            ),
+        ]),
+        Line::from(""),
+    ];
+
+    for (key, label, desc) in CONTAINER_ACTIONS {
+        content.push(Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled(
+                format!(" {} ", key),
+                Style::default().fg(Color::Black).bg(ACCENT).bold(),
+            ),
+            Span::styled(
+                format!(" {:<9}", label),
+                Style::default().fg(SUCCESS).bold(),
+            ),
+            Span::styled(*desc, Style::default().fg(TEXT_DIM)),
+        ]));
+    }
+    content.push(Line::from(""));
+    content.push(Line::from(Span::styled(
+        format!("  Stop timeout: {}s", app.stop_timeout),
+        Style::default().fg(MUTED),
+    )));
+    if critical {
+        content.push(Line::from(Span::styled(
+            "  ⚠ Critical container - you will be asked to type yes",
+            Style::default().fg(DANGER).bold(),
+        )));
+    }
+    content.push(Line::from(""));
+    content.push(Line::from(vec![
+        Span::styled("  ", Style::default()),
+        Span::styled(" Esc ", Style::default().fg(Color::Black).bg(MUTED)),
+        Span::styled(" Cancel", Style::default().fg(TEXT_DIM)),
+    ]));
+
+    let border = if critical { DANGER } else { Color::LightBlue };
+    let popup = Paragraph::new(content).block(
+        Block::default()
+            .title(Span::styled(
+                " Container Action ",
+                Style::default().fg(border).bold(),
+            ))
+            .title_alignment(ratatui::layout::Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .border_style(Style::default().fg(border))
+            .style(Style::default().bg(BG_DARK)),
+    );
+
+    f.render_widget(popup, area);
+}
+
+/// Draw the confirmation for a picked container action
+fn draw_container_confirm(f: &mut Frame, app: &App) {
+    let Some(confirm) = app.container_confirm.as_ref() else {
+        return;
+    };
+    let (label, desc) = confirm.action();
+    let border = if confirm.needs_yes() { DANGER } else { WARNING };
+
+    let area = centered_rect(50, 40, f.area());
+    f.render_widget(Clear, area);
+
+    let mut content = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  🐳 ", Style::default()),
+            Span::styled(
+                confirm.container.name.as_str(),
+                Style::default().fg(Color::LightBlue).bold(),
+            ),
+            Span::styled(
+                format!("  {}", confirm.container.image),
+                Style::default().fg(TEXT_DIM).italic(),
+            ),
+        ]),
+        Line::from(""),
+    ];
+
+    if confirm.needs_yes() {
+        content.push(Line::from(Span::styled(
+            "  ⚠ CRITICAL DATABASE container",
+            Style::default().fg(DANGER).bold(),
+        )));
+        content.push(Line::from(Span::styled(
+            format!("  {} may cause DATA LOSS ({})", label, desc),
+            Style::default().fg(WARNING),
+        )));
+        content.push(Line::from(""));
+        content.push(Line::from(vec![
+            Span::styled("  Type ", Style::default().fg(TEXT_DIM)),
+            Span::styled("yes", Style::default().fg(DANGER).bold()),
+            Span::styled(" and press Enter: ", Style::default().fg(TEXT_DIM)),
+            Span::styled(
+                format!("{}▌", confirm.input),
+                Style::default().fg(Color::White).bold(),
+            ),
+        ]));
+        content.push(Line::from(""));
+        content.push(Line::from(vec![
+            Span::styled("  ", Style::default()),
+            Span::styled(" Esc ", Style::default().fg(Color::Black).bg(MUTED)),
+            Span::styled(" Cancel", Style::default().fg(TEXT_DIM)),
+        ]));
+    } else {
+        content.push(Line::from(Span::styled(
+            format!("  {}? ({})", label, desc),
+            Style::default().fg(WARNING).bold(),
+        )));
+        content.push(Line::from(""));
+        content.push(Line::from(vec![
+            Span::styled("  ", Style::default()),
+            Span::styled(" y ", Style::default().fg(Color::Black).bg(DANGER).bold()),
+            Span::styled(format!(" {}   ", label), Style::default().fg(DANGER).bold()),
+            Span::styled(" N ", Style::default().fg(Color::Black).bg(MUTED)),
+            Span::styled(" Cancel", Style::default().fg(TEXT_DIM)),
+        ]));
+    }
+
+    let popup = Paragraph::new(content).block(
+        Block::default()
+            .title(Span::styled(
+                format!(" {} Container ", label),
+                Style::default().fg(border).bold(),
+            ))
+            .title_alignment(ratatui::layout::Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .border_style(Style::default().fg(border))
+            .style(Style::default().bg(BG_DARK)),
+    );
+
+    f.render_widget(popup, area);
+}
+
+/// Draw the kill confirmation (critical service or marked batch)
+fn draw_kill_confirm(f: &mut Frame, app: &App) {
+    let confirm = match app.kill_confirm.as_ref() {
+        Some(c) => c,
+        None => return,
+    };
+
+    let area = centered_rect(60, 60, f.area());
+    f.render_widget(Clear, area);
+
+    let mut content = vec![Line::from("")];
+    match confirm.targets.as_slice() {
+        [info] => content.extend(kill_target_lines(confirm, info)),
+        targets => content.extend(kill_batch_lines(confirm, targets)),
+    }
+
+    content.push(Line::from(vec![
+        Span::styled("  Signal:  ", Style::default().fg(MUTED)),
+        Span::styled(
+            crate::process::kill_signal_name(confirm.force),
+            Style::default().fg(WARNING).bold(),
+        ),
+        Span::styled("  (Tab to switch)", Style::default().fg(MUTED)),
+    ]));
+    content.push(Line::from(""));
+
+    if confirm.needs_yes() {
+        content.push(Line::from(vec![
+            Span::styled("  Type ", Style::default().fg(TEXT_DIM)),
+            Span::styled("yes", Style::default().fg(DANGER).bold()),
+            Span::styled(" and press Enter: ", Style::default().fg(TEXT_DIM)),
+            Span::styled(
+                format!("{}▌", confirm.input),
+                Style::default().fg(Color::White).bold(),
+            ),
+        ]));
+        content.push(Line::from(""));
+    }
+    let mut keys = vec![Span::styled("  ", Style::default())];
+    if confirm.needs_yes() {
+        keys.push(Span::styled(
+            " Esc ",
+            Style::default().fg(Color::Black).bg(MUTED),
+        ));
+        keys.push(Span::styled(" Cancel", Style::default().fg(TEXT_DIM)));
+    } else {
+        keys.push(Span::styled(
+            " y ",
+            Style::default().fg(Color::Black).bg(DANGER).bold(),
+        ));
+        keys.push(Span::styled(" Kill   ", Style::default().fg(DANGER).bold()));
+        keys.push(Span::styled(
+            " N ",
+            Style::default().fg(Color::Black).bg(MUTED),
+        ));
+        keys.push(Span::styled(" Cancel", Style::default().fg(TEXT_DIM)));
+    }
+    content.push(Line::from(keys));
+
+    let (title, border) = match confirm.targets.as_slice() {
+        _ if confirm.needs_yes() => (" Kill Critical Service ", DANGER),
+        [_] => (" Kill Process ", WARNING),
+        _ => (" Kill Marked Ports ", WARNING),
+    };
+    let popup = Paragraph::new(content).block(
+        Block::default()
+            .title(Span::styled(title, Style::default().fg(border).bold()))
+            .title_alignment(ratatui::layout::Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .border_style(Style::default().fg(border))
+            .style(Style::default().bg(BG_DARK)),
+    );
+
+    f.render_widget(popup, area);
+}
+
+/// Draw the confirmation for signaling a process tree node
+fn draw_process_signal(f: &mut Frame, app: &App) {
+    let Some(signal) = app.process_signal.as_ref() else {
+        return;
+    };
+    let node = &signal.node;
+
+    let area = centered_rect(55, 40, f.area());
+    f.render_widget(Clear, area);
+
+    let mut content = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  Process: ", Style::default().fg(MUTED)),
+            Span::styled(node.name.as_str(), Style::default().fg(SUCCESS).bold()),
+            Span::styled(format!(" (PID {})", node.pid), Style::default().fg(WARNING)),
+        ]),
+    ];
+    if !node.ports.is_empty() {
+        let ports: Vec<String> = node.ports.iter().map(|p| p.to_string()).collect();
+        content.push(Line::from(vec![
+            Span::styled("  Ports:   ", Style::default().fg(MUTED)),
+            Span::styled(ports.join(", "), Style::default().fg(ACCENT)),
+        ]));
+    }
+    if signal.ancestor {
+        content.push(Line::from(""));
+        content.push(Line::from(Span::styled(
+            "  ⚠ Parent process: everything it started may go down with it",
+            Style::default().fg(DANGER).bold(),
+        )));
+    }
+    if signal.critical {
+        if !signal.ancestor {
+            content.push(Line::from(""));
+        }
+        content.push(Line::from(Span::styled(
+            "  ⚠ Holds a critical service's port",
+            Style::default().fg(DANGER).bold(),
+        )));
+    }
+    content.push(Line::from(""));
+    content.push(Line::from(vec![
+        Span::styled("  Signal:  ", Style::default().fg(MUTED)),
+        Span::styled(
+            crate::process::kill_signal_name(signal.force),
+            Style::default().fg(WARNING).bold(),
+        ),
+        Span::styled("  (Tab to switch)", Style::default().fg(MUTED)),
+    ]));
+    content.push(Line::from(""));
+
+    let mut keys = vec![Span::styled("  ", Style::default())];
+    if signal.needs_yes() {
+        content.push(Line::from(vec![
+            Span::styled("  Type ", Style::default().fg(TEXT_DIM)),
+            Span::styled("yes", Style::default().fg(DANGER).bold()),
+            Span::styled(" and press Enter: ", Style::default().fg(TEXT_DIM)),
+            Span::styled(
+                format!("{}▌", signal.input),
+                Style::default().fg(Color::White).bold(),
+            ),
+        ]));
+        content.push(Line::from(""));
+    } else {
+        keys.push(Span::styled(
+            " Enter ",
+            Style::default().fg(Color::Black).bg(DANGER).bold(),
+        ));
+        keys.push(Span::styled(" Send   ", Style::default().fg(DANGER).bold()));
+    }
+    keys.push(Span::styled(
+        " Esc ",
+        Style::default().fg(Color::Black).bg(MUTED),
+    ));
+    keys.push(Span::styled(" Cancel", Style::default().fg(TEXT_DIM)));
+    content.push(Line::from(keys));
+
+    let border = if signal.needs_yes() { DANGER } else { WARNING };
+    let popup = Paragraph::new(content).block(
+        Block::default()
+            .title(Span::styled(
+                " Signal Process ",
+                Style::default().fg(border).bold(),
+            ))
+            .title_alignment(ratatui::layout::Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .border_style(Style::default().fg(border))
+            .style(Style::default().bg(BG_DARK)),
+    );
+
+    f.render_widget(popup, area);
+}
+
+/// Service, risk, process and process tree of a single kill target
+fn kill_target_lines<'a>(confirm: &'a KillConfirmation, info: &'a PortInfo) -> Vec<Line<'a>> {
+    let (service, risk) = match services::lookup(info.port) {
+        Some(s) => (format!("{} - {}", s.name, s.description), s.risk.label()),
+        None => ("Unknown service".to_string(), "Unknown Risk"),
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("  ⚠ Port ", Style::default().fg(DANGER).bold()),
+            Span::styled(info.port.to_string(), Style::default().fg(ACCENT).bold()),
+            Span::styled(format!("  {}", service), Style::default().fg(TEXT_DIM)),
+        ]),
+        Line::from(vec![
+            Span::styled("  Risk:    ", Style::default().fg(MUTED)),
+            Span::styled(risk, Style::default().fg(DANGER).bold()),
+        ]),
+        Line::from(vec![
+            Span::styled("  Process: ", Style::default().fg(MUTED)),
+            Span::styled(
+                info.process_name.as_str(),
+                Style::default().fg(SUCCESS).bold(),
+            ),
+            Span::styled(format!(" (PID {})", info.pid), Style::default().fg(WARNING)),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "  Process tree",
+            Style::default().fg(ACCENT).bold(),
+        )),
+    ];
+
+    // Root first, as in `portr <port> --tree`
+    let depth = confirm.tree.len();
+    for (i, (pid, name)) in confirm.tree.iter().rev().enumerate() {
+        let connector = if i == 0 { "●" } else { "└─" };
+        let mut spans = vec![Span::styled(
+            format!("  {}{} ", "  ".repeat(i), connector),
+            Style::default().fg(MUTED),
+        )];
+        if *pid == info.pid {
+            spans.push(Span::styled(
+                name.as_str(),
+                Style::default().fg(SUCCESS).bold(),
+            ));
+            spans.push(Span::styled(
+                format!(" (PID {}) ← killed", pid),
+                Style::default().fg(DANGER),
+            ));
+        } else {
+            spans.push(Span::styled(name.as_str(), Style::default().fg(TEXT_DIM)));
+            spans.push(Span::styled(
+                format!(" (PID {})", pid),
+                Style::default().fg(MUTED),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+    for (pid, name) in confirm.children.iter().take(5) {
+        lines.push(Line::from(Span::styled(
+            format!("  {}└─ {} (PID {})", "  ".repeat(depth), name, pid),
+            Style::default().fg(TEXT_DIM),
+        )));
+    }
+    if confirm.children.len() > 5 {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {}   … {} more children",
+                "  ".repeat(depth),
+                confirm.children.len() - 5
+            ),
+            Style::default().fg(MUTED),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines
+}
+
+/// Risk summary and list of a batch of kill targets
+fn kill_batch_lines<'a>(confirm: &'a KillConfirmation, targets: &'a [PortInfo]) -> Vec<Line<'a>> {
+    const SHOWN: usize = 8;
+
+    let mut summary = vec![Span::styled(
+        format!("  {} ports: ", targets.len()),
+        Style::default().fg(Color::White).bold(),
+    )];
+    for (i, (level, count)) in confirm.risk_summary().into_iter().enumerate() {
+        if i > 0 {
+            summary.push(Span::styled(" · ", Style::default().fg(MUTED)));
+        }
+        summary.push(Span::styled(
+            format!("{} {}", count, level.map_or("Unknown", |l| l.label())),
+            risk_style(level),
+        ));
+    }
+
+    let mut lines = vec![Line::from(summary), Line::from("")];
+    for info in targets.iter().take(SHOWN) {
+        let level = info.risk();
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:>6} ", info.port),
+                Style::default().fg(ACCENT).bold(),
+            ),
+            Span::styled(
+                format!("{:<20}", truncate(&info.display_name(), 20)),
+                Style::default().fg(Color::White),
+            ),
+            Span::styled(format!(" PID {:<8}", info.pid), Style::default().fg(MUTED)),
+            Span::styled(level.map_or("", |l| l.label()), risk_style(level)),
+        ]));
+    }
+    if targets.len() > SHOWN {
+        lines.push(Line::from(Span::styled(
+            format!("  … {} more", targets.len() - SHOWN),
+            Style::default().fg(MUTED),
+        )));
+    }
+    if confirm.skipped > 0 {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {} container port(s) skipped - use K on each",
+                confirm.skipped
+            ),
+            Style::default().fg(Color::LightBlue),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines
+}
+
+/// Color for a risk level (`None` is an unknown port)
+fn risk_style(level: Option<services::RiskLevel>) -> Style {
+    use services::RiskLevel;
+
+    match level {
+        Some(RiskLevel::Critical) => Style::default().fg(DANGER).bold(),
+        Some(RiskLevel::High) => Style::default().fg(DANGER),
+        Some(RiskLevel::Medium) => Style::default().fg(WARNING),
+        Some(RiskLevel::Low) => Style::default().fg(SUCCESS),
+        None => Style::default().fg(TEXT_DIM),
+    }
+}
+
 /// Draw the export popup
 fn draw_export_popup(f: &mut Frame, app: &App) {
     use super::app::ExportFormat;
@@ -935,7 +2051,11 @@ fn draw_export_popup(f: &mut Frame, app: &App) {
         Line::from(vec![
             Span::styled("  📁 Export ", Style::default().fg(ACCENT).bold()),
             Span::styled(
-                format!("{} ports", app.ports.len()),
+                if app.marked.is_empty() {
+                    format!("{} ports", app.ports.len())
+                } else {
+                    format!("{} marked ports", app.marked.len())
+                },
                 Style::default().fg(SUCCESS).bold(),
             ),
             Span::styled(" to file", Style::default().fg(ACCENT).bold()),
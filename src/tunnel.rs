@@ -0,0 +1,288 @@
+//! SSH tunnel detection
+//!
+//! `ssh -L` and `ssh -D` listeners only show up as `ssh` in the process
+//! table. Parsing the client's command line recovers the forward target and
+//! the server it goes through. Remote forwards (`ssh -R`) listen on the
+//! server, where they are held by the per-connection `sshd` process.
+
+use serde::{Deserialize, Serialize};
+
+/// Kind of SSH forward behind a listener
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TunnelKind {
+    /// `ssh -L`: local port forwarded to a host:port reachable from the server
+    Local,
+    /// `ssh -R`: port opened on this host by sshd for a connected client
+    Remote,
+    /// `ssh -D`: local SOCKS proxy
+    Socks,
+}
+
+impl TunnelKind {
+    /// ssh flag that creates this kind of forward
+    pub fn flag(&self) -> &'static str {
+        match self {
+            TunnelKind::Local => "-L",
+            TunnelKind::Remote => "-R",
+            TunnelKind::Socks => "-D",
+        }
+    }
+}
+
+/// An SSH tunnel listener
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SshTunnel {
+    pub kind: TunnelKind,
+    /// Forward destination as `host:port` (`-L` only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// SSH server the tunnel goes through, as given on the command line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_host: Option<String>,
+}
+
+impl SshTunnel {
+    /// Whether the listener is a SOCKS proxy
+    pub fn is_proxy(&self) -> bool {
+        self.kind == TunnelKind::Socks
+    }
+
+    /// Note shown when killing a client-side tunnel
+    ///
+    /// `None` for remote forwards: killing sshd's session process also
+    /// disconnects the client's shell.
+    pub fn kill_note(&self) -> Option<String> {
+        match self.kind {
+            TunnelKind::Remote => None,
+            _ => Some(format!(
+                "ssh tunnel ({}) - only the tunnel closes, nothing on the server is stopped",
+                self
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for SshTunnel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            TunnelKind::Local => write!(f, "→ {}", self.target.as_deref().unwrap_or("?"))?,
+            TunnelKind::Remote => write!(f, "remote forward")?,
+            TunnelKind::Socks => write!(f, "SOCKS proxy")?,
+        }
+        if let Some(ref host) = self.remote_host {
+            write!(f, " via {}", host)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check whether a process name is the OpenSSH client
+pub fn is_ssh_client(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name == "ssh" || name == "ssh.exe"
+}
+
+/// Check whether a process name is an OpenSSH server process
+fn is_sshd(name: &str) -> bool {
+    name == "sshd" || name == "sshd-session"
+}
+
+/// ssh options that take an argument (from `ssh(1)`)
+const OPTIONS_WITH_VALUE: &str = "BbcDEeFIiJLlmOopQRSWw";
+
+/// Detect the SSH tunnel holding `local_port`
+///
+/// For the ssh client, the `-L`/`-D` forward whose listen port matches is
+/// returned. A listener held by an sshd session process (one whose parent is
+/// sshd too) is a client's `-R` forward.
+pub fn detect_tunnel(
+    process_name: &str,
+    parent_name: Option<&str>,
+    cmd: &[String],
+    local_port: u16,
+) -> Option<SshTunnel> {
+    if is_sshd(process_name) && parent_name.is_some_and(is_sshd) {
+        return Some(SshTunnel {
+            kind: TunnelKind::Remote,
+            target: None,
+            remote_host: None,
+        });
+    }
+    if !is_ssh_client(process_name) {
+        return None;
+    }
+    parse_ssh_command(cmd)
+        .into_iter()
+        .find(|(port, _)| *port == local_port)
+        .map(|(_, tunnel)| tunnel)
+}
+
+/// Parse an ssh command line into its local listeners as (port, tunnel)
+pub fn parse_ssh_command(cmd: &[String]) -> Vec<(u16, SshTunnel)> {
+    let mut forwards = Vec::new();
+    let mut user = None;
+    let mut destination = None;
+    let mut args = cmd.iter().skip(1);
+
+    while let Some(arg) = args.next() {
+        let Some(cluster) = arg.strip_prefix('-').filter(|c| !c.is_empty()) else {
+            // First non-option is the destination; the rest is the remote command
+            destination = Some(arg.as_str());
+            break;
+        };
+        if cluster == "-" {
+            destination = args.next().map(String::as_str);
+            break;
+        }
+
+        // getopt-style cluster: flags until one that takes a value, which
+        // consumes the rest of the cluster or the next argument
+        for (i, opt) in cluster.char_indices() {
+            if !OPTIONS_WITH_VALUE.contains(opt) {
+                continue;
+            }
+            let rest = &cluster[i + opt.len_utf8()..];
+            let value = if rest.is_empty() {
+                args.next().map(String::as_str)
+            } else {
+                Some(rest)
+            };
+            match (opt, value) {
+                ('L', Some(spec)) => forwards.extend(parse_local_spec(spec)),
+                ('D', Some(spec)) => forwards.extend(parse_socks_spec(spec)),
+                ('l', Some(login)) => user = Some(login),
+                _ => {}
+            }
+            break;
+        }
+    }
+
+    let remote_host = destination.map(|dest| {
+        let dest = dest.strip_prefix("ssh://").unwrap_or(dest);
+        match user {
+            Some(user) if !dest.contains('@') => format!("{}@{}", user, dest),
+            _ => dest.to_string(),
+        }
+    });
+
+    forwards
+        .into_iter()
+        .map(|(port, kind, target)| {
+            (
+                port,
+                SshTunnel {
+                    kind,
+                    target,
+                    remote_host: remote_host.clone(),
+                },
+            )
+        })
+        .collect()
+}
+
+type Forward = (u16, TunnelKind, Option<String>);
+
+/// Parse `[bind:]port:host:hostport`; Unix socket forwards are skipped
+fn parse_local_spec(spec: &str) -> Option<Forward> {
+    let fields = split_fields(spec);
+    let (port, host, host_port) = match fields.as_slice() {
+        [port, host, host_port] | [_, port, host, host_port] => (port, host, host_port),
+        _ => return None,
+    };
+    let port = port.parse().ok()?;
+    let host_port: u16 = host_port.parse().ok()?;
+    let target = if host.contains(':') {
+        format!("[{}]:{}", host, host_port)
+    } else {
+        format!("{}:{}", host, host_port)
+    };
+    Some((port, TunnelKind::Local, Some(target)))
+}
+
+/// Parse `[bind:]port`
+fn parse_socks_spec(spec: &str) -> Option<Forward> {
+    let port = split_fields(spec).last()?.parse().ok()?;
+    Some((port, TunnelKind::Socks, None))
+}
+
+/// Split a forward spec on `:` (or `/`), keeping bracketed IPv6 addresses whole
+fn split_fields(spec: &str) -> Vec<&str> {
+    let sep = if spec.contains('[') || !spec.contains('/') {
+        ':'
+    } else {
+        '/'
+    };
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut depth = 0;
+    for (i, c) in spec.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            c if c == sep && depth == 0 => {
+                fields.push(&spec[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    fields.push(&spec[start..]);
+    fields
+        .into_iter()
+        .map(|f| f.trim_start_matches('[').trim_end_matches(']'))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cmd(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_local_forwards() {
+        let line = cmd("ssh -fN -L 5432:db.internal:5432 -L127.0.0.1:8080:web:80 deploy@bastion");
+        let forwards = parse_ssh_command(&line);
+        assert_eq!(forwards.len(), 2);
+
+        let tunnel = detect_tunnel("ssh", Some("zsh"), &line, 8080).unwrap();
+        assert_eq!(tunnel.kind, TunnelKind::Local);
+        assert_eq!(tunnel.target.as_deref(), Some("web:80"));
+        assert_eq!(tunnel.remote_host.as_deref(), Some("deploy@bastion"));
+        assert_eq!(tunnel.to_string(), "→ web:80 via deploy@bastion");
+        assert!(tunnel.kill_note().is_some());
+    }
+
+    #[test]
+    fn test_socks_and_clustered_options() {
+        let line = cmd("ssh -NTD 1080 -p 2222 -l alice jump.example.com uptime");
+        let tunnel = detect_tunnel("ssh", None, &line, 1080).unwrap();
+        assert!(tunnel.is_proxy());
+        assert_eq!(
+            tunnel.remote_host.as_deref(),
+            Some("alice@jump.example.com")
+        );
+        assert_eq!(tunnel.to_string(), "SOCKS proxy via alice@jump.example.com");
+        assert!(detect_tunnel("ssh", None, &line, 2222).is_none());
+    }
+
+    #[test]
+    fn test_ipv6_spec() {
+        let forwards = parse_ssh_command(&cmd("ssh -L [::1]:9000:[fd00::5]:443 host"));
+        assert_eq!(forwards[0].0, 9000);
+        assert_eq!(forwards[0].1.target.as_deref(), Some("[fd00::5]:443"));
+    }
+
+    #[test]
+    fn test_sshd_remote_forward() {
+        let tunnel = detect_tunnel("sshd", Some("sshd"), &cmd("sshd: alice@pts/0"), 9000).unwrap();
+        assert_eq!(tunnel.kind, TunnelKind::Remote);
+        assert!(tunnel.kill_note().is_none());
+
+        // The daemon itself is not a tunnel
+        assert!(detect_tunnel("sshd", Some("systemd"), &cmd("/usr/sbin/sshd -D"), 22).is_none());
+    }
+}
@@ -0,0 +1,136 @@
+//! Minimal mock of the Docker Engine HTTP API over a Unix socket
+//!
+//! Serves canned container listings and records every request, so Docker
+//! commands can be tested without a daemon. Point portr at it with
+//! `DOCKER_HOST=unix://<path>`.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A request received by the mock ("POST /containers/web/stop")
+pub type Recorded = Arc<Mutex<Vec<String>>>;
+
+/// Mock Docker daemon listening on a temporary Unix socket
+pub struct MockDocker {
+    pub socket: PathBuf,
+    requests: Recorded,
+}
+
+impl MockDocker {
+    /// Start a mock that answers `GET /containers/json` with `containers`
+    pub fn start(containers: serde_json::Value) -> Self {
+        let socket = std::env::temp_dir().join(format!(
+            "portr-mock-docker-{}-{}.sock",
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::SeqCst)
+        ));
+        let _ = std::fs::remove_file(&socket);
+
+        let listener = UnixListener::bind(&socket).expect("bind mock docker socket");
+        let requests: Recorded = Arc::default();
+        let recorded = Arc::clone(&requests);
+        let body = containers.to_string();
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                handle(stream, &body, &recorded);
+            }
+        });
+
+        Self { socket, requests }
+    }
+
+    /// Value for the `DOCKER_HOST` environment variable
+    pub fn docker_host(&self) -> String {
+        format!("unix://{}", self.socket.display())
+    }
+
+    /// Requests received so far, without the API version prefix or query
+    pub fn requests(&self) -> Vec<String> {
+        self.requests_with_query()
+            .into_iter()
+            .map(|r| r.split('?').next().unwrap_or("").to_string())
+            .collect()
+    }
+
+    /// Requests received so far, including query strings
+    pub fn requests_with_query(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockDocker {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+fn handle(stream: UnixStream, containers: &str, recorded: &Recorded) {
+    let mut reader = BufReader::new(&stream);
+
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+
+    // Drain headers, remembering the body length
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+            break;
+        }
+        if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+            content_length = value.trim().parse().unwrap_or(0);
+        }
+    }
+    let mut body = vec![0; content_length];
+    let _ = reader.read_exact(&mut body);
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let target = strip_version(parts.next().unwrap_or(""));
+    recorded
+        .lock()
+        .unwrap()
+        .push(format!("{} {}", method, target));
+    let path = target.split('?').next().unwrap_or("").to_string();
+
+    let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+    let (status, body) = match (method.as_str(), segments.as_slice()) {
+        ("GET", ["_ping"]) => ("200 OK", "OK".to_string()),
+        ("GET", ["containers", "json"]) => ("200 OK", containers.to_string()),
+        ("GET", ["containers", _, "json"]) => ("200 OK", "{}".to_string()),
+        ("POST", ["containers", _, _]) => ("204 No Content", String::new()),
+        ("DELETE", ["containers", _]) => ("204 No Content", String::new()),
+        _ => (
+            "404 Not Found",
+            r#"{"message":"page not found"}"#.to_string(),
+        ),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = (&stream).write_all(response.as_bytes());
+}
+
+/// Strip a leading "/v1.xx" API version
+fn strip_version(path: &str) -> String {
+    match path.strip_prefix("/v") {
+        Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => rest
+            .find('/')
+            .map(|i| rest[i..].to_string())
+            .unwrap_or_default(),
+        _ => path.to_string(),
+    }
+}
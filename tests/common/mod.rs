@@ -0,0 +1,6 @@
+//! Shared helpers for integration tests
+
+#![allow(dead_code)]
+
+#[cfg(all(unix, feature = "docker"))]
+pub mod mock_docker;
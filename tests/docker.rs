@@ -0,0 +1,343 @@
+//! Docker command tests against a mock Docker Engine API
+//!
+//! Run with: `cargo test --features docker --test docker`
+
+#![cfg(all(unix, feature = "docker"))]
+
+mod common;
+
+use common::mock_docker::MockDocker;
+use serde_json::json;
+use std::process::Command;
+
+fn containers() -> serde_json::Value {
+    json!([
+        {
+            "Id": "0123456789abcdef0123",
+            "Names": ["/web"],
+            "Image": "nginx:latest",
+            "State": "running",
+            "Status": "Up 2 hours",
+            "Ports": [
+                {"IP": "0.0.0.0", "PrivatePort": 80, "PublicPort": 8080, "Type": "tcp"}
+            ]
+        },
+        {
+            "Id": "fedcba98765432100000",
+            "Names": ["/db"],
+            "Image": "postgres:15",
+            "State": "running",
+            "Status": "Up 5 minutes",
+            "Ports": [
+                {"IP": "127.0.0.1", "PrivatePort": 5432, "PublicPort": 5432, "Type": "tcp"},
+                {"PrivatePort": 9187, "Type": "tcp"}
+            ]
+        }
+    ])
+}
+
+/// Run portr against the mock daemon
+fn portr(mock: &MockDocker, args: &[&str]) -> (String, String, bool) {
+    let output = Command::new(env!("CARGO_BIN_EXE_portr"))
+        .args(args)
+        .env("DOCKER_HOST", mock.docker_host())
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("Failed to execute portr");
+
+    (
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+        output.status.success(),
+    )
+}
+
+#[test]
+fn test_docker_ls_json() {
+    let mock = MockDocker::start(containers());
+    let (stdout, stderr, success) = portr(&mock, &["docker", "ls", "--json"]);
+    assert!(success, "stderr: {}", stderr);
+
+    let rows: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let rows = rows.as_array().unwrap();
+    assert_eq!(rows.len(), 3);
+
+    assert_eq!(rows[0]["container"], "web");
+    assert_eq!(rows[0]["host_ip"], "0.0.0.0");
+    assert_eq!(rows[0]["host_port"], 8080);
+    assert_eq!(rows[0]["container_port"], 80);
+    assert_eq!(rows[0]["critical"], false);
+
+    assert_eq!(rows[1]["container"], "db");
+    assert_eq!(rows[1]["host_ip"], "127.0.0.1");
+    assert_eq!(rows[1]["critical"], true);
+
+    // Exposed-only port has no host binding
+    assert!(rows[2]["host_port"].is_null());
+}
+
+#[test]
+fn test_docker_ls_csv_and_markdown() {
+    let mock = MockDocker::start(containers());
+
+    let (csv, _, success) = portr(&mock, &["docker", "ls", "--csv"]);
+    assert!(success);
+    assert!(csv.starts_with("container,id,image,status,host_ip,host_port"));
+    assert!(csv.contains("web,0123456789ab,nginx:latest,Up 2 hours,0.0.0.0,8080,80,tcp,false"));
+
+    let (md, _, success) = portr(&mock, &["docker", "ls", "--md"]);
+    assert!(success);
+    assert!(md.contains("| db | postgres:15 | Up 5 minutes | 127.0.0.1:5432 | 5432/tcp |"));
+}
+
+#[test]
+fn test_docker_ls_pretty() {
+    let mock = MockDocker::start(containers());
+    let (stdout, _, success) = portr(&mock, &["docker", "ls"]);
+    assert!(success);
+    assert!(stdout.contains("CONTAINER"));
+    assert!(stdout.contains("0.0.0.0:8080"));
+    assert!(stdout.contains("2 container(s) running"));
+}
+
+#[test]
+fn test_docker_stop_by_name() {
+    let mock = MockDocker::start(containers());
+    let (stdout, stderr, success) = portr(&mock, &["docker", "stop", "web", "--force"]);
+    assert!(success, "stderr: {}", stderr);
+    assert!(stdout.contains("Stopped container web"));
+    assert!(mock
+        .requests()
+        .contains(&"POST /containers/web/stop".to_string()));
+}
+
+#[test]
+fn test_docker_restart_by_port() {
+    let mock = MockDocker::start(containers());
+    let (stdout, stderr, success) = portr(&mock, &["docker", "restart", "5432", "-f"]);
+    assert!(success, "stderr: {}", stderr);
+    assert!(stdout.contains("Restarted container db"));
+    assert!(mock
+        .requests()
+        .contains(&"POST /containers/db/restart".to_string()));
+}
+
+#[test]
+fn test_docker_dry_run_sends_nothing() {
+    let mock = MockDocker::start(containers());
+    let (stdout, _, success) = portr(&mock, &["docker", "stop", "8080", "--dry-run"]);
+    assert!(success);
+    assert!(stdout.contains("Would stop Docker container: web"));
+    assert!(!mock.requests().iter().any(|r| r.starts_with("POST")));
+}
+
+#[test]
+fn test_docker_unknown_target() {
+    let mock = MockDocker::start(containers());
+    let (_, stderr, success) = portr(&mock, &["docker", "stop", "nope", "-f"]);
+    assert!(!success);
+    assert!(stderr.contains("No running container matches 'nope'"));
+}
+
+#[test]
+fn test_docker_pause_and_rm() {
+    let mock = MockDocker::start(containers());
+
+    let (stdout, _, success) = portr(&mock, &["docker", "pause", "web", "-f"]);
+    assert!(success);
+    assert!(stdout.contains("Paused container web"));
+
+    let (stdout, _, success) = portr(&mock, &["docker", "rm", "8080", "-f"]);
+    assert!(success);
+    assert!(stdout.contains("Removed container web"));
+
+    let requests = mock.requests();
+    assert!(requests.contains(&"POST /containers/web/pause".to_string()));
+    assert!(requests.contains(&"DELETE /containers/web".to_string()));
+    assert!(mock
+        .requests_with_query()
+        .iter()
+        .any(|r| r.starts_with("DELETE /containers/web?") && r.contains("force=true")));
+}
+
+#[test]
+fn test_kill_with_docker_action_and_timeout() {
+    let mock = MockDocker::start(containers());
+    let (stdout, stderr, success) = portr(
+        &mock,
+        &[
+            "kill",
+            "8080",
+            "-f",
+            "--docker-action",
+            "restart",
+            "--stop-timeout",
+            "3",
+        ],
+    );
+    assert!(success, "stderr: {}", stderr);
+    assert!(stdout.contains("Restarted container web on port 8080"));
+    assert!(mock
+        .requests_with_query()
+        .contains(&"POST /containers/web/restart?t=3".to_string()));
+}
+
+#[test]
+fn test_critical_container_requires_typed_confirmation() {
+    let mock = MockDocker::start(containers());
+    // No stdin: the "yes" prompt reads EOF and cancels
+    let (stdout, _, success) = portr(&mock, &["docker", "pause", "db"]);
+    assert!(success);
+    assert!(stdout.contains("CRITICAL DATABASE"));
+    assert!(stdout.contains("Type 'yes' to pause"));
+    assert!(!mock.requests().iter().any(|r| r.starts_with("POST")));
+}
+
+#[test]
+fn test_endpoint_from_docker_context() {
+    let mock = MockDocker::start(containers());
+
+    // A docker CLI config selecting a context that points at the mock
+    let config = std::env::temp_dir().join(format!("portr-docker-config-{}", std::process::id()));
+    let meta = config.join("contexts/meta/5f3e1d");
+    std::fs::create_dir_all(&meta).unwrap();
+    std::fs::write(config.join("config.json"), r#"{"currentContext": "mock"}"#).unwrap();
+    std::fs::write(
+        meta.join("meta.json"),
+        json!({"Name": "mock", "Endpoints": {"docker": {"Host": mock.docker_host()}}}).to_string(),
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_portr"))
+        .args(["docker", "ls", "-v"])
+        .env_remove("DOCKER_HOST")
+        .env_remove("DOCKER_CONTEXT")
+        .env("DOCKER_CONFIG", &config)
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("Failed to execute portr");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let _ = std::fs::remove_dir_all(&config);
+
+    assert!(output.status.success());
+    assert!(stdout.contains(&format!("Docker endpoint: {}", mock.docker_host())));
+    assert!(stdout.contains("(context 'mock')"));
+    assert!(stdout.contains("web"));
+}
+
+fn compose_stack() -> serde_json::Value {
+    let container = |id: &str, project: &str, service: &str, port: Option<u16>| {
+        json!({
+            "Id": id,
+            "Names": [format!("/{}-{}-1", project, service)],
+            "Image": format!("{}-{}", project, service),
+            "State": "running",
+            "Status": "Up 1 minute",
+            "Labels": {
+                "com.docker.compose.project": project,
+                "com.docker.compose.service": service
+            },
+            "Ports": port.map(|p| vec![
+                json!({"IP": "127.0.0.1", "PrivatePort": p, "PublicPort": p, "Type": "tcp"})
+            ]).unwrap_or_default()
+        })
+    };
+
+    json!([
+        container("aaaaaaaaaaaa0000", "shop", "web", Some(8080)),
+        container("bbbbbbbbbbbb0000", "shop", "api", Some(8081)),
+        container("cccccccccccc0000", "shop", "worker", None),
+        container("dddddddddddd0000", "blog", "api", Some(9000)),
+    ])
+}
+
+#[test]
+fn test_docker_ls_groups_compose_services() {
+    let mock = MockDocker::start(compose_stack());
+    let (stdout, stderr, success) = portr(&mock, &["docker", "ls"]);
+    assert!(success, "stderr: {}", stderr);
+
+    // Grouped by project, then service
+    let order: Vec<usize> = ["blog/api", "shop/api", "shop/web", "shop/worker"]
+        .iter()
+        .map(|s| stdout.find(s).unwrap_or_else(|| panic!("missing {}", s)))
+        .collect();
+    assert!(order.windows(2).all(|w| w[0] < w[1]));
+
+    let (stdout, _, _) = portr(&mock, &["docker", "ls", "--json"]);
+    let rows: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(rows[0]["compose_project"], "blog");
+    assert_eq!(rows[0]["compose_service"], "api");
+}
+
+#[test]
+fn test_kill_compose_project() {
+    let mock = MockDocker::start(compose_stack());
+    let (stdout, stderr, success) = portr(&mock, &["kill", "--compose-project", "shop", "-f"]);
+    assert!(success, "stderr: {}", stderr);
+    assert!(stdout.contains("Stopped shop/web"));
+    assert!(stdout.contains("Stopped shop/api"));
+
+    let posts: Vec<String> = mock
+        .requests()
+        .into_iter()
+        .filter(|r| r.starts_with("POST"))
+        .collect();
+    assert_eq!(
+        posts,
+        vec![
+            "POST /containers/shop-api-1/stop",
+            "POST /containers/shop-web-1/stop"
+        ]
+    );
+}
+
+#[test]
+fn test_kill_compose_service_dry_run() {
+    let mock = MockDocker::start(compose_stack());
+    let (stdout, _, success) = portr(
+        &mock,
+        &[
+            "kill",
+            "--compose-service",
+            "api",
+            "--compose-project",
+            "blog",
+            "-n",
+        ],
+    );
+    assert!(success);
+    assert!(stdout.contains("blog/api"));
+    assert!(!stdout.contains("shop/api"));
+    assert!(stdout.contains("Would stop 1 container(s)"));
+    assert!(!mock.requests().iter().any(|r| r.starts_with("POST")));
+}
+
+#[test]
+fn test_list_shows_ports_published_without_a_proxy() {
+    // With "userland-proxy": false nothing on the host listens on 64080
+    let mock = MockDocker::start(json!([{
+        "Id": "0123456789abcdef0123",
+        "Names": ["/web"],
+        "Image": "nginx:latest",
+        "State": "running",
+        "Status": "Up 2 hours",
+        "Ports": [
+            {"IP": "0.0.0.0", "PrivatePort": 80, "PublicPort": 64080, "Type": "tcp"},
+            {"IP": "::", "PrivatePort": 80, "PublicPort": 64080, "Type": "tcp"}
+        ]
+    }]));
+    let (stdout, stderr, success) = portr(&mock, &["list", "--json"]);
+    assert!(success, "stderr: {}", stderr);
+
+    let ports: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let published: Vec<_> = ports
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|p| p["port"] == 64080)
+        .collect();
+    assert_eq!(published.len(), 1);
+    assert_eq!(published[0]["container"]["name"], "web");
+    assert_eq!(published[0]["pid"], 0);
+}
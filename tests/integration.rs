@@ -52,6 +52,62 @@ fn test_invalid_port() {
     assert!(stderr.contains("invalid port"));
 }
 
+#[test]
+fn test_invalid_where_query() {
+    let (_, stderr, success) = portr(&["--where", "mem>lots"]);
+    assert!(!success);
+    assert!(stderr.contains("invalid number 'lots' for 'mem'"));
+
+    let (_, stderr, success) = portr(&["list", "--where", "colour:red"]);
+    assert!(!success);
+    assert!(stderr.contains("unknown field 'colour'"));
+}
+
+#[test]
+fn test_invalid_columns_and_sort() {
+    let (_, stderr, success) = portr(&["--columns", "port,colour"]);
+    assert!(!success);
+    assert!(stderr.contains("invalid column: 'colour'"));
+
+    let (_, stderr, success) = portr(&["list", "--sort", "mem:up"]);
+    assert!(!success);
+    assert!(stderr.contains("unknown direction 'up'"));
+}
+
+#[test]
+fn test_unused_options_are_rejected() {
+    let (_, stderr, success) = portr(&["kill", "65434", "--json", "--columns", "port"]);
+    assert!(!success);
+    assert!(stderr.contains("unexpected argument '--json'"));
+
+    let (_, stderr, success) = portr(&["--json", "kill", "65434"]);
+    assert!(!success);
+    assert!(stderr.contains("--json doesn't apply to `portr kill`"));
+
+    let (_, stderr, success) = portr(&["config", "path", "--verbose"]);
+    assert!(!success);
+    assert!(stderr.contains("unexpected argument '--verbose'"));
+}
+
+#[test]
+fn test_options_before_the_subcommand() {
+    // Older releases took output options at the top level
+    let (stdout, _, success) = portr(&["--json", "list"]);
+    assert!(success);
+    assert!(stdout.trim_start().starts_with('['));
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind a test port");
+    let port = listener.local_addr().unwrap().port().to_string();
+    let (stdout, _, success) = portr(&["--json", "find", &port]);
+    assert!(success);
+    let info: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert_eq!(info["pid"], std::process::id());
+
+    let (stdout, _, success) = portr(&["-v", "find", &port]);
+    assert!(success);
+    assert!(stdout.contains(&port));
+}
+
 #[test]
 fn test_port_not_in_use() {
     // Port 65432 is unlikely to be in use
@@ -97,3 +153,104 @@ fn test_udp_filter() {
     // May fail on CI if ss/netstat not available
     assert!(success || stderr.contains("error") || stdout.contains("port") || stdout.is_empty());
 }
+
+#[test]
+fn test_history_filters_by_port_and_since() {
+    let data_dir = std::env::temp_dir().join(format!("portr-data-{}", std::process::id()));
+    std::fs::create_dir_all(&data_dir).unwrap();
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let event = |ago: u64, event: &str, port: u16, pid: u32| {
+        format!(
+            r#"{{"timestamp":{},"event":"{}","port":{},"protocol":"TCP","pid":{},"process":"node","command":"node server.js"}}"#,
+            now - ago,
+            event,
+            port,
+            pid
+        )
+    };
+    let log = [
+        event(5 * 3600, "bind", 8080, 10),
+        event(4 * 3600, "unbind", 8080, 10),
+        event(600, "bind", 8080, 11),
+        event(300, "bind", 3000, 12),
+    ]
+    .join("\n");
+    std::fs::write(data_dir.join("history.jsonl"), log + "\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_portr"))
+        .args(["history", "8080", "--since", "2h", "--json"])
+        .env("PORTR_DATA_DIR", &data_dir)
+        .output()
+        .expect("Failed to execute portr");
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+
+    let pretty = Command::new(env!("CARGO_BIN_EXE_portr"))
+        .args(["history", "8080"])
+        .env("PORTR_DATA_DIR", &data_dir)
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("Failed to execute portr");
+    let pretty = String::from_utf8_lossy(&pretty.stdout).to_string();
+    let _ = std::fs::remove_dir_all(&data_dir);
+
+    assert!(output.status.success());
+    let events: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let events = events.as_array().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["pid"], 11);
+    assert_eq!(events[0]["event"], "bind");
+
+    assert!(pretty.contains("3 event(s)"));
+    assert!(pretty.contains("bound 2 times"));
+    assert!(pretty.contains("node server.js"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_daemon_serves_snapshot() {
+    let data_dir = std::env::temp_dir().join(format!("portr-daemon-it-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&data_dir);
+
+    let mut daemon = Command::new(env!("CARGO_BIN_EXE_portr"))
+        .args(["daemon", "run", "--interval", "1"])
+        .env("PORTR_DATA_DIR", &data_dir)
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .expect("Failed to start daemon");
+
+    let status = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_portr"))
+            .args(args)
+            .env("PORTR_DATA_DIR", &data_dir)
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("Failed to execute portr");
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    // Wait for the first scan to be served
+    let mut json = String::new();
+    for _ in 0..50 {
+        json = status(&["daemon", "status", "--json"]);
+        if json.contains("\"pid\"") {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    let running = status(&["daemon", "status"]);
+
+    let _ = daemon.kill();
+    let _ = daemon.wait();
+    let stopped = status(&["daemon", "status"]);
+    let _ = std::fs::remove_dir_all(&data_dir);
+
+    let snapshot: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
+    assert_eq!(snapshot["pid"], daemon.id());
+    assert!(snapshot["ports"].is_array());
+    assert!(running.contains("Daemon running"));
+    assert!(stopped.contains("No daemon running"));
+}